@@ -75,6 +75,11 @@ pub struct TwoAdicCoset<F: TwoAdicField> {
     generator: F,
     shift: F,
     log_size: usize,
+    // The odd exponent e < 2^log_size such that g = w^e, where w is the
+    // canonical generator of the subgroup (i. e. F::two_adic_generator). It is
+    // used to reorder the outputs of the FFTs, which are computed with respect
+    // to w.
+    generator_exponent: usize,
     // The i-th element, if present, is generator^(2^i). The vector starts off as
     // vec![generator] and is expanded every time a higher iterated square is
     // computed.
@@ -121,6 +126,68 @@ impl<F: TwoAdicField> TwoAdicCoset<F> {
             generator,
             shift,
             log_size,
+            generator_exponent: 1,
+            generator_iter_squares: vec![generator],
+            dft: None,
+        }
+    }
+
+    /// Returns the coset `shift * <generator>`, where `generator` is any
+    /// generator of the unique subgroup of the units of `F` of order `2 ^
+    /// log_size` (as opposed to the canonical one used by [`Self::new`]). The
+    /// elements of the coset are ordered as `shift * generator^0, shift *
+    /// generator^1, ...`, which is also the order of the evaluations handled
+    /// by [`Self::evaluate_polynomial`] and [`Self::interpolate`].
+    ///
+    /// # Arguments
+    ///
+    ///  - `shift`: the value by which the subgroup is (multiplicatively)
+    ///    shifted
+    ///  - `generator`: the generator of the subgroup
+    ///  - `log_size`: the size of the subgroup (and hence of the coset) is
+    ///    `2 ^ log_size`.
+    ///
+    /// # Panics
+    ///
+    ///  - If `generator` does not have multiplicative order exactly `2 ^
+    ///    log_size`
+    ///  - If `log_size` is greater than `usize::BITS` or than the two-adicity
+    ///    of `F`
+    pub fn new_with_generator(shift: F, generator: F, log_size: usize) -> Self {
+        assert!(
+            log_size <= usize::BITS as usize && log_size <= F::TWO_ADICITY,
+            "log_size must be <= the number of bits in usize ({}) and the two-adicity of the field ({})",
+            usize::BITS,
+            F::TWO_ADICITY
+        );
+
+        let has_order = |log_order: usize| generator.exp_power_of_2(log_order) == F::ONE;
+        assert!(
+            has_order(log_size) && (log_size == 0 || !has_order(log_size - 1)),
+            "The generator must have multiplicative order 2^{log_size}"
+        );
+
+        // Finding the exponent e such that generator = w^e bit by bit (a
+        // Pohlig-Hellman discrete logarithm in a group of order 2^log_size):
+        // assuming the k lower bits of e are known, the element
+        // generator * w^(-e_low) = w^(e - e_low) has order at most
+        // 2^(log_size - k), with equality if and only if the k-th bit of e is
+        // set.
+        let canonical_inverse = F::two_adic_generator(log_size).inverse();
+        let mut generator_exponent = 0;
+
+        for k in 0..log_size {
+            let remainder = generator * canonical_inverse.exp_u64(generator_exponent as u64);
+            if remainder.exp_power_of_2(log_size - 1 - k) != F::ONE {
+                generator_exponent |= 1 << k;
+            }
+        }
+
+        Self {
+            generator,
+            shift,
+            log_size,
+            generator_exponent,
             generator_iter_squares: vec![generator],
             dft: None,
         }
@@ -131,6 +198,15 @@ impl<F: TwoAdicField> TwoAdicCoset<F> {
         self.generator
     }
 
+    /// Returns the exponent `e` such that the generator of the coset is equal
+    /// to `w^e`, where `w = F::two_adic_generator(log_size)` is the canonical
+    /// one. This is `1` for cosets constructed with [`Self::new`] and an odd
+    /// number less than the size of the coset otherwise (except for cosets of
+    /// size one, where it is `0`).
+    pub fn generator_exponent(&self) -> usize {
+        self.generator_exponent
+    }
+
     /// Returns the shift of the coset.
     pub fn shift(&self) -> F {
         self.shift
@@ -173,10 +249,20 @@ impl<F: TwoAdicField> TwoAdicCoset<F> {
             }
         };
 
+        // Since w^(2^log_scale_factor) is the canonical generator of the
+        // smaller subgroup, the exponent is preserved (modulo the new size)
+        let log_size = self.log_size - log_scale_factor;
+        let generator_exponent = if self.generator_exponent == 1 {
+            1
+        } else {
+            self.generator_exponent & ((1 << log_size) - 1)
+        };
+
         TwoAdicCoset {
             generator,
             shift: self.shift,
-            log_size: self.log_size - log_scale_factor,
+            log_size,
+            generator_exponent,
             generator_iter_squares,
             dft: None,
         }
@@ -190,6 +276,20 @@ impl<F: TwoAdicField> TwoAdicCoset<F> {
         new_coset.set_shift(self.shift.exp_power_of_2(log_scale_factor))
     }
 
+    /// Returns the image of the coset under the map `x -> x^(2^log_folding_factor)`,
+    /// i. e. the domain over which the `2^log_folding_factor`-ary folding of a
+    /// polynomial is evaluated when the original one is evaluated over `self`.
+    /// The `j`-th element of the returned coset is the `2^log_folding_factor`-th
+    /// power of the `j`-th element of `self` (as well as of each element whose
+    /// index is congruent to `j` modulo the size of the returned coset).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `2^log_folding_factor` is larger than the size of the coset.
+    pub fn fold(&self, log_folding_factor: usize) -> TwoAdicCoset<F> {
+        self.shrink_coset(log_folding_factor)
+    }
+
     /// Returns a new coset where the shift has been set to `shift` times the
     /// original shift.
     pub fn shift_by(&self, shift: F) -> TwoAdicCoset<F> {
@@ -251,6 +351,18 @@ impl<F: TwoAdicField> TwoAdicCoset<F> {
             "The number of evaluations must be equal to the size of the coset."
         );
 
+        // The DFT machinery uses the canonical generator w, so the evaluations
+        // at shift * g^i = shift * w^(e * i) are moved to position e * i
+        let evals = if self.generator_exponent == 1 {
+            evals
+        } else {
+            let mut canonical_evals = vec![F::ZERO; size];
+            for (i, eval) in evals.into_iter().enumerate() {
+                canonical_evals[(self.generator_exponent * i) & (size - 1)] = eval;
+            }
+            canonical_evals
+        };
+
         let dft = self.dft.get_or_insert_with(Radix2Dit::default);
         dft.coset_idft(evals, self.shift)
     }
@@ -335,19 +447,30 @@ impl<F: TwoAdicField> TwoAdicCoset<F> {
         let mut coeffs = poly_coeffs;
         coeffs.resize(size, F::ZERO);
 
-        if self.shift == self.generator {
+        let evals = if self.shift == self.generator {
             // In this case it is more efficient to use a plain FFT without
             // shift, and then (cyclically) rotate the resulting evaluations by
-            // one position.
+            // e positions (i. e. one position for the canonical generator).
 
             // Note that this case is not unusual and is, e. g.
             // used in this repository's implementation of STIR
             let mut evals = dft.dft(coeffs);
-            evals.rotate_left(1);
+            evals.rotate_left(self.generator_exponent);
             evals
         } else {
             dft.coset_dft(coeffs, self.shift)
+        };
+
+        if self.generator_exponent == 1 {
+            return evals;
         }
+
+        // The DFT machinery uses the canonical generator w, so the evaluation
+        // at shift * g^i = shift * w^(e * i) is found at position e * i (after
+        // the rotation above, if applicable)
+        (0..size)
+            .map(|i| evals[(self.generator_exponent * i) & (size - 1)])
+            .collect()
     }
 
     // Internal function which computes `generator^exp`. It uses the previously
@@ -115,6 +115,28 @@ fn test_shrink_shift() {
     assert_eq!(shrunk.shift, shift.exp_power_of_2(2));
 }
 
+#[test]
+// Checks that the elements of the folded coset are the 2^k-th powers of those
+// of the original coset, each of them being attained by exactly one fold group
+fn test_fold() {
+    let mut rng = rand::rng();
+    let shift: GL = rng.random();
+
+    let log_size = 8;
+    let log_folding_factor = 3;
+
+    let mut coset = TwoAdicCoset::<GL>::new(shift, log_size);
+    let mut folded = coset.fold(log_folding_factor);
+
+    assert_eq!(folded.log_size(), log_size - log_folding_factor);
+
+    for i in 0..coset.size() {
+        let power = coset.element(i).exp_power_of_2(log_folding_factor);
+        assert!(folded.contains(power));
+        assert_eq!(folded.element(i % folded.size()), power);
+    }
+}
+
 #[test]
 // Checks that shrinking the coset by a factor of k results in a new coset whose
 // i-th element is the original coset's (i * k)-th element
@@ -260,3 +282,52 @@ fn test_contains() {
         d *= coset.generator();
     }
 }
+
+#[test]
+// Checks that a coset with a non-canonical generator recovers the exponent of
+// the generator, orders its elements accordingly and evaluates and
+// interpolates in that order, both with an arbitrary shift and with the shift
+// equal to the generator
+fn test_new_with_generator() {
+    let mut rng = rand::rng();
+    let log_size = 5;
+    let exponent = 13;
+    let generator = BB::two_adic_generator(log_size).exp_u64(exponent as u64);
+
+    let coeffs: Vec<BB> = (0..1 << log_size).map(|_| rng.random()).collect();
+
+    for shift in [rng.random(), generator] {
+        let mut coset = TwoAdicCoset::new_with_generator(shift, generator, log_size);
+        assert_eq!(coset.generator_exponent(), exponent);
+        assert_eq!(coset.element(1), shift * generator);
+
+        let evals = coset
+            .iter()
+            .map(|x| {
+                coeffs
+                    .iter()
+                    .rfold(BB::ZERO, |result, coeff| result * x + *coeff)
+            })
+            .collect_vec();
+
+        assert_eq!(coset.evaluate_polynomial(coeffs.clone()), evals);
+        assert_eq!(coset.interpolate(evals), coeffs);
+
+        let shrunk_coset = coset.shrink_subgroup(2);
+        assert_eq!(shrunk_coset.generator_exponent(), exponent % (1 << 3));
+        assert_eq!(shrunk_coset.generator(), generator.exp_power_of_2(2));
+    }
+
+    // The canonical generator is recognised as such
+    let coset =
+        TwoAdicCoset::new_with_generator(BB::ONE, BB::two_adic_generator(log_size), log_size);
+    assert_eq!(coset.generator_exponent(), 1);
+}
+
+#[test]
+#[should_panic = "The generator must have multiplicative order 2^5"]
+// Checks that constructing a coset with a generator of the wrong order panics
+fn test_new_with_generator_wrong_order() {
+    let generator = BB::two_adic_generator(4);
+    TwoAdicCoset::new_with_generator(BB::ONE, generator, 5);
+}
@@ -13,14 +13,18 @@ extern crate alloc;
 use alloc::vec;
 use alloc::vec::Vec;
 use core::clone::Clone;
-use core::iter::Product;
-use core::ops::{Add, AddAssign, Div, Mul, Neg, Sub};
+use core::iter::{self, Product};
+use core::ops::{Add, AddAssign, Div, Index, IndexMut, Mul, Neg, Sub};
 
 use itertools::{iterate, Itertools};
 use p3_dft::{Radix2Dit, TwoAdicSubgroupDft};
-use p3_field::{Field, TwoAdicField};
+use p3_field::{
+    batch_multiplicative_inverse, BasedVectorSpace, ExtensionField, Field, PackedFieldExtension,
+    PackedValue, PrimeCharacteristicRing, PrimeField64, TwoAdicField,
+};
 use p3_matrix::dense::RowMajorMatrix;
 use p3_matrix::Matrix;
+use p3_maybe_rayon::prelude::*;
 use serde::{Deserialize, Serialize};
 
 #[cfg(test)]
@@ -119,6 +123,62 @@ pub struct Polynomial<F: Field> {
     coeffs: Vec<F>,
 }
 
+/// Error returned by [`Polynomial::checked_mul`] when the product would exceed
+/// the maximum degree allowed.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct DegreeExceeded {
+    /// Degree of the product
+    pub degree: usize,
+    /// Maximum degree allowed
+    pub max_degree: usize,
+}
+
+/// Barycentric form of the polynomial interpolating some values `y_i` at
+/// distinct points `x_i`, as returned by
+/// [`Polynomial::interpolate_with_barycentric`]. It stores the weights
+/// `w_i = 1 / prod_{j != i} (x_i - x_j)` and evaluates the polynomial at `x` as
+/// `(sum_i w_i y_i / (x - x_i)) / (sum_i w_i / (x - x_i))`.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct BarycentricEvaluator<F: Field> {
+    // The distinct interpolation points x_i
+    points: Vec<F>,
+    // The values y_i at those points
+    evals: Vec<F>,
+    // The barycentric weight w_i of each point
+    weights: Vec<F>,
+}
+
+impl<F: Field> BarycentricEvaluator<F> {
+    /// Returns the evaluation at `point` of the interpolating polynomial, using
+    /// a linear number of multiplications and a single inversion.
+    pub fn evaluate(&self, point: &F) -> F {
+        let differences = self.points.iter().map(|&x| *point - x).collect_vec();
+
+        // The formula is undefined at the interpolation points themselves
+        if let Some(i) = differences.iter().position(|d| d.is_zero()) {
+            return self.evals[i];
+        }
+
+        let (numerator, denominator) = batch_multiplicative_inverse(&differences)
+            .into_iter()
+            .zip(self.weights.iter().zip(&self.evals))
+            .fold(
+                (F::ZERO, F::ZERO),
+                |(numerator, denominator), (inverse, (&weight, &eval))| {
+                    let term = weight * inverse;
+                    (numerator + term * eval, denominator + term)
+                },
+            );
+
+        numerator / denominator
+    }
+
+    /// Returns the distinct interpolation points.
+    pub fn points(&self) -> &[F] {
+        &self.points
+    }
+}
+
 impl<F: Field> Polynomial<F> {
     /// Returns the coefficients of the polynomial in increasing-degree order
     /// with no leading zeros
@@ -127,6 +187,88 @@ impl<F: Field> Polynomial<F> {
         &self.coeffs
     }
 
+    /// Returns an iterator over the coefficients of the polynomial in
+    /// increasing-degree order with no leading zeros (cf.
+    /// [`Polynomial::coeffs`])
+    pub fn iter(&self) -> impl Iterator<Item = &F> {
+        self.coeffs.iter()
+    }
+
+    /// Returns mutable access to the coefficients of the polynomial (in
+    /// increasing-degree order), e. g. to modify or extend them in place
+    /// without copying.
+    ///
+    /// Mutation can break the internal invariant that there are no leading
+    /// zeros, which other methods (such as [`Polynomial::degree`]) rely on.
+    /// Callers must therefore call [`Polynomial::renormalize`] once they are
+    /// done mutating the coefficients and before using the polynomial in any
+    /// other way.
+    pub fn coeffs_mut(&mut self) -> &mut Vec<F> {
+        &mut self.coeffs
+    }
+
+    /// Trims the leading zeros of the coefficient vector, restoring the
+    /// internal invariant after mutation through [`Polynomial::coeffs_mut`].
+    /// This is a no-op if there are no leading zeros.
+    pub fn renormalize(&mut self) {
+        *self = core::mem::take(self).truncate_leading_zeros();
+    }
+
+    /// Returns a cheap 64-bit fingerprint of the polynomial, e. g. for use as
+    /// a caching or de-duplication key. It is computed over the coefficients
+    /// up to the last non-zero one, so it only depends on the polynomial as a
+    /// function even if leading zeros were left in place by
+    /// [`Polynomial::coeffs_mut`] or [`Polynomial::with_len`]. It is stable
+    /// across runs and platforms and independent of the internal
+    /// representation of field elements: it hashes the number of coefficients
+    /// followed by the canonical values of their coordinates over the prime
+    /// subfield.
+    ///
+    /// The fingerprint is computed with a fast, non-cryptographic hash (cf.
+    /// [`fnv1a`]) and is not binding: distinct polynomials collide with
+    /// probability about `2^-64` if chosen independently of it, but
+    /// collisions can easily be found on purpose. It must not be used where an
+    /// adversary chooses the polynomials.
+    pub fn fingerprint(&self) -> u64
+    where
+        F: BasedVectorSpace<F::PrimeSubfield>,
+        F::PrimeSubfield: PrimeField64,
+    {
+        let len = self
+            .coeffs
+            .iter()
+            .rposition(|c| !c.is_zero())
+            .map_or(0, |i| i + 1);
+
+        let values = self.coeffs[..len].iter().flat_map(|c| {
+            <F as BasedVectorSpace<F::PrimeSubfield>>::as_basis_coefficients_slice(c)
+                .iter()
+                .map(PrimeField64::as_canonical_u64)
+        });
+
+        fnv1a(iter::once(len as u64).chain(values))
+    }
+
+    /// Returns a copy of `self` with exactly `len` coefficients (in
+    /// increasing-degree order), obtained by padding with zeros or truncating
+    /// the higher-degree coefficients as needed. This is useful for preparing
+    /// the input of an FFT or serializing into a fixed layout.
+    ///
+    /// The output is in raw-coefficient form: as after mutation through
+    /// [`Polynomial::coeffs_mut`], [`Polynomial::coeffs`] returns all `len`
+    /// coefficients, including any leading zeros. Hence, the internal
+    /// invariant that there are no leading zeros does not hold in general and
+    /// [`Polynomial::renormalize`] must be called before using the output in
+    /// any other way. After renormalization, the output is `self` if
+    /// `len` is at least the number of coefficients of `self` and `self mod
+    /// x^len` otherwise.
+    pub fn with_len(&self, len: usize) -> Polynomial<F> {
+        let mut coeffs = self.coeffs[..len.min(self.coeffs.len())].to_vec();
+        coeffs.resize(len, F::ZERO);
+
+        Self { coeffs }
+    }
+
     /// Returns the leading coefficient of the polynomial
     pub fn leading_coeff(&self) -> F {
         *self.coeffs.last().unwrap_or(&F::ZERO)
@@ -143,7 +285,17 @@ impl<F: Field> Polynomial<F> {
     }
 
     /// Returns the polynomial with the given coefficients. Leading zeros are automatically trimmed.
+    /// This is the same as [`Polynomial::from_coeffs_normalized`].
     pub fn from_coeffs(coeffs: Vec<F>) -> Self {
+        Self::from_coeffs_normalized(coeffs)
+    }
+
+    /// Returns the polynomial with the given coefficients (in increasing-degree
+    /// order) after truncating its leading zeros, i. e. the trailing zeros of
+    /// `coeffs`. All constructors go through this normalization (except for the
+    /// explicitly raw [`Polynomial::with_len`]), so that equal polynomials have
+    /// equal coefficient vectors and `Eq` and `Hash` are consistent.
+    pub fn from_coeffs_normalized(coeffs: Vec<F>) -> Self {
         Self { coeffs }.truncate_leading_zeros()
     }
 
@@ -152,11 +304,10 @@ impl<F: Field> Polynomial<F> {
         *self.coeffs.first().unwrap_or(&F::ZERO)
     }
 
-    /// Returns the constant polynomial with the given constant term
+    /// Returns the constant polynomial with the given constant term (which is
+    /// the zero polynomial if `constant` is zero)
     pub fn constant(constant: F) -> Self {
-        Self {
-            coeffs: vec![constant],
-        }
+        Self::from_coeffs_normalized(vec![constant])
     }
 
     /// Returns the unique monic polynomial of degree 1 with no constant term
@@ -195,13 +346,123 @@ impl<F: Field> Polynomial<F> {
         self
     }
 
-    /// Evaluates `self` at the given `point` using Horner's method
+    /// Evaluates `self` at the given `point` using Horner's method (or simply
+    /// returns the constant term if `point` is zero)
     pub fn evaluate(&self, point: &F) -> F {
+        if point.is_zero() {
+            return self.constant_term();
+        }
+
         self.coeffs
             .iter()
             .rfold(F::ZERO, move |result, coeff| result * *point + *coeff)
     }
 
+    /// Returns the formal derivative of `self`.
+    pub fn derivative(&self) -> Polynomial<F> {
+        Polynomial::from_coeffs(
+            self.coeffs
+                .iter()
+                .enumerate()
+                .skip(1)
+                .map(|(i, &coeff)| coeff * F::from_usize(i))
+                .collect(),
+        )
+    }
+
+    /// Evaluates the formal derivative of `self` at the given `point` without
+    /// computing the derivative itself. This uses a modified Horner's method
+    /// which updates the evaluations of `self` and of its derivative together:
+    /// if `q` is the polynomial given by the leading coefficients processed so
+    /// far and `c` is the next one, the next such polynomial is `q * x + c`,
+    /// whose derivative is `q' * x + q`.
+    pub fn evaluate_derivative(&self, point: &F) -> F {
+        let (_, derivative_eval) =
+            self.coeffs
+                .iter()
+                .rfold((F::ZERO, F::ZERO), |(eval, derivative_eval), &coeff| {
+                    (eval * *point + coeff, derivative_eval * *point + eval)
+                });
+
+        derivative_eval
+    }
+
+    /// Evaluates each of the given polynomials at the same point in a single
+    /// pass over their coefficients, computing the powers of `point` only once
+    /// for all of them. The results are returned in the same order as the
+    /// polynomials.
+    pub fn evaluate_all(polys: &[&Polynomial<F>], point: F) -> Vec<F> {
+        let mut evaluations = vec![F::ZERO; polys.len()];
+
+        let max_len = polys.iter().map(|p| p.coeffs.len()).max().unwrap_or(0);
+        let mut power = F::ONE;
+
+        for i in 0..max_len {
+            for (evaluation, poly) in evaluations.iter_mut().zip(polys) {
+                if let Some(coeff) = poly.coeffs.get(i) {
+                    *evaluation += *coeff * power;
+                }
+            }
+
+            power *= point;
+        }
+
+        evaluations
+    }
+
+    /// Evaluates the polynomial at each of the given points, which are
+    /// processed in batches of `B::Packing::WIDTH` packed into a single
+    /// [`ExtensionField::ExtensionPacking`] element, so that each step of
+    /// Horner's rule operates on a full batch at once. The remaining points
+    /// (fewer than the packing width) are evaluated one by one. The results
+    /// coincide with those of [`Polynomial::evaluate`] and are returned in the
+    /// same order as the points.
+    pub fn evaluate_packed<B>(&self, points: &[F]) -> Vec<F>
+    where
+        B: Field,
+        F: ExtensionField<B>,
+    {
+        let width = B::Packing::WIDTH;
+        let (packed_points, remaining_points) =
+            points.split_at(points.len() - points.len() % width);
+
+        let mut evaluations = Vec::with_capacity(points.len());
+
+        for batch in packed_points.chunks_exact(width) {
+            let packed_point = F::ExtensionPacking::from_ext_slice(batch);
+
+            let packed_evaluation = self
+                .coeffs
+                .iter()
+                .rfold(F::ExtensionPacking::ZERO, |result, &coeff| {
+                    result * packed_point + coeff
+                });
+
+            // Unpacking the evaluations lane by lane
+            let coefficients = packed_evaluation.as_basis_coefficients_slice();
+            evaluations.extend(
+                (0..width).map(|lane| {
+                    F::from_basis_coefficients_fn(|i| coefficients[i].as_slice()[lane])
+                }),
+            );
+        }
+
+        evaluations.extend(remaining_points.iter().map(|point| self.evaluate(point)));
+        evaluations
+    }
+
+    /// Evaluates the polynomial at each of the given points, packing them into
+    /// `F::Packing` lanes. This is [`Polynomial::evaluate_packed`] with `F` as
+    /// its own base field, which is the natural choice for polynomials over a
+    /// field with a non-trivial packing (typically a prime field). Polynomials
+    /// over an extension field should rather call `evaluate_packed` with the
+    /// base field the extension packing is built on. The results coincide with
+    /// those of [`Polynomial::evaluate`] and are returned in the same order as
+    /// the points.
+    pub fn evaluate_many(&self, points: &[F]) -> Vec<F> {
+        self.evaluate_packed::<F>(points)
+    }
+
     /// Returns `None` if self is the zero polynomial and `Some(d)` if `self` is
     /// a (non-zero) polynomial of degree `d`
     pub fn degree(&self) -> Option<usize> {
@@ -212,6 +473,18 @@ impl<F: Field> Polynomial<F> {
         }
     }
 
+    /// Returns the degree of `self` as a function: `None` if all of its
+    /// coefficients are zero and the index of the last non-zero one otherwise.
+    /// This coincides with [`Polynomial::degree`] for normalised polynomials,
+    /// but does not rely on the absence of leading zeros, which is not
+    /// guaranteed for polynomials deserialised from untrusted input (or left
+    /// raw by [`Polynomial::coeffs_mut`] or [`Polynomial::with_len`]).
+    /// Degree bounds on polynomials received from an adversary (e. g. those in
+    /// a proof) should be enforced with this method.
+    pub fn degree_checked(&self) -> Option<usize> {
+        self.coeffs.iter().rposition(|c| !c.is_zero())
+    }
+
     /// Returns `true` if and only if `self` is the zero polynomial
     pub fn is_zero(&self) -> bool {
         self.coeffs.is_empty()
@@ -324,27 +597,39 @@ impl<F: Field> Polynomial<F> {
 
         // We iteratively multiply the polynomial (x - points[0]) by each of the
         // vanishing polynomials (x - points[i]) for i > 0
-        let mut coeffs = vec![-points.pop().unwrap(), F::ONE];
+        let mut polynomial = Polynomial::vanishing_linear_polynomial(points.pop().unwrap());
 
         while let Some(point) = points.pop() {
-            // Basic idea: add shifted and scaled versions of the current polynomial
-            // For instance, if f has coefficients
-            //   [2, -3, 4, 1],
-            // then (x - 5) * f has coefficients
-            //   [0, 2, -3, 4, 1] + (-5) * [2, -3, 4, 1, 0]
-
-            let mut prev_coeff = F::ZERO;
-
-            for coeff in coeffs.iter_mut() {
-                let current_coeff = *coeff;
-                *coeff = prev_coeff - *coeff * point;
-                prev_coeff = current_coeff;
-            }
+            polynomial = polynomial.mul_linear(point);
+        }
+
+        polynomial
+    }
 
-            coeffs.push(F::ONE);
+    /// Returns `self * (x - root)`, computed in a single linear pass over the
+    /// coefficients rather than through general (FFT-based) multiplication.
+    pub fn mul_linear(&self, root: F) -> Polynomial<F> {
+        if self.is_zero() {
+            return Self::zero();
         }
 
-        Polynomial::from_coeffs(coeffs)
+        // Basic idea: add shifted and scaled versions of the current polynomial
+        // For instance, if f has coefficients
+        //   [2, -3, 4, 1],
+        // then (x - 5) * f has coefficients
+        //   [0, 2, -3, 4, 1] + (-5) * [2, -3, 4, 1, 0]
+        let mut coeffs = Vec::with_capacity(self.coeffs.len() + 1);
+        let mut prev_coeff = F::ZERO;
+
+        for &coeff in &self.coeffs {
+            coeffs.push(prev_coeff - coeff * root);
+            prev_coeff = coeff;
+        }
+
+        coeffs.push(prev_coeff);
+
+        // The leading coefficient is that of self, hence non-zero
+        Self { coeffs }
     }
 
     /// Returns the unique polynomial of degree less than the number of
@@ -371,30 +656,37 @@ impl<F: Field> Polynomial<F> {
     /// - `point_to_evals` has two points (i. e. x coordinates) with different
     ///    requested evaluation (i. e. y coordinates)
     pub fn lagrange_interpolation(point_to_evals: Vec<(F, F)>) -> Polynomial<F> {
+        Self::interpolate_with_barycentric(point_to_evals).0
+    }
+
+    /// Returns the same interpolating polynomial as
+    /// [`Polynomial::lagrange_interpolation`] together with its barycentric
+    /// form, which evaluates it at any point in time linear in the number of
+    /// (distinct) pairs in `point_to_evals` instead of requiring Horner's
+    /// method on the coefficients. The barycentric weights are obtained from
+    /// the quantities computed during interpolation at the cost of a single
+    /// batch inversion.
+    ///
+    /// # Panics
+    ///
+    /// Same as [`Polynomial::lagrange_interpolation`].
+    pub fn interpolate_with_barycentric(
+        point_to_evals: Vec<(F, F)>,
+    ) -> (Polynomial<F>, BarycentricEvaluator<F>) {
         if point_to_evals.is_empty() {
             panic!("The Lagrange interpolation of an empty set is undefined");
         }
 
-        // Testing for consistency and removing duplicate points
-        let point_to_evals = point_to_evals.into_iter().unique().collect_vec();
+        let (points, evals): (Vec<F>, Vec<F>) = Self::remove_duplicate_points(point_to_evals)
+            .into_iter()
+            .unzip();
 
-        let points = point_to_evals
-            .iter()
-            .map(|(x, _)| *x)
-            .unique()
-            .collect_vec();
-
-        assert_eq!(
-            points.len(),
-            point_to_evals.len(),
-            "One point has two different requested evaluations"
-        );
-
-        let vanishing_poly = Self::vanishing_polynomial(points);
+        let vanishing_poly = Self::vanishing_polynomial(points.iter().copied());
 
         let mut result = Polynomial::zero();
+        let mut denominators = Vec::with_capacity(points.len());
 
-        for (point, eval) in point_to_evals.into_iter() {
+        for (&point, &eval) in points.iter().zip(&evals) {
             // We obtain the (non-normalised) vanishing polynomial at all points
             // other than point by removing the (x - point) factor from the full
             // vanishing polynomial
@@ -402,10 +694,30 @@ impl<F: Field> Polynomial<F> {
 
             // We normalise it so that it takes the value `eval` at `point`
             let denominator = polynomial.evaluate(&point);
-            result += &(&polynomial * &(eval / denominator));
+            result.add_scaled_assign(eval / denominator, &polynomial);
+            denominators.push(denominator);
         }
 
-        result
+        let evaluator = BarycentricEvaluator {
+            points,
+            evals,
+            weights: batch_multiplicative_inverse(&denominators),
+        };
+
+        (result, evaluator)
+    }
+
+    // Internal method which removes the repeated pairs from point_to_evals,
+    // panicking if two of the remaining ones share their point
+    fn remove_duplicate_points(point_to_evals: Vec<(F, F)>) -> Vec<(F, F)> {
+        let point_to_evals = point_to_evals.into_iter().unique().collect_vec();
+
+        assert!(
+            point_to_evals.iter().map(|(x, _)| x).all_unique(),
+            "One point has two different requested evaluations"
+        );
+
+        point_to_evals
     }
 
     /// Returns the composition of `self` with the polynomial `x^exponent`. In
@@ -428,6 +740,77 @@ impl<F: Field> Polynomial<F> {
         Polynomial::from_coeffs(coeffs)
     }
 
+    /// Returns the product of `self` and `x^k`, i. e. `self` with its
+    /// coefficients shifted up by `k` positions.
+    pub fn shift_up(&self, k: usize) -> Polynomial<F> {
+        if self.is_zero() {
+            return Polynomial::zero();
+        }
+
+        let mut coeffs = vec![F::ZERO; k];
+        coeffs.extend_from_slice(&self.coeffs);
+
+        Self { coeffs }
+    }
+
+    /// Splits `self` at degree `k`, returning the unique pair `(low, high)`
+    /// such that `self = low + x^k * high` and `low` is zero or has degree
+    /// less than `k`. In other words, `low` and `high` are the remainder and
+    /// quotient of the division of `self` by `x^k`. If `k` exceeds the degree
+    /// of `self`, `high` is zero.
+    pub fn split_at(&self, k: usize) -> (Polynomial<F>, Polynomial<F>) {
+        let (low, high) = self.coeffs.split_at(k.min(self.coeffs.len()));
+
+        (
+            Polynomial::from_coeffs(low.to_vec()),
+            Self {
+                coeffs: high.to_vec(),
+            },
+        )
+    }
+
+    /// Splits `self` into its `k = 2^log_k` strided sub-polynomials, i. e. the
+    /// unique `f_0, ..., f_(k - 1)` such that
+    /// `self(x) = f_0(x^k) + x * f_1(x^k) + ... + x^(k - 1) * f_(k - 1)(x^k)`.
+    /// The `j`-th coefficient of `f_i` is the `(i + j * k)`-th coefficient of
+    /// `self`. This is the decomposition underlying FRI-style folding, which
+    /// replaces each `x^i` by a power of the folding randomness.
+    pub fn split_radix(&self, log_k: usize) -> Vec<Polynomial<F>> {
+        let k = 1 << log_k;
+
+        (0..k)
+            .map(|i| self.coeffs.iter().skip(i).step_by(k).copied().collect())
+            .collect()
+    }
+
+    /// Splits `self` into its even and odd parts, i. e. the unique pair
+    /// `(f_even, f_odd)` such that `self(x) = f_even(x^2) + x * f_odd(x^2)`.
+    /// This is [`Polynomial::split_radix`] with `log_k = 1`.
+    pub fn split_even_odd(&self) -> (Polynomial<F>, Polynomial<F>) {
+        let mut parts = self.split_radix(1);
+        let odd = parts.pop().unwrap();
+        let even = parts.pop().unwrap();
+
+        (even, odd)
+    }
+
+    /// Adds `scale * term` to `self` in place. This is equivalent to
+    /// `self += &(term * &scale)` but does not allocate the intermediate
+    /// polynomial (nor any memory at all unless `term` has more coefficients
+    /// than `self`), which makes it suitable for accumulation loops.
+    pub fn add_scaled_assign(&mut self, scale: F, term: &Polynomial<F>) {
+        if self.coeffs.len() < term.coeffs.len() {
+            self.coeffs.resize(term.coeffs.len(), F::ZERO);
+        }
+
+        for (coeff, &term_coeff) in self.coeffs.iter_mut().zip(&term.coeffs) {
+            *coeff += scale * term_coeff;
+        }
+
+        // Cancellation may have produced leading zeros
+        self.renormalize();
+    }
+
     /// Returns the polynomial `1 + r * x + r^2 * x^2 + ... + r^degree * x^degree`
     pub fn power_polynomial(r: F, degree: usize) -> Polynomial<F> {
         if r == F::ZERO {
@@ -459,6 +842,429 @@ impl<F: Field> Polynomial<F> {
     }
 }
 
+impl<F: TwoAdicField> Polynomial<F> {
+    /// Returns the product of `self` and the vanishing polynomial of `roots`,
+    /// i. e. `self * (x - distinct_roots[0]) * ... * (x - distinct_roots[n -
+    /// 1])`, where `distinct_roots` contains the distinct elements of `roots`
+    /// (cf. [`Polynomial::vanishing_polynomial`]). If `roots` is empty, a copy
+    /// of `self` is returned.
+    ///
+    /// Depending on what is expected to be faster based on the degree of
+    /// `self` and the number of roots, this either multiplies `self` by each
+    /// linear factor `x - root` in place (which takes `O(deg(self) * n)`
+    /// operations and is preferable for few roots) or computes the vanishing
+    /// polynomial with a subproduct tree (i. e. by multiplying the linear
+    /// factors pairwise, then the resulting products pairwise and so on, which
+    /// allows the larger products to be computed using FFTs) and then
+    /// multiplies it by `self` once.
+    pub fn mul_by_vanishing(&self, roots: &[F]) -> Polynomial<F> {
+        let roots = roots.iter().copied().unique().collect_vec();
+
+        if roots.is_empty() || self.is_zero() {
+            return self.clone();
+        }
+
+        // Rough estimate in the same spirit as that of Mul::mul: only
+        // multiplications are taken into account and the cost of the subproduct
+        // tree is approximated by that of the final multiplication
+        let product_len = self.coeffs.len() + roots.len();
+        let fft_domain_size = product_len.next_power_of_two();
+        let fft_cost = 3 * fft_domain_size * fft_domain_size.ilog2() as usize + fft_domain_size;
+        let in_place_cost = product_len * roots.len();
+
+        if in_place_cost <= fft_cost {
+            return self.mul_by_linear_factors(&roots);
+        }
+
+        let mut layer = roots
+            .into_iter()
+            .map(Polynomial::vanishing_linear_polynomial)
+            .collect_vec();
+
+        while layer.len() > 1 {
+            layer = layer
+                .chunks(2)
+                .map(|pair| match pair {
+                    [left, right] => left * right,
+                    [single] => single.clone(),
+                    _ => unreachable!(),
+                })
+                .collect();
+        }
+
+        self * &layer[0]
+    }
+
+    /// Returns the same interpolating polynomial as
+    /// [`Polynomial::lagrange_interpolation`] (with the same handling of
+    /// repeated points), computed with a subproduct tree: the linear factors
+    /// `x - x_i` are multiplied pairwise, the resulting products pairwise and
+    /// so on up to the vanishing polynomial `V` of all points. The weights
+    /// `y_i / V'(x_i)` are then combined up the same tree, the node with
+    /// children `L` and `R` receiving `n_L * V_R + n_R * V_L`, where `n` and
+    /// `V` denote the combined weights and the vanishing polynomial of a node.
+    ///
+    /// The multiplications of large polynomials in both passes use FFTs, which
+    /// makes this method preferable to Lagrange interpolation for hundreds of
+    /// points or more. With the `parallel` feature, the nodes of each layer
+    /// and the two products of each combination are computed in parallel. The
+    /// result does not depend on the scheduling, since field arithmetic is
+    /// exact and each node is combined from the same operands in the same
+    /// order.
+    ///
+    /// # Panics
+    ///
+    /// Same as [`Polynomial::lagrange_interpolation`].
+    pub fn interpolate_subproduct_tree(point_to_evals: Vec<(F, F)>) -> Polynomial<F> {
+        Self::interpolate_subproduct_tree_with(point_to_evals, true)
+    }
+
+    // Internal method implementing interpolate_subproduct_tree, which runs
+    // serially if parallel is false (even with the parallel feature)
+    fn interpolate_subproduct_tree_with(
+        point_to_evals: Vec<(F, F)>,
+        parallel: bool,
+    ) -> Polynomial<F> {
+        if point_to_evals.is_empty() {
+            panic!("The interpolation of an empty set is undefined");
+        }
+
+        let (points, evals): (Vec<F>, Vec<F>) = Self::remove_duplicate_points(point_to_evals)
+            .into_iter()
+            .unzip();
+
+        // The first layer contains the linear factors x - x_i and each of the
+        // following ones the pairwise products of the previous one (the last
+        // polynomial being carried over if their number is odd)
+        let mut tree = vec![points
+            .iter()
+            .map(|&x| Polynomial::vanishing_linear_polynomial(x))
+            .collect_vec()];
+
+        while tree.last().unwrap().len() > 1 {
+            let layer = tree.last().unwrap();
+            let next_layer = map_indices(layer.len().div_ceil(2), parallel, |j| {
+                match layer.get(2 * j + 1) {
+                    Some(right) => &layer[2 * j] * right,
+                    None => layer[2 * j].clone(),
+                }
+            });
+            tree.push(next_layer);
+        }
+
+        // V'(x_i) is non-zero since the points are distinct
+        let vanishing_poly = &tree.last().unwrap()[0];
+        let derivative_evals = map_indices(points.len(), parallel, |i| {
+            vanishing_poly.evaluate_derivative(&points[i])
+        });
+
+        let mut numerators = evals
+            .into_iter()
+            .zip(batch_multiplicative_inverse(&derivative_evals))
+            .map(|(y, inverse)| Polynomial::from_coeffs(vec![y * inverse]))
+            .collect_vec();
+
+        for layer in &tree[..tree.len() - 1] {
+            numerators = map_indices(layer.len().div_ceil(2), parallel, |j| {
+                match layer.get(2 * j + 1) {
+                    Some(right) => {
+                        let (left_term, right_term) = join_if(
+                            parallel,
+                            || &numerators[2 * j] * right,
+                            || &numerators[2 * j + 1] * &layer[2 * j],
+                        );
+                        &left_term + &right_term
+                    }
+                    None => numerators[2 * j].clone(),
+                }
+            });
+        }
+
+        numerators.pop().unwrap()
+    }
+
+    /// Multiplies `self` and `other` if their product has degree at most
+    /// `max_degree`, e. g. so that it fits in a committed domain. The degree of
+    /// the product is determined from those of the factors before any
+    /// multiplication takes place. The product of the zero polynomial with any
+    /// other one is always returned.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DegreeExceeded`] with the degree of the product if it is
+    /// greater than `max_degree`.
+    pub fn checked_mul(
+        &self,
+        other: &Self,
+        max_degree: usize,
+    ) -> Result<Polynomial<F>, DegreeExceeded> {
+        if let (Some(d1), Some(d2)) = (self.degree(), other.degree()) {
+            if d1 + d2 > max_degree {
+                return Err(DegreeExceeded {
+                    degree: d1 + d2,
+                    max_degree,
+                });
+            }
+        }
+
+        Ok(self * other)
+    }
+
+    /// Returns the unique polynomial of degree less than `n = evals.len()`
+    /// which takes the value `evals[i]` at `shift * w^i` for each `i`, where
+    /// `w = F::two_adic_generator(log2(n))`. In other words, this interpolates
+    /// over the coset `shift * <w>`, which amounts to an inverse coset DFT and
+    /// is much cheaper than general interpolation (cf.
+    /// [`Polynomial::lagrange_interpolation`]).
+    ///
+    /// # Parameters
+    ///
+    /// - `evals`: The evaluations over the coset, in the order given above.
+    /// - `shift`: The shift of the coset, which must be non-zero.
+    /// - `dft`: The DFT implementation to use.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is not a power of two or exceeds the two-adicity of `F`.
+    pub fn interpolate_coset<D: TwoAdicSubgroupDft<F>>(
+        evals: &[F],
+        shift: F,
+        dft: &D,
+    ) -> Polynomial<F> {
+        Polynomial::from_coeffs(dft.coset_idft(evals.to_vec(), shift))
+    }
+
+    /// Returns whether `self` and `other` agree at every point of the coset
+    /// `shift * <w>` of size `n = 2^log_size`, where
+    /// `w = F::two_adic_generator(log_size)`, i. e. whether they are equal as
+    /// functions on the coset. Unlike `self == other`, this ignores differences
+    /// in the coefficients which vanish on the coset, e. g. between a
+    /// polynomial and a padded or truncated form of it whose extra terms are
+    /// multiples of `x^n - shift^n`.
+    ///
+    /// Both polynomials are first reduced modulo `x^n - shift^n` (which does
+    /// not change their values on the coset) and then evaluated over it with a
+    /// coset DFT.
+    ///
+    /// # Parameters
+    ///
+    /// - `other`: The polynomial to compare `self` with.
+    /// - `log_size`: The log2 of the size of the coset.
+    /// - `shift`: The shift of the coset, which must be non-zero.
+    /// - `dft`: The DFT implementation to use.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `log_size` exceeds the two-adicity of `F`.
+    pub fn agrees_on_coset<D: TwoAdicSubgroupDft<F>>(
+        &self,
+        other: &Self,
+        log_size: usize,
+        shift: F,
+        dft: &D,
+    ) -> bool {
+        let evals = |polynomial: &Self| {
+            dft.coset_dft(
+                polynomial.reduce_mod_coset_vanishing(log_size, shift),
+                shift,
+            )
+        };
+
+        evals(self) == evals(other)
+    }
+
+    // Returns the 2^log_size coefficients of the remainder of self modulo the
+    // vanishing polynomial x^n - shift^n of the coset shift * <w> of size
+    // n = 2^log_size. Since x^n = shift^n on the coset, the coefficient of
+    // x^(j * n + i) contributes shift^(j * n) times itself to that of x^i.
+    fn reduce_mod_coset_vanishing(&self, log_size: usize, shift: F) -> Vec<F> {
+        let size = 1 << log_size;
+        let shift_pow = shift.exp_power_of_2(log_size);
+
+        let mut reduced = vec![F::ZERO; size];
+        let mut scale = F::ONE;
+
+        for chunk in self.coeffs.chunks(size) {
+            for (r, &c) in reduced.iter_mut().zip(chunk) {
+                *r += scale * c;
+            }
+            scale *= shift_pow;
+        }
+
+        reduced
+    }
+
+    /// Returns the remainder of the division of `self` by `divisor`, i. e. the
+    /// second component of
+    /// [`divide_with_remainder`](Polynomial::divide_with_remainder), without
+    /// materialising the quotient where possible.
+    ///
+    /// If `divisor` is linear, the remainder is the evaluation of `self` at its
+    /// root (cf. Ruffini's rule). Otherwise, depending on what is expected to
+    /// be faster, either the classical algorithm is run in place on a single
+    /// vector of coefficients, discarding the quotient coefficients as they
+    /// are produced, or the quotient is computed with Newton iteration (i. e.
+    /// by inverting the reversal of `divisor` as a power series using FFT
+    /// multiplications) and the remainder is recovered as `self - quotient *
+    /// divisor`. The latter is preferable when both the quotient and the
+    /// divisor are large.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `divisor` is the zero polynomial
+    pub fn rem(&self, divisor: &Self) -> Polynomial<F> {
+        let d_deg = divisor
+            .degree()
+            .expect("Cannot divide by the zero polynomial");
+
+        // Trivial division cases
+        let Some(d_self) = self.degree() else {
+            return Polynomial::zero();
+        };
+
+        if d_self < d_deg {
+            return self.clone();
+        }
+
+        if d_deg == 0 {
+            return Polynomial::zero();
+        }
+
+        if d_deg == 1 {
+            let root = -divisor.coeffs[0] * divisor.coeffs[1].inverse();
+            return Polynomial::constant(self.evaluate(&root));
+        }
+
+        // Rough estimate in the same spirit as that of Mul::mul: Newton
+        // iteration amounts to about four FFT multiplications of the size of
+        // self, whereas the classical algorithm performs one multiplication per
+        // coefficient of the divisor and of the quotient
+        let quotient_len = d_self - d_deg + 1;
+        let fft_domain_size = (d_self + 1).next_power_of_two();
+        let newton_cost =
+            4 * (3 * fft_domain_size * fft_domain_size.ilog2() as usize + fft_domain_size);
+        let classical_cost = quotient_len * (d_deg + 1);
+
+        if classical_cost <= newton_cost {
+            let mut coeffs = self.coeffs.clone();
+            let divisor_leading_coeff_inv = divisor.leading_coeff().inverse();
+
+            // Cancel the leading coefficient of the current remainder, whose
+            // degree decreases by one at each step
+            for deg in (d_deg..=d_self).rev() {
+                let q_coeff = coeffs[deg] * divisor_leading_coeff_inv;
+
+                for (i, &div_coeff) in divisor.coeffs.iter().enumerate() {
+                    coeffs[deg - d_deg + i] -= q_coeff * div_coeff;
+                }
+            }
+
+            coeffs.truncate(d_deg);
+            return Polynomial::from_coeffs(coeffs);
+        }
+
+        // If rev_n(h) denotes the polynomial x^n * h(1/x), then
+        //   rev_{d_self}(self) = rev_{d_self - d_deg}(q) * rev_{d_deg}(divisor)
+        //                        + x^{d_self - d_deg + 1} * rev_{d_deg - 1}(r)
+        // so that the reversal of the quotient q can be obtained modulo
+        // x^{quotient_len} by multiplying by the inverse of rev_{d_deg}(divisor)
+        let reversed_divisor =
+            Polynomial::from_coeffs(divisor.coeffs.iter().rev().copied().collect());
+        let reversed_self =
+            Polynomial::from_coeffs(self.coeffs[d_deg..].iter().rev().copied().collect());
+
+        let reversed_quotient = (&reversed_self
+            * &reversed_divisor.inverse_mod_power_of_x(quotient_len))
+            .with_len(quotient_len);
+
+        let quotient =
+            Polynomial::from_coeffs(reversed_quotient.coeffs.into_iter().rev().collect());
+
+        // Only the coefficients of self - q * divisor of degree less than that
+        // of the divisor can be non-zero
+        let product = &quotient * divisor;
+
+        Polynomial::from_coeffs(
+            (0..d_deg)
+                .map(|i| self.coeffs[i] - product.coeffs.get(i).copied().unwrap_or(F::ZERO))
+                .collect(),
+        )
+    }
+
+    // Internal method which returns the size of the domain over which the FFT
+    // multiplication of self and other should be performed, or None if the
+    // naive algorithm should be used instead. Both polynomials must be
+    // non-zero.
+    fn fft_mul_domain_size(&self, other: &Self) -> Option<usize> {
+        let d_self = self.degree().unwrap();
+        let d_other = other.degree().unwrap();
+
+        let fft_domain_size = (d_self + d_other + 1).next_power_of_two();
+        let fft_domain_size_log = fft_domain_size.ilog2() as usize;
+
+        // This is only a rough estimate to avoid doing three [i]FFTs in very
+        // imbalanced cases (such as large poly times constant or deg-two poly).
+        // Only multiplications are taken into account.
+        let fft_cost = 3 * fft_domain_size * fft_domain_size_log + fft_domain_size;
+        let naive_cost = (d_self + 1) * (d_other + 1);
+
+        // We also use the naive algorithm in the unlikely case the poylnomials
+        // are so large that the two-adicity of F* does not support an FFT
+        // therein
+        (fft_cost <= naive_cost && fft_domain_size_log <= F::TWO_ADICITY).then_some(fft_domain_size)
+    }
+
+    // Internal method which returns the inverse of self as a power series
+    // modulo x^len using Newton iteration: if g is the inverse of self modulo
+    // x^t, then g * (2 - self * g) is its inverse modulo x^{2t}. The constant
+    // term of self must be non-zero.
+    fn inverse_mod_power_of_x(&self, len: usize) -> Polynomial<F> {
+        let mut inverse = Polynomial::constant(self.constant_term().inverse());
+        let mut precision = 1;
+
+        // Reduction modulo x^len, restoring the invariant of no leading zeros
+        let reduce = |polynomial: &Polynomial<F>, len: usize| {
+            let mut reduced = polynomial.with_len(len);
+            reduced.renormalize();
+            reduced
+        };
+
+        while precision < len {
+            precision = (2 * precision).min(len);
+
+            let mut correction = (&reduce(self, precision) * &inverse).with_len(precision);
+            correction.coeffs.iter_mut().for_each(|c| *c = -*c);
+            correction.coeffs[0] += F::TWO;
+            correction.renormalize();
+
+            inverse = reduce(&(&inverse * &correction), precision);
+        }
+
+        inverse
+    }
+
+    // Internal method which multiplies self by x - root for each of the given
+    // roots, updating a single vector of coefficients in place
+    fn mul_by_linear_factors(&self, roots: &[F]) -> Polynomial<F> {
+        let mut coeffs = Vec::with_capacity(self.coeffs.len() + roots.len());
+        coeffs.extend_from_slice(&self.coeffs);
+
+        for &root in roots {
+            // If f has coefficients [c_0, ..., c_d], then (x - root) * f has
+            // coefficients [0, c_0, ..., c_d] - root * [c_0, ..., c_d, 0]
+            coeffs.push(F::ZERO);
+
+            for i in (1..coeffs.len()).rev() {
+                coeffs[i] = coeffs[i - 1] - root * coeffs[i];
+            }
+
+            coeffs[0] = -root * coeffs[0];
+        }
+
+        Polynomial::from_coeffs(coeffs)
+    }
+}
+
 impl<'a, F: Field> Add<&'a Polynomial<F>> for &Polynomial<F> {
     type Output = Polynomial<F>;
 
@@ -519,24 +1325,9 @@ impl<F: TwoAdicField> Mul<&Polynomial<F>> for &Polynomial<F> {
             return Polynomial::zero();
         }
 
-        let d_self = self.degree().unwrap();
-        let d_other = other.degree().unwrap();
-
-        let fft_domain_size = (d_self + d_other + 1).next_power_of_two();
-        let fft_domain_size_log = fft_domain_size.ilog2() as usize;
-
-        // This is only a rough estimate to avoid doing three [i]FFTs in very
-        // imbalanced cases (such as large poly times constant or deg-two poly).
-        // Only multiplications are taken into account.
-        let fft_cost = 3 * fft_domain_size * fft_domain_size_log + fft_domain_size;
-        let naive_cost = (d_self + 1) * (d_other + 1);
-
-        // We also use the naive algorithm in the unlikely case the poylnomials
-        // are so large that the two-adicity of F* does not support an FFT
-        // therein
-        if fft_cost > naive_cost || fft_domain_size_log > F::TWO_ADICITY {
+        let Some(fft_domain_size) = self.fft_mul_domain_size(other) else {
             return self.mul_naive(other);
-        }
+        };
 
         let mut extended_self = self.coeffs.clone();
         let mut extended_other = other.coeffs.clone();
@@ -592,6 +1383,46 @@ impl<F: TwoAdicField> Product<Polynomial<F>> for Polynomial<F> {
     }
 }
 
+/// Collects coefficients in increasing-degree order into a polynomial. Leading
+/// zeros are automatically trimmed, as in [`Polynomial::from_coeffs_normalized`].
+impl<F: Field> FromIterator<F> for Polynomial<F> {
+    fn from_iter<I: IntoIterator<Item = F>>(iter: I) -> Self {
+        Polynomial::from_coeffs_normalized(iter.into_iter().collect())
+    }
+}
+
+/// Access to the coefficient of `x^index`, i. e. `self.coeffs()[index]`.
+///
+/// # Panics
+///
+/// Panics if `index` is not smaller than the number of coefficients (which,
+/// since there are no leading zeros, is the degree plus one), even though the
+/// coefficient of `x^index` is zero mathematically.
+impl<F: Field> Index<usize> for Polynomial<F> {
+    type Output = F;
+
+    fn index(&self, index: usize) -> &F {
+        check_coeff_index(index, self.coeffs.len());
+        &self.coeffs[index]
+    }
+}
+
+/// Mutable access to the coefficient of `x^index`. As with
+/// [`Polynomial::coeffs_mut`], setting the leading coefficient to zero breaks
+/// the internal invariant that there are no leading zeros, and
+/// [`Polynomial::renormalize`] must then be called before using the polynomial
+/// in any other way.
+///
+/// # Panics
+///
+/// Panics under the same conditions as [`Index`].
+impl<F: Field> IndexMut<usize> for Polynomial<F> {
+    fn index_mut(&mut self, index: usize) -> &mut F {
+        check_coeff_index(index, self.coeffs.len());
+        &mut self.coeffs[index]
+    }
+}
+
 impl<F: Field> Add<&F> for &Polynomial<F> {
     type Output = Polynomial<F>;
 
@@ -616,10 +1447,73 @@ impl<F: Field> Mul<&F> for &Polynomial<F> {
     }
 }
 
+/// Division by a scalar, i. e. multiplication by its inverse.
+///
+/// # Panics
+///
+/// Panics if the scalar is zero.
 impl<F: Field> Div<&F> for &Polynomial<F> {
     type Output = Polynomial<F>;
 
     fn div(self, other: &F) -> Polynomial<F> {
-        Polynomial::from_coeffs(self.coeffs.iter().map(|&c| c * other.inverse()).collect())
+        let inverse = other
+            .try_inverse()
+            .expect("Cannot divide a polynomial by the zero scalar");
+
+        self * &inverse
+    }
+}
+
+/// 64-bit FNV-1a hash of the little-endian bytes of the given words. It is
+/// available without std and is not randomly seeded, unlike the hashers of std,
+/// and does not depend on the platform, which makes it suitable for
+/// fingerprints meant to be stable across runs and machines. It is not
+/// cryptographic: collisions can easily be found on purpose.
+pub fn fnv1a(words: impl IntoIterator<Item = u64>) -> u64 {
+    words
+        .into_iter()
+        .flat_map(u64::to_le_bytes)
+        .fold(0xcbf29ce484222325, |hash, byte| {
+            (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+        })
+}
+
+// Panics with an informative message if index is out of bounds for a
+// coefficient vector of length len
+fn check_coeff_index(
+    // Index of the accessed coefficient
+    index: usize,
+    // Number of coefficients of the polynomial
+    len: usize,
+) {
+    assert!(
+        index < len,
+        "Coefficient index {index} is out of bounds for a polynomial with {len} \
+        coefficients"
+    );
+}
+
+// Maps f over 0..n, in parallel if requested (and the parallel feature is
+// enabled), preserving the order of the results
+fn map_indices<U: Send>(n: usize, parallel: bool, f: impl Fn(usize) -> U + Sync + Send) -> Vec<U> {
+    if parallel {
+        (0..n).into_par_iter().map(f).collect()
+    } else {
+        (0..n).map(f).collect()
+    }
+}
+
+// Runs a and b, in parallel if requested (and the parallel feature is enabled)
+fn join_if<A, B, RA, RB>(parallel: bool, a: A, b: B) -> (RA, RB)
+where
+    A: FnOnce() -> RA + Send,
+    B: FnOnce() -> RB + Send,
+    RA: Send,
+    RB: Send,
+{
+    if parallel {
+        join(a, b)
+    } else {
+        (a(), b())
     }
 }
@@ -1,19 +1,22 @@
 use alloc::vec;
 use alloc::vec::Vec;
+use core::iter;
 
 use itertools::Itertools;
 use p3_baby_bear::BabyBear;
 use p3_dft::{Radix2Dit, TwoAdicSubgroupDft};
-use p3_field::{Field, PrimeCharacteristicRing, TwoAdicField};
+use p3_field::extension::BinomialExtensionField;
+use p3_field::{Field, PackedValue, PrimeCharacteristicRing, PrimeField64, TwoAdicField};
 use p3_goldilocks::Goldilocks;
 use p3_matrix::dense::RowMajorMatrix;
 use p3_matrix::Matrix;
 use rand::Rng;
 
 use crate::test_utils::rand_poly;
-use crate::Polynomial;
+use crate::{fnv1a, DegreeExceeded, Polynomial};
 
 type BB = BabyBear;
+type BBExt = BinomialExtensionField<BB, 4>;
 type GL = Goldilocks;
 
 const TEST_ITERATIONS: usize = 30;
@@ -45,6 +48,131 @@ fn test_trimmed_coeffs() {
     assert_eq!(poly.coeffs, vec![]);
 }
 
+#[test]
+// Checks that from_coeffs_normalized trims trailing zeros, so that polynomials
+// built from coefficient vectors differing only in them are equal, and that so
+// does the constant polynomial zero
+fn test_from_coeffs_normalized() {
+    assert_eq!(
+        Polynomial::from_coeffs_normalized(field_elements_from_i64::<GL>(vec![1, 0, 0])),
+        Polynomial::from_coeffs(vec![GL::ONE])
+    );
+    assert!(Polynomial::from_coeffs_normalized(vec![GL::ZERO; 3]).is_zero());
+
+    assert_eq!(Polynomial::constant(GL::ZERO), Polynomial::zero());
+    assert_eq!(Polynomial::constant(GL::ZERO).degree(), None);
+
+    // The remainder of the division by a linear polynomial at one of whose
+    // roots the dividend vanishes is the zero polynomial
+    let divisor = Polynomial::vanishing_linear_polynomial(GL::TWO);
+    let dividend = &divisor * &rand_poly(10);
+    assert_eq!(dividend.rem(&divisor), Polynomial::zero());
+}
+
+#[test]
+// Checks that evaluating at zero returns the constant term, including for the
+// zero polynomial and polynomials with a zero constant term
+fn test_evaluate_at_zero() {
+    let poly = rand_poly::<BB>(20);
+    assert_eq!(poly.evaluate(&BB::ZERO), poly.constant_term());
+    assert_eq!(poly.evaluate(&BB::ZERO), poly.coeffs()[0]);
+
+    let poly = Polynomial::from_coeffs(field_elements_from_i64::<BB>(vec![0, 3, 0, 7]));
+    assert_eq!(poly.evaluate(&BB::ZERO), BB::ZERO);
+
+    assert_eq!(Polynomial::<BB>::zero().evaluate(&BB::ZERO), BB::ZERO);
+    assert_eq!(Polynomial::<BB>::zero().constant_term(), BB::ZERO);
+}
+
+#[test]
+// Checks that evaluate_all agrees with evaluating each polynomial individually,
+// including for the zero polynomial and polynomials of different degrees
+fn test_evaluate_all() {
+    let mut rng = rand::rng();
+
+    let polys = [
+        rand_poly::<GL>(20),
+        Polynomial::zero(),
+        rand_poly(3),
+        Polynomial::constant(rng.random()),
+        rand_poly(31),
+    ];
+    let poly_refs = polys.iter().collect_vec();
+
+    for point in [GL::ZERO, GL::ONE, rng.random(), rng.random()] {
+        let expected = polys.iter().map(|p| p.evaluate(&point)).collect_vec();
+        assert_eq!(Polynomial::evaluate_all(&poly_refs, point), expected);
+    }
+
+    assert!(Polynomial::<GL>::evaluate_all(&[], GL::ONE).is_empty());
+}
+
+#[test]
+// Checks that evaluate_packed agrees with evaluate at every point, for numbers of
+// points which are and are not multiples of the packing width
+fn test_evaluate_packed() {
+    let mut rng = rand::rng();
+
+    let polys = [rand_poly::<BBExt>(20), Polynomial::zero(), rand_poly(1)];
+
+    for poly in polys {
+        for num_points in [0, 1, 7, 8, 16, 37] {
+            let points: Vec<BBExt> = (0..num_points).map(|_| rng.random()).collect();
+            let expected = points.iter().map(|x| poly.evaluate(x)).collect_vec();
+
+            assert_eq!(poly.evaluate_packed::<BB>(&points), expected);
+        }
+    }
+}
+
+#[test]
+// Checks that evaluate_many agrees with evaluate at every point when the number
+// of points is not a multiple of the packing width
+fn test_evaluate_many() {
+    let mut rng = rand::rng();
+
+    let width = <BB as Field>::Packing::WIDTH;
+    let points: Vec<BB> = (0..3 * width + 1).map(|_| rng.random()).collect();
+
+    for poly in [rand_poly::<BB>(20), Polynomial::zero(), rand_poly(1)] {
+        let expected = points.iter().map(|x| poly.evaluate(x)).collect_vec();
+
+        assert_eq!(poly.evaluate_many(&points), expected);
+    }
+}
+
+#[test]
+// Checks that mutating the coefficients through coeffs_mut and then calling
+// renormalize yields a correctly normalised polynomial
+fn test_coeffs_mut_renormalize() {
+    let mut poly = Polynomial::from_coeffs(field_elements_from_i64::<GL>(vec![1, 2, 3]));
+
+    // Zeroing the leading coefficient and appending zeros
+    poly.coeffs_mut()[2] = GL::ZERO;
+    poly.coeffs_mut().extend([GL::ZERO; 3]);
+    poly.renormalize();
+
+    assert_eq!(poly.coeffs, field_elements_from_i64(vec![1, 2]));
+    assert_eq!(poly.degree(), Some(1));
+
+    // Extending in place with a non-zero leading coefficient
+    poly.coeffs_mut()
+        .extend(field_elements_from_i64::<GL>(vec![0, 5]));
+    poly.renormalize();
+
+    assert_eq!(
+        poly,
+        Polynomial::from_coeffs(field_elements_from_i64(vec![1, 2, 0, 5]))
+    );
+
+    // Zeroing all coefficients
+    poly.coeffs_mut().iter_mut().for_each(|c| *c = GL::ZERO);
+    poly.renormalize();
+
+    assert!(poly.is_zero());
+    assert_eq!(poly.coeffs, vec![]);
+}
+
 #[test]
 // Checks the evaluate method manually and using an FFT
 fn test_evaluate() {
@@ -283,6 +411,73 @@ fn test_div_zero_by_zero() {
     Polynomial::<GL>::zero().divide_with_remainder(&Polynomial::zero());
 }
 
+#[test]
+// Checks that with_len pads with zeros or truncates to exactly the requested
+// number of coefficients and that renormalizing the output recovers the
+// polynomial or its remainder modulo x^len, respectively
+fn test_with_len() {
+    let poly = Polynomial::from_coeffs(vec![BB::ONE, BB::TWO, BB::ZERO, BB::from_u8(3)]);
+
+    // Padding
+    let mut padded = poly.with_len(6);
+    assert_eq!(
+        padded.coeffs(),
+        &[
+            BB::ONE,
+            BB::TWO,
+            BB::ZERO,
+            BB::from_u8(3),
+            BB::ZERO,
+            BB::ZERO
+        ]
+    );
+    padded.renormalize();
+    assert_eq!(padded, poly);
+
+    // Same length
+    assert_eq!(poly.with_len(4), poly);
+
+    // Truncation, resulting in a leading zero
+    let mut truncated = poly.with_len(3);
+    assert_eq!(truncated.coeffs(), &[BB::ONE, BB::TWO, BB::ZERO]);
+    truncated.renormalize();
+    assert_eq!(truncated, Polynomial::from_coeffs(vec![BB::ONE, BB::TWO]));
+
+    assert!(poly.with_len(0).coeffs().is_empty());
+    assert_eq!(
+        Polynomial::<BB>::zero().with_len(2).coeffs(),
+        &[BB::ZERO; 2]
+    );
+}
+
+#[test]
+// Checks that split_at produces a low part of degree less than the splitting
+// point and that recombining both parts via shift_up reconstructs the original
+// polynomial
+fn test_split_at() {
+    let mut rng = rand::rng();
+
+    for _ in 0..TEST_ITERATIONS {
+        let deg = rng.random_range(0..100);
+        let polynomial: Polynomial<BB> = rand_poly(deg);
+
+        for k in [0, 1, deg / 2, deg, deg + 1, deg + 10] {
+            let (low, high) = polynomial.split_at(k);
+
+            assert!(low.degree().is_none_or(|d| d < k));
+            assert_eq!(&low + &high.shift_up(k), polynomial);
+
+            if k > deg {
+                assert!(high.is_zero());
+            }
+        }
+    }
+
+    let (low, high) = Polynomial::<BB>::zero().split_at(3);
+    assert!(low.is_zero() && high.is_zero());
+    assert!(Polynomial::<BB>::zero().shift_up(3).is_zero());
+}
+
 #[test]
 // Checks that multiplication using the FFT and the naive algorithm return the
 // same result
@@ -325,6 +520,221 @@ fn test_lagrange_interpolation() {
     }
 }
 
+#[test]
+// Checks that evaluate_derivative agrees with evaluating the derivative,
+// including for constant and zero polynomials (whose derivative is zero) and
+// at zero
+fn test_evaluate_derivative() {
+    let mut rng = rand::rng();
+
+    for deg in 0..TEST_ITERATIONS {
+        let polynomial = rand_poly::<BB>(deg);
+
+        for point in [BB::ZERO, rng.random()] {
+            assert_eq!(
+                polynomial.evaluate_derivative(&point),
+                polynomial.derivative().evaluate(&point)
+            );
+        }
+    }
+
+    // The derivative of 1 + 2x + 3x^2 is 2 + 6x
+    let polynomial: Polynomial<GL> =
+        Polynomial::from_coeffs(field_elements_from_i64(vec![1, 2, 3]));
+    assert_eq!(
+        polynomial.derivative(),
+        Polynomial::from_coeffs(field_elements_from_i64(vec![2, 6]))
+    );
+    assert_eq!(
+        polynomial.evaluate_derivative(&GL::TWO),
+        field_element_from_i64(14)
+    );
+
+    assert_eq!(
+        Polynomial::<GL>::zero().evaluate_derivative(&GL::TWO),
+        GL::ZERO
+    );
+    assert!(Polynomial::constant(GL::TWO).derivative().is_zero());
+}
+
+#[test]
+// Checks that the coefficient and barycentric forms returned by
+// interpolate_with_barycentric agree at random points as well as at the
+// interpolation points, where they take the requested values
+fn test_interpolate_with_barycentric() {
+    let mut rng = rand::rng();
+
+    for _ in 0..TEST_ITERATIONS {
+        let num_points = rng.random_range(1..20);
+
+        let point_to_evals: Vec<(GL, GL)> = (0..num_points)
+            .map(|_| (rng.random(), rng.random()))
+            .collect();
+
+        let (polynomial, evaluator) =
+            Polynomial::interpolate_with_barycentric(point_to_evals.clone());
+
+        assert_eq!(
+            polynomial,
+            Polynomial::lagrange_interpolation(point_to_evals.clone())
+        );
+
+        for _ in 0..10 {
+            let point = rng.random();
+            assert_eq!(evaluator.evaluate(&point), polynomial.evaluate(&point));
+        }
+
+        for (point, eval) in point_to_evals {
+            assert_eq!(evaluator.evaluate(&point), eval);
+        }
+    }
+}
+
+#[test]
+// Checks that add_scaled_assign agrees with adding the scaled polynomial,
+// including when the sum cancels out some or all of the leading coefficients
+fn test_add_scaled_assign() {
+    let mut rng = rand::rng();
+
+    for _ in 0..TEST_ITERATIONS {
+        let poly = rand_poly::<GL>(rng.random_range(0..30));
+        let term = rand_poly::<GL>(rng.random_range(0..30));
+        let scale: GL = rng.random();
+
+        let mut accumulated = poly.clone();
+        accumulated.add_scaled_assign(scale, &term);
+
+        assert_eq!(accumulated, &poly + &(&term * &scale));
+    }
+
+    let poly = rand_poly::<GL>(10);
+
+    let mut cancelled = poly.clone();
+    cancelled.add_scaled_assign(-GL::ONE, &poly);
+    assert!(cancelled.is_zero());
+
+    let mut partially_cancelled = &poly + &Polynomial::x();
+    partially_cancelled.add_scaled_assign(-GL::ONE, &poly);
+    assert_eq!(partially_cancelled, Polynomial::x());
+}
+
+#[test]
+// Checks that a polynomial is recovered from its strided sub-polynomials as
+// f(x) = f_0(x^k) + x * f_1(x^k) + ... + x^(k - 1) * f_(k - 1)(x^k) and that
+// split_even_odd agrees with split_radix for k = 2
+fn test_split_radix() {
+    let mut rng = rand::rng();
+
+    for log_k in 0..4 {
+        let k = 1 << log_k;
+
+        for _ in 0..TEST_ITERATIONS {
+            let poly = rand_poly::<GL>(rng.random_range(0..50));
+            let parts = poly.split_radix(log_k);
+
+            assert_eq!(parts.len(), k);
+
+            let reconstructed = parts
+                .iter()
+                .enumerate()
+                .fold(Polynomial::zero(), |acc, (i, part)| {
+                    &acc + &part.compose_with_exponent(k).shift_up(i)
+                });
+
+            assert_eq!(reconstructed, poly);
+        }
+    }
+
+    let poly = rand_poly::<GL>(9);
+    let (even, odd) = poly.split_even_odd();
+
+    assert_eq!(vec![even.clone(), odd.clone()], poly.split_radix(1));
+    assert_eq!(
+        even.coeffs(),
+        poly.coeffs().iter().step_by(2).copied().collect_vec()
+    );
+    assert_eq!(
+        odd.coeffs(),
+        poly.coeffs()
+            .iter()
+            .skip(1)
+            .step_by(2)
+            .copied()
+            .collect_vec()
+    );
+
+    assert_eq!(
+        Polynomial::<GL>::zero().split_even_odd(),
+        (Polynomial::zero(), Polynomial::zero())
+    );
+}
+
+#[test]
+// Checks that checked_mul returns the product when its degree is within the
+// bound (including exactly at it) and the error otherwise
+fn test_checked_mul() {
+    let poly1 = rand_poly::<BB>(10);
+    let poly2 = rand_poly::<BB>(20);
+
+    assert_eq!(poly1.checked_mul(&poly2, 30), Ok(&poly1 * &poly2));
+    assert_eq!(poly1.checked_mul(&poly2, 100), Ok(&poly1 * &poly2));
+    assert_eq!(
+        poly1.checked_mul(&poly2, 29),
+        Err(DegreeExceeded {
+            degree: 30,
+            max_degree: 29
+        })
+    );
+
+    assert_eq!(
+        poly1.checked_mul(&Polynomial::zero(), 0),
+        Ok(Polynomial::zero())
+    );
+}
+
+#[test]
+// Checks that collecting coefficients into a polynomial and iterating over them
+// round-trips, with leading zeros trimmed
+fn test_from_iter_and_iter() {
+    let coeffs = (1..=5).map(GL::from_u32).collect_vec();
+
+    let polynomial: Polynomial<GL> = coeffs.clone().into_iter().collect();
+    assert_eq!(polynomial, Polynomial::from_coeffs(coeffs.clone()));
+    assert_eq!(polynomial.iter().copied().collect_vec(), coeffs);
+
+    let padded: Polynomial<GL> = coeffs
+        .iter()
+        .copied()
+        .chain(iter::repeat_n(GL::ZERO, 3))
+        .collect();
+    assert_eq!(padded, polynomial);
+    assert_eq!(padded.iter().count(), coeffs.len());
+
+    let zero: Polynomial<GL> = iter::repeat_n(GL::ZERO, 4).collect();
+    assert!(zero.is_zero());
+    assert_eq!(zero.iter().next(), None);
+}
+
+#[test]
+// Checks that Lagrange interpolation, which accumulates its terms in place,
+// produces the same polynomial as summing the scaled terms one by one
+fn test_lagrange_interpolation_accumulation() {
+    let mut rng = rand::rng();
+
+    let point_to_evals: Vec<(GL, GL)> = (0..20).map(|_| (rng.random(), rng.random())).collect();
+    let points = point_to_evals.iter().map(|(x, _)| *x).collect_vec();
+    let vanishing_poly = Polynomial::vanishing_polynomial(points);
+
+    let mut expected = Polynomial::zero();
+
+    for &(point, eval) in &point_to_evals {
+        let (polynomial, _) = vanishing_poly.divide_by_vanishing_linear_polynomial(point);
+        expected += &(&polynomial * &(eval / polynomial.evaluate(&point)));
+    }
+
+    assert_eq!(Polynomial::lagrange_interpolation(point_to_evals), expected);
+}
+
 #[test]
 #[should_panic(expected = "One point has two different requested evaluations")]
 // Checks that Lagrange interpolation panics when two different evaluations are requested of the
@@ -381,6 +791,60 @@ fn test_lagrange_interpolation_duplicates() {
     }
 }
 
+#[test]
+// Checks that the parallel and serial subproduct-tree interpolation of 512
+// random point/value pairs agree and recover the polynomial which produced the
+// values
+fn test_interpolate_subproduct_tree_parallel() {
+    let mut rng = rand::rng();
+
+    let num_points = 512;
+    let polynomial = rand_poly::<GL>(num_points - 1);
+
+    let mut points: Vec<GL> = Vec::new();
+
+    while points.len() < num_points {
+        let point = rng.random();
+        if !points.contains(&point) {
+            points.push(point);
+        }
+    }
+
+    let point_to_evals = points
+        .into_iter()
+        .map(|x| (x, polynomial.evaluate(&x)))
+        .collect_vec();
+
+    let parallel = Polynomial::interpolate_subproduct_tree(point_to_evals.clone());
+    let serial = Polynomial::interpolate_subproduct_tree_with(point_to_evals, false);
+
+    assert_eq!(parallel, serial);
+    assert_eq!(parallel, polynomial);
+}
+
+#[test]
+// Checks that subproduct-tree interpolation agrees with Lagrange interpolation
+// for any number of points (in particular, odd ones, which leave a node
+// without sibling) and duplicate (point, evaluation) pairs
+fn test_interpolate_subproduct_tree() {
+    let mut rng = rand::rng();
+
+    for num_points in 1..=TEST_ITERATIONS {
+        let mut point_to_evals: Vec<(BB, BB)> = (0..num_points)
+            .map(|_| (rng.random(), rng.random()))
+            .collect();
+
+        // Adding a duplicate pair
+        let index = rng.random_range(0..num_points);
+        point_to_evals.push(point_to_evals[index]);
+
+        assert_eq!(
+            Polynomial::interpolate_subproduct_tree(point_to_evals.clone()),
+            Polynomial::lagrange_interpolation(point_to_evals)
+        );
+    }
+}
+
 #[test]
 // Checks the correctness of compose_with_exponent by manually calculating the
 // expected result. Ensures the output matches the input when given a constant
@@ -448,6 +912,51 @@ fn test_vanishing_random() {
         .all(|p| vanishing_poly.evaluate(p) == BB::ZERO));
 }
 
+#[test]
+// Checks that mul_by_vanishing agrees with multiplying by vanishing_polynomial
+// and that dividing its output by the vanishing polynomial recovers the
+// original polynomial, including when the roots contain duplicates
+fn test_mul_by_vanishing() {
+    let mut rng = rand::rng();
+
+    for num_roots in [1, 2, 7, 33] {
+        let poly = rand_poly::<BB>(rng.random_range(0..50));
+        let mut roots: Vec<BB> = (0..num_roots).map(|_| rng.random()).collect();
+        roots.push(roots[0]);
+
+        let vanishing_poly = Polynomial::vanishing_polynomial(roots.clone());
+        let product = poly.mul_by_vanishing(&roots);
+
+        assert_eq!(product, &poly * &vanishing_poly);
+        assert_eq!(&product / &vanishing_poly, poly);
+    }
+
+    let poly = rand_poly::<BB>(10);
+    assert_eq!(poly.mul_by_vanishing(&[]), poly);
+    assert_eq!(
+        Polynomial::<BB>::zero().mul_by_vanishing(&[BB::ONE]),
+        Polynomial::zero()
+    );
+}
+
+#[test]
+// Checks that mul_by_vanishing agrees with multiplying by vanishing_polynomial
+// when the polynomial is large compared to the number of roots, in which case
+// the linear factors are multiplied in place
+fn test_mul_by_vanishing_few_roots() {
+    let mut rng = rand::rng();
+    let poly = rand_poly::<GL>(1000);
+
+    for num_roots in 1..5 {
+        let roots: Vec<GL> = (0..num_roots).map(|_| rng.random()).collect();
+
+        assert_eq!(
+            poly.mul_by_vanishing(&roots),
+            &poly * &Polynomial::vanishing_polynomial(roots)
+        );
+    }
+}
+
 #[test]
 #[should_panic(expected = "The vanishing polynomial of an empty set is undefined")]
 // Checks that vanishing_polynomial panics when the set of points is empty
@@ -537,6 +1046,109 @@ fn test_division_by_linear_polynomial_vanishing() {
     }
 }
 
+#[test]
+// Checks that interpolate_coset recovers a polynomial from its evaluations over
+// a coset and agrees with Lagrange interpolation over the same points
+fn test_interpolate_coset() {
+    let mut rng = rand::rng();
+    let dft = Radix2Dit::<BB>::default();
+
+    for log_size in 0..8 {
+        let size = 1 << log_size;
+        let polynomial: Polynomial<BB> = rand_poly(size - 1);
+        let shift: BB = rng.random();
+
+        let points = BB::two_adic_generator(log_size)
+            .shifted_powers(shift)
+            .take(size)
+            .collect_vec();
+        let evals = points.iter().map(|x| polynomial.evaluate(x)).collect_vec();
+
+        let interpolated = Polynomial::interpolate_coset(&evals, shift, &dft);
+        assert_eq!(interpolated, polynomial);
+
+        let point_to_evals = points.into_iter().zip(evals).collect_vec();
+        assert_eq!(
+            interpolated,
+            Polynomial::lagrange_interpolation(point_to_evals)
+        );
+    }
+}
+
+#[test]
+// Checks that agrees_on_coset identifies polynomials which differ by a multiple
+// of the vanishing polynomial x^n - shift^n of the coset (and hence only above
+// the degree n - 1 implied by it), but not ones which differ on the coset
+fn test_agrees_on_coset() {
+    let mut rng = rand::rng();
+    let dft = Radix2Dit::<BB>::default();
+
+    for log_size in 0..6 {
+        let size = 1 << log_size;
+        let shift: BB = rng.random();
+
+        let mut vanishing_coeffs = vec![BB::ZERO; size + 1];
+        vanishing_coeffs[0] = -shift.exp_power_of_2(log_size);
+        vanishing_coeffs[size] = BB::ONE;
+        let vanishing = Polynomial::from_coeffs(vanishing_coeffs);
+
+        let polynomial: Polynomial<BB> = rand_poly(size - 1);
+        let padded = &polynomial + &(&vanishing * &rand_poly(2 * size));
+
+        assert_ne!(padded, polynomial);
+        assert!(padded.agrees_on_coset(&polynomial, log_size, shift, &dft));
+        assert!(polynomial.agrees_on_coset(&padded, log_size, shift, &dft));
+        assert!(vanishing.agrees_on_coset(&Polynomial::zero(), log_size, shift, &dft));
+
+        // Agreement on one coset does not imply agreement on another
+        assert!(!padded.agrees_on_coset(&polynomial, log_size, shift + BB::ONE, &dft));
+
+        let other = &padded + &Polynomial::constant(BB::ONE);
+        assert!(!other.agrees_on_coset(&polynomial, log_size, shift, &dft));
+    }
+}
+
+#[test]
+// Checks that rem returns the remainder computed by divide_with_remainder for
+// divisors of all degrees, including linear ones and those large enough for
+// the remainder to be recovered through Newton iteration
+fn test_rem() {
+    let mut rng = rand::rng();
+
+    for (deg, divisor_deg) in [
+        (50, 0),
+        (50, 1),
+        (10, 20),
+        (20, 20),
+        (100, 7),
+        (300, 150),
+        (2000, 1000),
+    ] {
+        let polynomial: Polynomial<BB> = rand_poly(deg);
+        let divisor = rand_poly(divisor_deg);
+
+        let (_, expected_r) = polynomial.divide_with_remainder(&divisor);
+        assert_eq!(polynomial.rem(&divisor), expected_r);
+    }
+
+    // Reduction modulo a vanishing polynomial preserves the evaluations at
+    // its roots
+    let points: Vec<BB> = (0..200).map(|_| rng.random()).collect();
+    let vanishing_poly = Polynomial::vanishing_polynomial(points.clone());
+    let polynomial: Polynomial<BB> = rand_poly(1000);
+    let remainder = polynomial.rem(&vanishing_poly);
+
+    assert!(remainder.degree().unwrap() < points.len());
+    assert!(points
+        .iter()
+        .all(|x| remainder.evaluate(x) == polynomial.evaluate(x)));
+
+    assert_eq!(
+        Polynomial::<BB>::zero().rem(&vanishing_poly),
+        Polynomial::zero()
+    );
+}
+
 #[test]
 fn test_power_polynomial() {
     let mut rng = rand::rng();
@@ -558,3 +1170,145 @@ fn test_power_polynomial() {
 
     assert_eq!(&power_polynomial * &rx_1, rxn_1);
 }
+
+#[test]
+// Checks that dividing by a nonzero scalar is the inverse of multiplying by it
+fn test_div_by_scalar() {
+    let mut rng = rand::rng();
+
+    for _ in 0..TEST_ITERATIONS {
+        let p: Polynomial<BB> = rand_poly(rng.random_range(0..100));
+
+        let mut c: BB = rng.random();
+        while c.is_zero() {
+            c = rng.random();
+        }
+
+        assert_eq!(&(&p / &c) * &c, p);
+    }
+}
+
+#[test]
+#[should_panic(expected = "Cannot divide a polynomial by the zero scalar")]
+// Checks that dividing by the zero scalar panics
+fn test_div_by_zero_scalar() {
+    let p: Polynomial<BB> = rand_poly(10);
+    let _ = &p / &BB::ZERO;
+}
+
+#[test]
+// Checks that indexing reads and writes the coefficients and that degree
+// behaves after renormalising a polynomial whose leading coefficient was
+// zeroed through IndexMut
+fn test_index_coeffs() {
+    let mut p: Polynomial<BB> = rand_poly(10);
+    let coeffs = p.coeffs().to_vec();
+
+    for (i, c) in coeffs.iter().enumerate() {
+        assert_eq!(p[i], *c);
+    }
+
+    p[3] = BB::ZERO;
+    assert_eq!(p[3], BB::ZERO);
+    assert_eq!(p.degree(), Some(10));
+
+    let mut expected = coeffs.clone();
+    expected[3] = BB::ZERO;
+    assert_eq!(p, Polynomial::from_coeffs(expected));
+
+    p[3] += BB::ONE;
+    assert_eq!(p[3], BB::ONE);
+
+    p[10] = BB::ZERO;
+    p.renormalize();
+    assert!(p.degree().unwrap() < 10);
+    assert_eq!(p.coeffs().len(), p.degree().unwrap() + 1);
+    assert_ne!(*p.coeffs().last().unwrap(), BB::ZERO);
+}
+
+#[test]
+#[should_panic(expected = "Coefficient index 11 is out of bounds for a polynomial with 11")]
+// Checks that indexing past the last coefficient panics
+fn test_index_coeffs_out_of_bounds() {
+    let p: Polynomial<BB> = rand_poly(10);
+    let _ = p[11];
+}
+
+#[test]
+// Checks that multiplying by a linear factor x - root matches general
+// polynomial multiplication, including for the zero polynomial
+fn test_mul_linear() {
+    let mut rng = rand::rng();
+
+    for _ in 0..TEST_ITERATIONS {
+        let p: Polynomial<BB> = rand_poly(rng.random_range(0..100));
+        let root: BB = rng.random();
+
+        assert_eq!(
+            p.mul_linear(root),
+            &p * &Polynomial::vanishing_linear_polynomial(root)
+        );
+    }
+
+    assert!(Polynomial::<BB>::zero().mul_linear(BB::ONE).is_zero());
+}
+
+#[test]
+// Checks that fingerprints only depend on the polynomial as a function (in
+// particular, not on leading zeros left after raw mutation) and that those of
+// distinct polynomials differ
+fn test_fingerprint() {
+    let mut rng = rand::rng();
+
+    for _ in 0..TEST_ITERATIONS {
+        let p: Polynomial<BB> = rand_poly(rng.random_range(0..100));
+        let padded = p.with_len(p.coeffs().len() + rng.random_range(1..10));
+
+        assert_eq!(p.fingerprint(), p.clone().fingerprint());
+        assert_eq!(padded.fingerprint(), p.fingerprint());
+
+        let mut other = p.clone();
+        other.coeffs_mut()[0] += BB::ONE;
+        assert_ne!(other.fingerprint(), p.fingerprint());
+    }
+
+    let zero = Polynomial::<BB>::zero();
+    assert_eq!(zero.with_len(5).fingerprint(), zero.fingerprint());
+    assert_ne!(Polynomial::<BB>::one().fingerprint(), zero.fingerprint());
+
+    // The fingerprint hashes the number of coefficients followed by the
+    // canonical values of their coordinates (not, e. g., their Montgomery form)
+    let p = Polynomial::from_coeffs(vec![BB::from_u32(3), BB::NEG_ONE]);
+    assert_eq!(p.fingerprint(), fnv1a([2, 3, BB::ORDER_U64 - 1]));
+
+    let ext_p = Polynomial::from_coeffs(vec![BBExt::from(BB::from_u32(5))]);
+    assert_eq!(ext_p.fingerprint(), fnv1a([1, 5, 0, 0, 0]));
+}
+
+#[test]
+// Checks degree_checked on the zero polynomial, constants and polynomials of
+// higher degree, with and without leading zeros
+fn test_degree_checked() {
+    let zero = Polynomial::<BB>::zero();
+    assert_eq!(zero.degree_checked(), None);
+    assert_eq!(zero.with_len(4).degree_checked(), None);
+
+    let constant = Polynomial::constant(BB::from_u8(7));
+    assert_eq!(constant.degree_checked(), Some(0));
+    assert_eq!(constant.with_len(4).degree_checked(), Some(0));
+
+    let mut rng = rand::rng();
+
+    for _ in 0..TEST_ITERATIONS {
+        let degree = rng.random_range(1..100);
+        let p: Polynomial<BB> = rand_poly(degree);
+
+        assert_eq!(p.degree_checked(), Some(degree));
+        assert_eq!(p.degree_checked(), p.degree());
+
+        // Unlike degree, degree_checked ignores leading zeros
+        let padded = p.with_len(degree + 5);
+        assert_eq!(padded.degree(), Some(degree + 4));
+        assert_eq!(padded.degree_checked(), Some(degree));
+    }
+}
@@ -1,10 +1,11 @@
 use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
 use p3_poly::test_utils::rand_poly;
 use p3_stir::test_utils::{
-    test_bb_challenger, test_bb_stir_config, test_gl_challenger, test_gl_stir_config,
+    test_bb_challenger, test_bb_stir_config, test_gl_challenger, test_gl_stir_config, Bb, BbExt,
     BB_EXT_SEC_LEVEL, GL_EXT_SEC_LEVEL,
 };
 use p3_stir::{commit, prove, verify, SecurityAssumption};
+use rand::Rng;
 
 const SAMPLES: usize = 10;
 const LOG_INV_RATE: usize = 1;
@@ -58,9 +59,9 @@ macro_rules! impl_bench_field {
 
             group.bench_function(BenchmarkId::new("verify", log_degree), |b| {
                 b.iter_batched(
-                    || (proof.clone(), commitment.clone(), challenger.clone()),
-                    |(proof, commitment, mut challenger)| {
-                        verify(&config, commitment, proof, &mut challenger)
+                    || (commitment.clone(), challenger.clone()),
+                    |(commitment, mut challenger)| {
+                        verify(&config, commitment, &proof, &mut challenger)
                     },
                     criterion::BatchSize::SmallInput,
                 );
@@ -85,7 +86,36 @@ impl_bench_field!(
     test_gl_challenger
 );
 
+// Compares the point-by-point and packed evaluation of a polynomial of the size
+// of a typical Ans_i at the preimages of the queried points, which is the
+// innermost consistency check of the verifier
+fn bench_query_evaluation(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Query-evaluation-BabyBear");
+
+    let polynomial = rand_poly::<BbExt>(63);
+    let mut rng = rand::rng();
+
+    for log_num_points in [6, 10] {
+        let points: Vec<BbExt> = (0..1 << log_num_points).map(|_| rng.random()).collect();
+
+        group.bench_function(BenchmarkId::new("scalar", log_num_points), |b| {
+            b.iter(|| {
+                points
+                    .iter()
+                    .map(|x| polynomial.evaluate(x))
+                    .collect::<Vec<_>>()
+            });
+        });
+
+        group.bench_function(BenchmarkId::new("packed", log_num_points), |b| {
+            b.iter(|| polynomial.evaluate_packed::<Bb>(&points));
+        });
+    }
+}
+
 fn bench(c: &mut Criterion) {
+    bench_query_evaluation(c);
+
     for log_degree in (14..=22).step_by(2) {
         bench_bb_with_log_degree(c, log_degree);
         bench_gl_with_log_degree(c, log_degree);
@@ -0,0 +1,33 @@
+//! Deterministic construction of the default challenger from a seed, so that
+//! provers and verifiers (possibly in different implementations or processes)
+//! can agree on a Fiat-Shamir transcript by sharing only the seed.
+
+use p3_baby_bear::{BabyBear, Poseidon2BabyBear};
+use p3_challenger::DuplexChallenger;
+use p3_keccak::Keccak256Hash;
+use p3_symmetric::CryptographicHasher;
+use rand::SeedableRng;
+use rand_chacha::ChaCha20Rng;
+
+/// The Poseidon2 permutation over BabyBear underlying [`BbChallenger`]
+pub type BbPerm = Poseidon2BabyBear<16>;
+
+/// The default challenger: a Poseidon2 duplex challenger over BabyBear, which
+/// can sample elements of any of its extensions
+pub type BbChallenger = DuplexChallenger<BabyBear, BbPerm, 16, 8>;
+
+/// Deterministically builds the default challenger ([`BbChallenger`]) from
+/// arbitrary seed bytes, e. g. those passed on a command line or shared with
+/// another implementation. The bytes are hashed with Keccak-256 into the seed
+/// of the ChaCha20 generator from which the Poseidon2 permutation is drawn, so
+/// that seeds of any length are accepted and equal seeds give identical
+/// challengers.
+///
+/// # Parameters
+///
+/// - `seed`: Bytes from which the challenger is derived
+pub fn challenger_from_seed(seed: &[u8]) -> BbChallenger {
+    let mut rng = ChaCha20Rng::from_seed(Keccak256Hash {}.hash_iter(seed.iter().copied()));
+    let perm = BbPerm::new_from_rng_128(&mut rng);
+    BbChallenger::new(perm)
+}
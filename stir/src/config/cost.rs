@@ -0,0 +1,156 @@
+use core::ops::{Add, AddAssign};
+
+use super::StirConfig;
+
+/// Estimated number of operations performed by the verifier on an honest
+/// proof, as computed by [`StirConfig::verifier_op_estimate`].
+///
+/// The estimate is an upper bound obtained from the configuration alone (e.
+/// g. it assumes all queried indices are distinct) and is meant for comparing
+/// configurations and estimating costs in advance, not as an exact count.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct VerifierCost {
+    /// Multiplications in the (extension) field. Additions are not counted.
+    pub multiplications: usize,
+
+    /// Invocations of the MMCS hasher or compressor when verifying the
+    /// Merkle proofs of the queried evaluations. The hashing performed by the
+    /// challenger is not counted.
+    pub hashes: usize,
+
+    /// Field inversions, after batching.
+    pub inversions: usize,
+}
+
+impl Add for VerifierCost {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        VerifierCost {
+            multiplications: self.multiplications + rhs.multiplications,
+            hashes: self.hashes + rhs.hashes,
+            inversions: self.inversions + rhs.inversions,
+        }
+    }
+}
+
+impl AddAssign for VerifierCost {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl<M: Clone> StirConfig<M> {
+    /// Estimate the number of field multiplications, hashes and field
+    /// inversions performed by the verifier for this configuration. The
+    /// dominating terms are the evaluation of the virtual oracles at the
+    /// queried points and the verification of the Merkle proofs.
+    pub fn verifier_op_estimate(&self) -> VerifierCost {
+        let mut cost = VerifierCost::default();
+
+        // log2 of the size of L_{i - 1}
+        let mut log_domain_size = self.starting_domain_log_size();
+
+        // Size of the quotient set of the oracle f_{i - 1} (zero for the
+        // transparent oracle f_0)
+        let mut quotient_set_size = 0;
+
+        for round_config in self.round_configs() {
+            let num_queries = round_config.num_queries;
+            let log_folding_factor = round_config.log_folding_factor;
+
+            cost += query_cost(
+                (log_domain_size, log_folding_factor),
+                self.log_rows_per_fold_group(log_folding_factor),
+                num_queries,
+                quotient_set_size,
+            );
+
+            // Ans_i interpolates the replies at the out-of-domain and in-domain
+            // queried points, and is checked against all of them using the
+            // shake polynomial (two evaluations, a batch inversion and one
+            // multiplication per point), the out-of-domain replies also being
+            // checked directly
+            quotient_set_size = round_config.num_ood_samples + num_queries;
+
+            cost += VerifierCost {
+                multiplications: (round_config.num_ood_samples + 2) * quotient_set_size
+                    + 4 * quotient_set_size,
+                hashes: 0,
+                inversions: 1,
+            };
+
+            log_domain_size -= 1;
+        }
+
+        // Final round: in addition to the queries, the final polynomial is
+        // evaluated at each folded queried point
+        let num_queries = self.final_num_queries();
+        let log_folding_factor = self.log_last_folding_factor();
+
+        cost += query_cost(
+            (log_domain_size, log_folding_factor),
+            self.log_rows_per_fold_group(log_folding_factor),
+            num_queries,
+            quotient_set_size,
+        );
+
+        cost += VerifierCost {
+            multiplications: num_queries * (self.final_degree_bound() + log_folding_factor),
+            hashes: 0,
+            inversions: 0,
+        };
+
+        cost
+    }
+}
+
+// Cost of processing the queries of one round: verifying the Merkle proofs of
+// the opened fold groups of g_{i - 1}, computing the values of f_{i - 1} over
+// them and folding those
+fn query_cost(
+    // log2 of the sizes of the domain L_{i - 1} and of the folding factor
+    // k_{i - 1}
+    (log_domain_size, log_folding_factor): (usize, usize),
+    // log2 of the number of leaves each fold group is split into
+    log_rows_per_group: usize,
+    // Number of queried fold groups
+    num_queries: usize,
+    // Size of the quotient set of the virtual oracle f_{i - 1}, zero if the
+    // oracle is transparent
+    quotient_set_size: usize,
+) -> VerifierCost {
+    let folding_factor = 1 << log_folding_factor;
+    let num_points = num_queries * folding_factor;
+
+    // Each leaf is hashed and its path compressed up to the root
+    let log_height = log_domain_size - log_folding_factor + log_rows_per_group;
+    let hashes = num_queries * (1 << log_rows_per_group) * (1 + log_height);
+
+    // Computing the roots of each queried point
+    let mut multiplications = num_queries * log_domain_size + num_points;
+    let mut inversions = 0;
+
+    if quotient_set_size > 0 {
+        // For each point: the vanishing polynomial of the quotient set, the
+        // evaluation of Ans, the degree-correction factor and a few products,
+        // plus two batch inversions over all points
+        multiplications += num_points
+            * (2 * quotient_set_size
+                + (usize::BITS - quotient_set_size.leading_zeros()) as usize
+                + 3)
+            + 2 * 3 * num_points;
+        inversions += 2;
+    }
+
+    // Folding the values over each fold group, plus the batch inversion of the
+    // roots of the queried points and the inverses of 2 and omega
+    multiplications += 2 * num_points + 3 * num_queries;
+    inversions += 3;
+
+    VerifierCost {
+        multiplications,
+        hashes,
+        inversions,
+    }
+}
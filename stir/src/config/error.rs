@@ -0,0 +1,75 @@
+/// Error during the expansion of [`StirParameters`](crate::StirParameters)
+/// into a full [`StirConfig`](crate::StirConfig)
+#[derive(Debug, PartialEq)]
+pub enum ConfigError {
+    /// No folding factor is given, whereas one is needed for each round
+    /// (incl. the final one)
+    NoRounds,
+    /// The folding factor of round `round` (from 1 to `M + 1`) is `2^0`,
+    /// whereas the polynomial has to be folded with arity at least 2 in each
+    /// round
+    ZeroLogFoldingFactor { round: usize },
+    /// The per-round parameter `parameter` (e. g. `log_inv_rates`) contains
+    /// `got` entries instead of the `expected` ones, i. e. one per round or
+    /// one per full round depending on the parameter
+    ParameterCount {
+        parameter: &'static str,
+        expected: usize,
+        got: usize,
+    },
+    /// The size `2^(log_starting_degree + log_starting_inv_rate)` of the
+    /// initial domain `L_0` does not fit in a `usize`
+    DomainSizeOverflow {
+        log_starting_degree: usize,
+        log_starting_inv_rate: usize,
+    },
+    /// The initial domain `L_0`, of size `2^log_domain_size` with
+    /// `log_domain_size = log_starting_degree + log_starting_inv_rate`, is
+    /// larger than the largest two-adic subgroup of the field, of size
+    /// `2^two_adicity`
+    InsufficientTwoAdicity {
+        log_domain_size: usize,
+        two_adicity: usize,
+    },
+    /// The folding factors reduce the degree bound by a factor of
+    /// `2^total_reduction`, which is larger than the initial degree bound
+    /// `2^log_starting_degree`
+    ExcessiveFolding {
+        total_reduction: usize,
+        log_starting_degree: usize,
+    },
+    /// The inverse rates `log_inv_rates` of the codewords of the rounds are not
+    /// the ones determined by the initial inverse rate and the folding factors
+    InconsistentInvRates,
+    /// The exponent `e` of the root generator `w^e` is even, so that the latter
+    /// does not generate the initial domain `L_0`
+    InvalidRootGeneratorExponent { exponent: usize },
+    /// No number of out-of-domain samples below 64 meets the security level in
+    /// full round `round` (from 1 to `M`)
+    UnreachableOodSecurity { round: usize },
+    /// The leaf width `2^log_leaf_width` of the committed Merkle trees is
+    /// larger than the smallest folding factor `2^min_log_folding_factor`
+    InvalidLeafWidth {
+        log_leaf_width: usize,
+        min_log_folding_factor: usize,
+    },
+    /// The query slack (cf.
+    /// [`StirParameters::query_slack`](crate::StirParameters::query_slack)) is
+    /// negative or not finite, or inflates the number of queries of some round
+    /// beyond the range of `usize`
+    InvalidQuerySlack { query_slack: f64 },
+    /// The number of queries computed for round `round` (from 1 to `M + 1`,
+    /// the latter being the final round) is zero, which happens when the
+    /// proof-of-work bits cover the entire security level. Such a round would
+    /// not check the prover's codeword at all.
+    ZeroQueries { round: usize },
+    /// The proof-of-work bits scheduled for round `round` (from 1 to `M + 1`)
+    /// through [`StirParameters::query_pow_schedule`](crate::StirParameters::query_pow_schedule)
+    /// are fewer than the `required` ones, which are needed to meet the
+    /// security level given the number of queries scheduled for that round
+    InsufficientProofOfWork {
+        round: usize,
+        required: usize,
+        scheduled: usize,
+    },
+}
@@ -1,17 +1,29 @@
+use alloc::string::ToString;
 use alloc::vec::Vec;
 use alloc::{format, vec};
 use core::fmt::{Debug, Display, Formatter, Result};
+use core::iter;
 
 use itertools::Itertools;
 use p3_challenger::FieldChallenger;
-use p3_field::Field;
+use p3_coset::TwoAdicCoset;
+use p3_field::{Field, TwoAdicField};
+use p3_matrix::Dimensions;
+use p3_poly::fnv1a;
+use serde::{Deserialize, Serialize};
 
-use crate::utils::{compute_pow, observe_usize_slice};
-use crate::SecurityAssumption;
+use crate::utils::{checked_domain_size, compute_pow, observe_usize_slice};
+use crate::{initial_domain, SecurityAssumption};
+
+mod cost;
+mod error;
 
 #[cfg(test)]
 mod tests;
 
+pub use cost::VerifierCost;
+pub use error::ConfigError;
+
 /// STIR-related parameters chosen by the user. These get expanded into a full
 /// [`StirConfig`] by the function [`StirConfig::new`].
 ///
@@ -63,6 +75,71 @@ pub struct StirParameters<M: Clone> {
     /// Number of proof-of-work bits used to reduce the query error.
     pub pow_bits: usize,
 
+    /// log2 of the number of evaluations in each leaf of the committed Merkle
+    /// trees. The evaluations needed to verify each folding (i. e. those at
+    /// the `k_i`-th roots of a queried point) are spread over `k_i / 2^x`
+    /// consecutive leaves, where `x` is this value, so it can be at most the
+    /// log2 of the smallest folding factor. If `None`, each leaf contains
+    /// exactly the evaluations needed to verify one folding. Narrower leaves
+    /// mean cheaper hashing in exchange for more (and longer) Merkle proofs.
+    pub log_leaf_width: Option<usize>,
+
+    /// Exponent `e` such that the generator of the initial domain `L_0` (and
+    /// thus of all subsequent domains) is `w^e`, where `w` is the canonical
+    /// generator of the two-adic subgroup of that size (i. e. the output of
+    /// `F::two_adic_generator`). If `None`, `w` itself is used. This is set
+    /// through [`StirParameters::with_root_generator`].
+    pub root_generator_exponent: Option<usize>,
+
+    /// If `true`, the challenger is only squeezed for the initial folding
+    /// randomness `r_0`, which acts as a seed from which the folding randomness
+    /// of each subsequent round `i` is derived as `r_i = r_0^(2^i)`. This
+    /// saves one challenger invocation per round, which can matter for
+    /// constrained (e. g. recursive) verifiers. Note that the prover then knows
+    /// all folding randomness before committing to the round polynomials, a
+    /// setting not covered by the soundness analysis of the protocol. This is
+    /// set through [`StirParameters::with_batched_folding_randomness`].
+    pub batched_folding_randomness: bool,
+
+    /// Fraction by which the number of queries of each round (incl. the final
+    /// one) is inflated beyond the minimum dictated by the security analysis,
+    /// as a safety margin: a round requiring `q` queries makes
+    /// `ceil(q * (1 + query_slack))` of them instead. The proof-of-work bits are
+    /// still derived from the minimum number of queries, so that the extra
+    /// queries are not traded for less grinding. Defaults to `0.0` and is set
+    /// through [`StirParameters::with_query_slack`].
+    pub query_slack: f64,
+
+    /// Optional explicit number of queries and proof-of-work bits for each
+    /// round `i = 1, ..., M + 1` (the latter being the final round), given as
+    /// `(num_queries, pow_bits)` pairs. This allows trading queries for
+    /// grinding: a round may make fewer queries than dictated by the security
+    /// analysis as long as its proof-of-work bits compensate for the larger
+    /// query error, which is checked by [`StirConfig::new`]. The query slack is
+    /// not applied to scheduled rounds. Defaults to `None` (the numbers of
+    /// queries and proof-of-work bits are derived from the security level) and
+    /// is set through [`StirParameters::with_query_pow_schedule`].
+    pub query_pow_schedule: Option<Vec<(usize, usize)>>,
+
+    /// Optional explicit number of out-of-domain samples (and thus of replies
+    /// `beta_{i, j}` in the proof) for each full round `i = 1, ..., M`. More
+    /// samples reduce the out-of-domain error at the cost of a larger proof
+    /// and a larger degree of `Ans_i`, whereas fewer ones than dictated by the
+    /// security analysis weaken the soundness of the round (which is not
+    /// checked). Defaults to `None` (the numbers are derived from the security
+    /// level) and is set through [`StirParameters::with_num_ood_samples`].
+    pub num_ood_samples: Option<Vec<usize>>,
+
+    /// Optional lower bound on the log2 of the size of the evaluation domains
+    /// `L_1, ..., L_M` of the full rounds. Since `|L_i| = |L_0| / 2^i`, this
+    /// caps the number of full rounds: [`StirConfig::new`] drops the rounds
+    /// whose domain would fall below `2^min_log_domain`, sending the current
+    /// polynomial in plain as the final one instead (as in
+    /// [`StirConfig::truncate_rounds`]). Defaults to `None` (all folding
+    /// factors are used) and is set through
+    /// [`StirParameters::with_min_log_domain`].
+    pub min_log_domain: Option<usize>,
+
     /// Configuration of the Mixed Matrix Commitment Scheme (hasher and
     /// compressor) used to commit to the initial polynomial `f_0` and round
     /// polynomials `g_1, ... g_M`.
@@ -98,19 +175,7 @@ impl<M: Clone> StirParameters<M> {
         pow_bits: usize,
         mmcs_config: M,
     ) -> Self {
-        // With each subsequent round, the size of the evaluation domain is
-        // decreased by a factor of 2 whereas the degree-plus-1 bound of the
-        // polynomial is decreased by a factor of 2^log_folding_factor. Thus,
-        // the logarithm of the inverse of the rate increases by log_k - 1.
-        let mut i_th_log_rate = log_starting_inv_rate;
-
-        let log_inv_rates = log_folding_factors
-            .iter()
-            .map(|log_k| {
-                i_th_log_rate = i_th_log_rate + log_k - 1;
-                i_th_log_rate
-            })
-            .collect();
+        let log_inv_rates = compute_log_inv_rates(log_starting_inv_rate, &log_folding_factors);
 
         StirParameters {
             log_starting_degree,
@@ -120,6 +185,13 @@ impl<M: Clone> StirParameters<M> {
             security_assumption,
             security_level,
             pow_bits,
+            log_leaf_width: None,
+            root_generator_exponent: None,
+            batched_folding_randomness: false,
+            query_slack: 0.0,
+            query_pow_schedule: None,
+            num_ood_samples: None,
+            min_log_domain: None,
             mmcs_config,
         }
     }
@@ -162,6 +234,342 @@ impl<M: Clone> StirParameters<M> {
             mmcs_config,
         )
     }
+
+    /// Set the log2 of the number of evaluations in each leaf of the committed
+    /// Merkle trees. Cf. [`StirParameters::log_leaf_width`] for more details.
+    pub fn with_log_leaf_width(self, log_leaf_width: usize) -> Self {
+        StirParameters {
+            log_leaf_width: Some(log_leaf_width),
+            ..self
+        }
+    }
+
+    /// Use `generator` as the generator of the initial domain `L_0` (and,
+    /// through its powers, of all subsequent domains) instead of the canonical
+    /// one provided by `F::two_adic_generator`. This determines the order in
+    /// which the evaluations of the initial polynomial are committed to, which
+    /// can be used to match a commitment produced by an external scheme. `F`
+    /// must be the field the configuration is then instantiated with in
+    /// [`StirConfig::new`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `generator` does not have multiplicative order equal to the
+    /// size `2^(log_starting_degree + log_starting_inv_rate)` of `L_0`.
+    pub fn with_root_generator<F: TwoAdicField>(self, generator: F) -> Self {
+        let log_size = self.log_starting_degree + self.log_starting_inv_rate;
+        let domain = TwoAdicCoset::new_with_generator(generator, generator, log_size);
+
+        StirParameters {
+            root_generator_exponent: Some(domain.generator_exponent()),
+            ..self
+        }
+    }
+
+    /// Inflate the number of queries of each round by the fraction
+    /// `query_slack` (e. g. `0.1` for 10% more queries). Cf.
+    /// [`StirParameters::query_slack`] for more details.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `query_slack` is negative or not finite.
+    pub fn with_query_slack(self, query_slack: f64) -> Self {
+        assert!(
+            query_slack.is_finite() && query_slack >= 0.0,
+            "The query slack must be a non-negative finite number"
+        );
+
+        StirParameters {
+            query_slack,
+            ..self
+        }
+    }
+
+    /// Fix the number of queries and proof-of-work bits of each round
+    /// `i = 1, ..., M + 1` to the given `(num_queries, pow_bits)` pairs. Cf.
+    /// [`StirParameters::query_pow_schedule`] for more details.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `schedule` does not contain exactly one pair per round.
+    pub fn with_query_pow_schedule(self, schedule: Vec<(usize, usize)>) -> Self {
+        assert_eq!(
+            schedule.len(),
+            self.log_folding_factors.len(),
+            "The query/proof-of-work schedule must contain one entry per round"
+        );
+
+        StirParameters {
+            query_pow_schedule: Some(schedule),
+            ..self
+        }
+    }
+
+    /// Stop folding once the degree-plus-1 bound reaches `2^log_stopping_degree`
+    /// and send the remaining polynomial in plain from then on, trading proof
+    /// size for fewer rounds. The folding factors are used in order (the last
+    /// one being repeated if they do not suffice) and the one of the last
+    /// round is reduced if needed, so that the degree bound of the final
+    /// polynomial is exactly `2^log_stopping_degree`. The inverse rates are
+    /// recomputed accordingly.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `log_stopping_degree` is not smaller than
+    /// `log_starting_degree` (at least one folding is needed) or if a
+    /// query/proof-of-work schedule has been set, since the number of rounds
+    /// may change.
+    pub fn with_log_stopping_degree(self, log_stopping_degree: usize) -> Self {
+        assert!(
+            log_stopping_degree < self.log_starting_degree,
+            "The stopping degree must be smaller than the starting degree"
+        );
+        assert!(
+            self.query_pow_schedule.is_none(),
+            "The stopping degree must be set before the query/proof-of-work schedule"
+        );
+        assert!(
+            self.num_ood_samples.is_none(),
+            "The stopping degree must be set before the number of out-of-domain samples"
+        );
+
+        let mut remaining_reduction = self.log_starting_degree - log_stopping_degree;
+        let last_log_folding_factor = *self.log_folding_factors.last().unwrap();
+
+        let log_folding_factors = self
+            .log_folding_factors
+            .iter()
+            .copied()
+            .chain(iter::repeat(last_log_folding_factor))
+            .map_while(|log_k| {
+                let log_k = log_k.min(remaining_reduction);
+                remaining_reduction -= log_k;
+                (log_k > 0).then_some(log_k)
+            })
+            .collect_vec();
+
+        let log_inv_rates = compute_log_inv_rates(self.log_starting_inv_rate, &log_folding_factors);
+
+        StirParameters {
+            log_folding_factors,
+            log_inv_rates,
+            ..self
+        }
+    }
+
+    /// Fix the number of out-of-domain samples of each full round
+    /// `i = 1, ..., M`. Cf. [`StirParameters::num_ood_samples`] for more
+    /// details.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `num_ood_samples` does not contain exactly one entry per full
+    /// round.
+    pub fn with_num_ood_samples(self, num_ood_samples: Vec<usize>) -> Self {
+        assert_eq!(
+            num_ood_samples.len(),
+            self.log_folding_factors.len() - 1,
+            "The number of out-of-domain samples must be given for each full round"
+        );
+
+        StirParameters {
+            num_ood_samples: Some(num_ood_samples),
+            ..self
+        }
+    }
+
+    /// Derive the folding randomness of all rounds from a single challenger
+    /// squeeze. Cf. [`StirParameters::batched_folding_randomness`] for more
+    /// details.
+    pub fn with_batched_folding_randomness(self) -> Self {
+        StirParameters {
+            batched_folding_randomness: true,
+            ..self
+        }
+    }
+
+    /// Stop adding full rounds once their evaluation domain would have fewer
+    /// than `2^min_log_domain` elements. Cf.
+    /// [`StirParameters::min_log_domain`] for more details.
+    pub fn with_min_log_domain(self, min_log_domain: usize) -> Self {
+        StirParameters {
+            min_log_domain: Some(min_log_domain),
+            ..self
+        }
+    }
+
+    // Keeps the first n rounds only (i. e. the first n - 1 full rounds), the
+    // n-th becoming the final one
+    fn truncate_rounds(&mut self, n: usize) {
+        self.log_folding_factors.truncate(n);
+        self.log_inv_rates.truncate(n);
+
+        // The final round only needs to compensate for the query error, so a
+        // valid schedule remains valid when its n-th round becomes final
+        if let Some(schedule) = self.query_pow_schedule.as_mut() {
+            schedule.truncate(n);
+        }
+
+        // The n-th round becomes final and thus samples no out-of-domain points
+        if let Some(num_ood_samples) = self.num_ood_samples.as_mut() {
+            num_ood_samples.truncate(n - 1);
+        }
+    }
+
+    /// Returns the parameters other than the MMCS configuration, which can be
+    /// serialised (e. g. to be stored alongside proofs, cf.
+    /// [`StirProofEnvelope`](crate::StirProofEnvelope)) and turned back into
+    /// parameters with [`StirParametersSpec::with_mmcs_config`].
+    pub fn spec(&self) -> StirParametersSpec {
+        let StirParameters {
+            security_level,
+            security_assumption,
+            log_starting_degree,
+            log_folding_factors,
+            log_starting_inv_rate,
+            log_inv_rates,
+            pow_bits,
+            log_leaf_width,
+            root_generator_exponent,
+            batched_folding_randomness,
+            query_slack,
+            query_pow_schedule,
+            num_ood_samples,
+            min_log_domain,
+            mmcs_config: _,
+        } = self.clone();
+
+        StirParametersSpec {
+            security_level,
+            security_assumption,
+            log_starting_degree,
+            log_folding_factors,
+            log_starting_inv_rate,
+            log_inv_rates,
+            pow_bits,
+            log_leaf_width,
+            root_generator_exponent,
+            batched_folding_randomness,
+            query_slack,
+            query_pow_schedule,
+            num_ood_samples,
+            min_log_domain,
+        }
+    }
+}
+
+/// Serialisable counterpart of [`StirParameters`] containing all parameters
+/// but the MMCS configuration, which usually cannot be serialised (it may
+/// contain e. g. the round constants of a hash function). It is obtained with
+/// [`StirParameters::spec`] and the fields have the same meaning as those of
+/// [`StirParameters`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StirParametersSpec {
+    /// Cf. [`StirParameters::security_level`].
+    pub security_level: usize,
+
+    /// Cf. [`StirParameters::security_assumption`].
+    pub security_assumption: SecurityAssumption,
+
+    /// Cf. [`StirParameters::log_starting_degree`].
+    pub log_starting_degree: usize,
+
+    /// Cf. [`StirParameters::log_folding_factors`].
+    pub log_folding_factors: Vec<usize>,
+
+    /// Cf. [`StirParameters::log_starting_inv_rate`].
+    pub log_starting_inv_rate: usize,
+
+    /// Cf. [`StirParameters::log_inv_rates`].
+    pub log_inv_rates: Vec<usize>,
+
+    /// Cf. [`StirParameters::pow_bits`].
+    pub pow_bits: usize,
+
+    /// Cf. [`StirParameters::log_leaf_width`].
+    pub log_leaf_width: Option<usize>,
+
+    /// Cf. [`StirParameters::root_generator_exponent`].
+    pub root_generator_exponent: Option<usize>,
+
+    /// Cf. [`StirParameters::batched_folding_randomness`].
+    pub batched_folding_randomness: bool,
+
+    /// Cf. [`StirParameters::query_slack`].
+    pub query_slack: f64,
+
+    /// Cf. [`StirParameters::query_pow_schedule`].
+    pub query_pow_schedule: Option<Vec<(usize, usize)>>,
+
+    /// Cf. [`StirParameters::num_ood_samples`].
+    pub num_ood_samples: Option<Vec<usize>>,
+
+    /// Cf. [`StirParameters::min_log_domain`].
+    pub min_log_domain: Option<usize>,
+}
+
+impl StirParametersSpec {
+    /// Completes the specification into parameters using the given MMCS
+    /// configuration, which should be the one the original parameters were
+    /// created with.
+    pub fn with_mmcs_config<M: Clone>(self, mmcs_config: M) -> StirParameters<M> {
+        let StirParametersSpec {
+            security_level,
+            security_assumption,
+            log_starting_degree,
+            log_folding_factors,
+            log_starting_inv_rate,
+            log_inv_rates,
+            pow_bits,
+            log_leaf_width,
+            root_generator_exponent,
+            batched_folding_randomness,
+            query_slack,
+            query_pow_schedule,
+            num_ood_samples,
+            min_log_domain,
+        } = self;
+
+        StirParameters {
+            security_level,
+            security_assumption,
+            log_starting_degree,
+            log_folding_factors,
+            log_starting_inv_rate,
+            log_inv_rates,
+            pow_bits,
+            log_leaf_width,
+            root_generator_exponent,
+            batched_folding_randomness,
+            query_slack,
+            query_pow_schedule,
+            num_ood_samples,
+            min_log_domain,
+            mmcs_config,
+        }
+    }
+}
+
+// Compute log2 of the inverses of the rates of the codewords of all rounds.
+// With each subsequent round, the size of the evaluation domain is decreased by
+// a factor of 2 whereas the degree-plus-1 bound of the polynomial is decreased
+// by a factor of 2^log_folding_factor. Thus, the logarithm of the inverse of
+// the rate increases by log_k - 1.
+fn compute_log_inv_rates(
+    // log2 of the inverse of the rate of the initial codeword
+    log_starting_inv_rate: usize,
+    // log2 of the folding factors of all rounds
+    log_folding_factors: &[usize],
+) -> Vec<usize> {
+    let mut i_th_log_rate = log_starting_inv_rate;
+
+    log_folding_factors
+        .iter()
+        .map(|log_k| {
+            i_th_log_rate = i_th_log_rate + log_k - 1;
+            i_th_log_rate
+        })
+        .collect()
 }
 
 /// Configuration parameters specific to one round of STIR.
@@ -225,7 +633,52 @@ pub struct StirConfig<M: Clone> {
 
 impl<M: Clone> StirConfig<M> {
     /// Expand STIR parameters into a full STIR configuration.
-    pub fn new<F: Field>(parameters: StirParameters<M>) -> Self {
+    ///
+    /// `F` is the field over which the protocol runs (typically an extension
+    /// of the challenger's field) and which hosts all evaluation domains: only
+    /// `F` needs to be two-adic, not its base field (which, as in the case of
+    /// Mersenne31, may have two-adicity as low as 1). Note that the challenger
+    /// must nonetheless be able to sample `log_starting_degree +
+    /// log_starting_inv_rate` bits and grind the resulting proof-of-work bits,
+    /// which limits both to 30 bits over Mersenne31.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ConfigError::NoRounds`],
+    /// [`ConfigError::ZeroLogFoldingFactor`], [`ConfigError::ParameterCount`],
+    /// [`ConfigError::ExcessiveFolding`], [`ConfigError::InconsistentInvRates`]
+    /// or [`ConfigError::InvalidRootGeneratorExponent`] if the parameters are
+    /// inconsistent (which the constructors and setters of
+    /// [`StirParameters`] prevent, but a deserialised
+    /// [`StirParametersSpec`] may not), [`ConfigError::DomainSizeOverflow`] if
+    /// the size of the initial domain `L_0` does not fit in a `usize`,
+    /// [`ConfigError::InsufficientTwoAdicity`] if the initial domain
+    /// `L_0` (which is the largest domain used by the protocol) does not fit in
+    /// the largest two-adic subgroup of `F`, [`ConfigError::InvalidLeafWidth`]
+    /// if the leaf width exceeds the smallest folding factor,
+    /// [`ConfigError::InvalidQuerySlack`] if the query slack is negative, not
+    /// finite or inflates the number of queries of some round beyond the
+    /// range of `usize`, [`ConfigError::ZeroQueries`] if some round would make
+    /// no queries,
+    /// [`ConfigError::UnreachableOodSecurity`] if some round cannot meet the
+    /// security level with any reasonable number of out-of-domain samples and
+    /// [`ConfigError::InsufficientProofOfWork`] if some round of the
+    /// query/proof-of-work schedule does not grind enough to meet the security
+    /// level.
+    pub fn new<F: TwoAdicField>(mut parameters: StirParameters<M>) -> Result<Self, ConfigError> {
+        // Drop the full rounds whose domain L_i, of log size log|L_0| - i,
+        // would be smaller than the lower bound
+        if let Some(min_log_domain) = parameters.min_log_domain {
+            let max_full_rounds = parameters
+                .log_starting_degree
+                .saturating_add(parameters.log_starting_inv_rate)
+                .saturating_sub(min_log_domain);
+
+            if max_full_rounds.saturating_add(1) < parameters.log_folding_factors.len() {
+                parameters.truncate_rounds(max_full_rounds + 1);
+            }
+        }
+
         let StirParameters {
             security_level,
             security_assumption,
@@ -234,27 +687,114 @@ impl<M: Clone> StirConfig<M> {
             log_starting_inv_rate,
             log_inv_rates,
             pow_bits,
+            log_leaf_width,
+            root_generator_exponent,
+            query_slack,
+            query_pow_schedule,
+            num_ood_samples: fixed_num_ood_samples,
             ..
         } = parameters.clone();
 
+        if log_folding_factors.is_empty() {
+            return Err(ConfigError::NoRounds);
+        }
+
         // The polynomial has to be folded with arity at least 2 in each round
-        assert!(
-            log_folding_factors.iter().all(|&x| x != 0),
-            "The logarithm of each folding factor should be positive"
-        );
-        assert_eq!(log_folding_factors.len(), log_inv_rates.len());
+        if let Some(i) = log_folding_factors.iter().position(|&x| x == 0) {
+            return Err(ConfigError::ZeroLogFoldingFactor { round: i + 1 });
+        }
+
+        let num_rounds = log_folding_factors.len();
+
+        for (parameter, expected, got) in [
+            ("log_inv_rates", num_rounds, Some(log_inv_rates.len())),
+            (
+                "num_ood_samples",
+                num_rounds - 1,
+                fixed_num_ood_samples.as_ref().map(Vec::len),
+            ),
+            (
+                "query_pow_schedule",
+                num_rounds,
+                query_pow_schedule.as_ref().map(Vec::len),
+            ),
+        ] {
+            if let Some(got) = got.filter(|&got| got != expected) {
+                return Err(ConfigError::ParameterCount {
+                    parameter,
+                    expected,
+                    got,
+                });
+            }
+        }
+
+        if !(query_slack.is_finite() && query_slack >= 0.0) {
+            return Err(ConfigError::InvalidQuerySlack { query_slack });
+        }
+
+        // The sizes of all domains and degree bounds used by the protocol are
+        // at most that of L_0, so checking the latter once here guarantees
+        // that none of them overflows later on
+        let log_domain_size = log_starting_degree
+            .checked_add(log_starting_inv_rate)
+            .filter(|&log_size| checked_domain_size(log_size).is_some())
+            .ok_or(ConfigError::DomainSizeOverflow {
+                log_starting_degree,
+                log_starting_inv_rate,
+            })?;
+
+        // All domains L_0, L_1, ... are cosets of two-adic subgroups of the
+        // field, the largest one being L_0. If it does not fit, the DFT used to
+        // compute the initial codeword would panic.
+        if log_domain_size > F::TWO_ADICITY {
+            return Err(ConfigError::InsufficientTwoAdicity {
+                log_domain_size,
+                two_adicity: F::TWO_ADICITY,
+            });
+        }
+
+        // Each leaf must contain a fraction of the evaluations needed to verify
+        // the folding in every round
+        let min_log_folding_factor = *log_folding_factors.iter().min().unwrap();
+        if let Some(log_leaf_width) = log_leaf_width {
+            if log_leaf_width > min_log_folding_factor {
+                return Err(ConfigError::InvalidLeafWidth {
+                    log_leaf_width,
+                    min_log_folding_factor,
+                });
+            }
+        }
 
         // log2(degree + 1) can not be reduced past 0. This also ensures the
         // domain is large enough to be shrunk by raising it to all of the
-        // subsequent folding factors iteratively.
-        let total_reduction = log_folding_factors.iter().sum::<usize>();
-        assert!(total_reduction <= log_starting_degree);
+        // subsequent folding factors iteratively, and in particular that the
+        // first round does not reduce the degree more than possible (in which
+        // case one should send the polynomial directly instead).
+        let total_reduction = log_folding_factors
+            .iter()
+            .fold(0_usize, |total, &x| total.saturating_add(x));
 
-        let log_starting_folding_factor = log_folding_factors[0];
+        if total_reduction > log_starting_degree {
+            return Err(ConfigError::ExcessiveFolding {
+                total_reduction,
+                log_starting_degree,
+            });
+        }
 
-        // If the first round wants to reduce the degree more than possible, one
-        // should send the polynomial directly instead
-        assert!(log_starting_degree >= log_starting_folding_factor);
+        // The security of each round is derived from the rate of its codeword,
+        // which must therefore match the domain sizes actually used
+        if log_inv_rates != compute_log_inv_rates(log_starting_inv_rate, &log_folding_factors) {
+            return Err(ConfigError::InconsistentInvRates);
+        }
+
+        // w^e generates the same subgroup as w if and only if e is odd
+        if let Some(exponent) = root_generator_exponent {
+            if log_domain_size > 0 && exponent.is_multiple_of(2) {
+                return Err(ConfigError::InvalidRootGeneratorExponent { exponent });
+            }
+        }
+
+        let log_starting_folding_factor = log_folding_factors[0];
 
         // Compute the log of (final-degree-plus-1 bound) as well as the number
         // of (non-final) rounds
@@ -263,11 +803,7 @@ impl<M: Clone> StirConfig<M> {
 
         // Compute the security level afforded by the actual protocol without
         // grinding
-        let protocol_security_level = 0.max(security_level - pow_bits);
-
-        // Initial domain size
-        let starting_domain_log_size =
-            parameters.log_starting_degree + parameters.log_starting_inv_rate;
+        let protocol_security_level = security_level.saturating_sub(pow_bits);
 
         // Degree of next polynomial to send
         let mut current_log_degree = log_starting_degree - log_starting_folding_factor;
@@ -291,8 +827,10 @@ impl<M: Clone> StirConfig<M> {
         // If folding factors has length (i. e. num_rounds) 1, the only round is
         // by definition the last one, which is treated separately; In that
         // case, windows(2) returns no elements, as desired.
-        for (log_folding_factor_pair, next_rate) in
-            log_folding_factors.windows(2).zip(log_inv_rates)
+        for (i, (log_folding_factor_pair, next_rate)) in log_folding_factors
+            .windows(2)
+            .zip(log_inv_rates)
+            .enumerate()
         {
             let (log_curr_folding_factor, log_next_folding_factor) =
                 (log_folding_factor_pair[0], log_folding_factor_pair[1]);
@@ -300,22 +838,34 @@ impl<M: Clone> StirConfig<M> {
             // This is the size of the new evaluation domain
             let new_evaluation_domain_size = current_log_degree + next_rate;
 
-            // Compute the ood samples required
-            let num_ood_samples = security_assumption.determine_ood_samples(
-                security_level,
-                current_log_degree,
-                next_rate,
-                field_bits,
-            );
+            // Compute the ood samples required, unless fixed
+            let num_ood_samples = match &fixed_num_ood_samples {
+                Some(fixed_num_ood_samples) => fixed_num_ood_samples[i],
+                None => security_assumption
+                    .try_determine_ood_samples(
+                        security_level,
+                        current_log_degree,
+                        next_rate,
+                        field_bits,
+                    )
+                    .ok_or(ConfigError::UnreachableOodSecurity { round: i + 1 })?,
+            };
 
-            // Compute the number of queries required
-            let num_queries = security_assumption.queries(protocol_security_level, log_inv_rate);
+            // Compute the number of queries required, unless scheduled
+            let num_queries = match &query_pow_schedule {
+                Some(schedule) => schedule[i].0,
+                None => security_assumption.queries(protocol_security_level, log_inv_rate),
+            };
+
+            if num_queries == 0 {
+                return Err(ConfigError::ZeroQueries { round: i + 1 });
+            }
 
             // We need to compute the three errors from which the number of
             // proof-of-work bits is derived
             let query_error = security_assumption.queries_error(log_inv_rate, num_queries);
 
-            let num_terms = num_queries + num_ood_samples;
+            let num_terms = num_queries.saturating_add(num_ood_samples);
             let prox_gaps_error_1 = parameters.security_assumption.prox_gaps_error(
                 current_log_degree,
                 next_rate,
@@ -331,12 +881,24 @@ impl<M: Clone> StirConfig<M> {
             );
 
             // Now compute the proof-of-work bits
-            let pow_bits = compute_pow(
+            let required_pow_bits = compute_pow(
                 security_level,
                 query_error.min(prox_gaps_error_1).min(prox_gaps_error_2),
             )
             .ceil() as usize;
 
+            let (num_queries, pow_bits) = match &query_pow_schedule {
+                Some(schedule) => (
+                    num_queries,
+                    check_scheduled_pow_bits(i + 1, required_pow_bits, schedule[i].1)?,
+                ),
+                None => (
+                    apply_query_slack(num_queries, query_slack)
+                        .ok_or(ConfigError::InvalidQuerySlack { query_slack })?,
+                    required_pow_bits,
+                ),
+            };
+
             let round_config = RoundConfig {
                 log_evaluation_domain_size: new_evaluation_domain_size,
                 log_folding_factor: log_curr_folding_factor,
@@ -353,10 +915,21 @@ impl<M: Clone> StirConfig<M> {
             current_log_degree -= log_curr_folding_factor;
         }
 
-        // Compute the number of queries required
-        let final_num_queries = parameters
-            .security_assumption
-            .queries(protocol_security_level, log_inv_rate);
+        // Compute the number of queries required, unless scheduled
+        let final_num_queries = match &query_pow_schedule {
+            Some(schedule) => schedule[log_folding_factors.len() - 1].0,
+            None => parameters
+                .security_assumption
+                .queries(protocol_security_level, log_inv_rate),
+        };
+
+        // The final round has index M + 1, which is the number of folding
+        // factors
+        if final_num_queries == 0 {
+            return Err(ConfigError::ZeroQueries {
+                round: log_folding_factors.len(),
+            });
+        }
 
         // We need to compute the three errors from which the final number of
         // proof-of-work bits is derived
@@ -365,18 +938,63 @@ impl<M: Clone> StirConfig<M> {
             .queries_error(log_inv_rate, final_num_queries);
 
         // Now compute actual number of final proof-of-work bits
-        let final_pow_bits = compute_pow(security_level, query_error).ceil() as usize;
-
-        StirConfig {
+        let required_final_pow_bits = compute_pow(security_level, query_error).ceil() as usize;
+
+        let (final_num_queries, final_pow_bits) = match &query_pow_schedule {
+            Some(schedule) => (
+                final_num_queries,
+                check_scheduled_pow_bits(
+                    log_folding_factors.len(),
+                    required_final_pow_bits,
+                    schedule[log_folding_factors.len() - 1].1,
+                )?,
+            ),
+            None => (
+                apply_query_slack(final_num_queries, query_slack)
+                    .ok_or(ConfigError::InvalidQuerySlack { query_slack })?,
+                required_final_pow_bits,
+            ),
+        };
+
+        Ok(StirConfig {
             parameters,
-            starting_domain_log_size,
+            starting_domain_log_size: log_domain_size,
             starting_folding_pow_bits,
             round_parameters,
             log_stopping_degree,
             log_final_inv_rate: log_inv_rate,
             final_num_queries,
             final_pow_bits,
-        }
+        })
+    }
+
+    /// Returns the configuration which performs the first `n` rounds of `self`
+    /// only: the first `n - 1` are full rounds identical to those of `self`,
+    /// whereas the `n`-th is a final round in which the polynomial `g_n` is
+    /// sent in plain instead of being committed to. This allows, for instance,
+    /// switching from STIR to direct low-degree checking after a few rounds.
+    /// The final degree bound of the output is therefore the degree bound of
+    /// `g_n` in `self`. `F` must be the field `self` was instantiated with in
+    /// [`StirConfig::new`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is zero or greater than the number of rounds of `self`,
+    /// `M + 1`.
+    pub fn truncate_rounds<F: TwoAdicField>(&self, n: usize) -> StirConfig<M> {
+        assert!(
+            (1..=self.total_rounds()).contains(&n),
+            "The number of rounds to keep must be between 1 and {}, got {n}",
+            self.total_rounds()
+        );
+
+        let mut parameters = self.parameters.clone();
+        parameters.truncate_rounds(n);
+
+        // The truncated parameters are at least as permissive as the original
+        // ones in every respect checked by new()
+        StirConfig::new::<F>(parameters)
+            .expect("Truncating a valid configuration yields a valid configuration")
     }
 
     /// User-defined parameters of the configuration.
@@ -384,6 +1002,80 @@ impl<M: Clone> StirConfig<M> {
         &self.parameters
     }
 
+    /// Compact fingerprint of the parameters of the configuration (all of them
+    /// except the MMCS configuration), which is embedded in the proofs
+    /// produced under it. The verifier compares the fingerprint of a proof to
+    /// that of its own configuration, so that a proof produced under a
+    /// different configuration is reported as such (with
+    /// `VerificationError::ParameterMismatch`) instead of failing some later
+    /// check. This is a 64-bit FNV-1a hash (cf. `p3_poly::fnv1a`), which is not meant to resist
+    /// deliberate collisions: soundness does not rely on it, since the
+    /// parameters are bound to the transcript as well.
+    pub fn parameter_fingerprint(&self) -> u64 {
+        let StirParameters {
+            security_level,
+            security_assumption,
+            log_starting_degree,
+            log_folding_factors,
+            log_starting_inv_rate,
+            log_inv_rates,
+            pow_bits,
+            log_leaf_width,
+            root_generator_exponent,
+            batched_folding_randomness,
+            query_slack,
+            query_pow_schedule,
+            num_ood_samples,
+            // Its effect is reflected in the (truncated) folding factors
+            min_log_domain: _,
+            mmcs_config: _,
+        } = &self.parameters;
+
+        let mut words = vec![
+            *security_level as u64,
+            *security_assumption as u64,
+            *log_starting_degree as u64,
+            *log_starting_inv_rate as u64,
+            *pow_bits as u64,
+            *batched_folding_randomness as u64,
+            query_slack.to_bits(),
+        ];
+
+        // Lists are prefixed by their length and optional values by whether
+        // they are present, so that different parameters cannot result in the
+        // same sequence of words
+        let push_list = |words: &mut Vec<u64>, values: &[usize]| {
+            words.push(values.len() as u64);
+            words.extend(values.iter().map(|&value| value as u64));
+        };
+
+        push_list(&mut words, log_folding_factors);
+        push_list(&mut words, log_inv_rates);
+
+        for value in [log_leaf_width, root_generator_exponent] {
+            match value {
+                Some(value) => words.extend([1, *value as u64]),
+                None => words.push(0),
+            }
+        }
+
+        let flattened_schedule = query_pow_schedule
+            .as_ref()
+            .map(|schedule| schedule.iter().flat_map(|&(q, p)| [q, p]).collect_vec());
+
+        for values in [&flattened_schedule, num_ood_samples] {
+            match values {
+                Some(values) => {
+                    words.push(1);
+                    push_list(&mut words, values);
+                }
+                None => words.push(0),
+            }
+        }
+
+        fnv1a(words)
+    }
+
     /// log2 of the size of the initial domain.
     pub fn starting_domain_log_size(&self) -> usize {
         self.starting_domain_log_size
@@ -394,10 +1086,18 @@ impl<M: Clone> StirConfig<M> {
         self.starting_folding_pow_bits
     }
 
-    /// Number of rounds `M + 1` (`M` full and one final).
-    pub fn num_rounds(&self) -> usize {
+    /// Total number of rounds `M + 1`, i. e. the `M` full rounds and the final
+    /// one. Full round `i` (for `i = 1, ..., M`) produces a commitment to
+    /// `g_i`, so this is one more than the number of round commitments in a
+    /// proof.
+    pub fn total_rounds(&self) -> usize {
         // See the comment at the start of StirParameters for the convention
-        self.round_parameters.len() + 1
+        self.num_full_rounds() + 1
+    }
+
+    /// Number `M` of full rounds, i. e. of rounds other than the final one.
+    pub fn num_full_rounds(&self) -> usize {
+        self.round_parameters.len()
     }
 
     /// Configurations of the `M` full rounds (the ones happening inside the
@@ -406,8 +1106,8 @@ impl<M: Clone> StirConfig<M> {
         &self.round_parameters
     }
 
-    /// Configuration of the i-th full round (from 1 to `M` = `num_rounds() -
-    /// 1`)
+    /// Configuration of the i-th full round (from 1 to `M` =
+    /// `num_full_rounds()`)
     pub(crate) fn round_config(&self, i: usize) -> &RoundConfig {
         assert!(i > 0, "Rounds are numbered starting at i = 1");
 
@@ -419,17 +1119,35 @@ impl<M: Clone> StirConfig<M> {
                 panic!(
                     "Index out of bounds: there are {} rounds, but only {} round \
                     configurations (the final round does not have one)",
-                    self.num_rounds(),
-                    self.round_parameters.len(),
+                    self.total_rounds(),
+                    self.num_full_rounds(),
                 )
             })
     }
 
+    /// Number of out-of-domain samples of the `round`-th full round, i. e. of
+    /// replies `beta_{i, j}` its proof must contain.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `round` is not between 1 and the number `M` of full rounds.
+    pub fn num_ood_samples(&self, round: usize) -> usize {
+        self.round_config(round).num_ood_samples
+    }
+
     /// log2 of the degree-plus-1 bound of the final polynomial `p = g_{M + 1}`
     pub fn log_stopping_degree(&self) -> usize {
         self.log_stopping_degree
     }
 
+    /// Degree bound of the final polynomial `p = g_{M + 1}`, namely
+    /// `2^(log_starting_degree - (log_folding_factors[0] + ... +
+    /// log_folding_factors[M]))`: the verifier rejects final polynomials of
+    /// degree greater than or equal to it.
+    pub fn final_degree_bound(&self) -> usize {
+        1 << self.log_stopping_degree
+    }
+
     /// log2 of the inverse of the rate of the final codeword.
     pub fn final_log_inv_rate(&self) -> usize {
         self.log_final_inv_rate
@@ -502,12 +1220,89 @@ impl<M: Clone> StirConfig<M> {
         pow_bits
     }
 
+    /// log2 of the number of evaluations in each leaf of the committed Merkle
+    /// trees, if set explicitly. Cf. [`StirParameters::log_leaf_width`].
+    pub fn log_leaf_width(&self) -> Option<usize> {
+        self.parameters.log_leaf_width
+    }
+
+    /// Fraction by which the number of queries of each round exceeds the
+    /// minimum dictated by the security analysis. Cf.
+    /// [`StirParameters::query_slack`].
+    pub fn query_slack(&self) -> f64 {
+        self.parameters.query_slack
+    }
+
+    /// Whether the folding randomness of all rounds is derived from a single
+    /// challenger squeeze. Cf. [`StirParameters::batched_folding_randomness`].
+    pub fn batched_folding_randomness(&self) -> bool {
+        self.parameters.batched_folding_randomness
+    }
+
+    /// Returns the generator of the initial domain `L_0` in the field `F`,
+    /// which is the canonical one unless a different one was chosen with
+    /// [`StirParameters::with_root_generator`].
+    pub fn root_generator<F: TwoAdicField>(&self) -> F {
+        let generator = F::two_adic_generator(self.starting_domain_log_size);
+
+        match self.parameters.root_generator_exponent {
+            Some(exponent) => generator.exp_u64(exponent as u64),
+            None => generator,
+        }
+    }
+
+    /// Returns the domains `L_0, ..., L_M` over which `f_0` and `g_1, ..., g_M`
+    /// are committed to, where `L_0` is [`initial_domain`] and each `L_i` is
+    /// obtained from `L_{i - 1}` by squaring its subgroup. Callers verifying
+    /// many proofs under the same configuration can compute this schedule
+    /// once and pass it to
+    /// [`verify_with_domains`](crate::verify_with_domains).
+    pub fn domains<F: TwoAdicField>(&self) -> Vec<TwoAdicCoset<F>> {
+        iter::successors(Some(initial_domain(self)), |domain| {
+            Some(domain.shrink_subgroup(1))
+        })
+        .take(self.total_rounds())
+        .collect()
+    }
+
     /// Configuration of the Mixed Matrix Commitment Scheme (hasher and
     /// compressor) used to commit to the initial polynomial `f_0` and
     /// full-round polynomials `g_1, ... g_M`.
     pub fn mmcs_config(&self) -> &M {
         &self.parameters.mmcs_config
     }
+
+    /// Dimensions of the matrix of stacked evaluations committed to for the
+    /// `i`-th codeword, i. e. that of `f_0` for `i = 0` and that of `g_i` for
+    /// `i = 1, ..., M`. Each of its rows contains `width` evaluations over the
+    /// domain `L_i` and the evaluations needed to fold at one queried point
+    /// (i. e. at its `k_{i + 1}`-th roots) make up `k_{i + 1} / width`
+    /// consecutive rows, where `k_{i + 1}` is the folding factor applied to
+    /// that codeword. The width is `k_{i + 1}` unless a smaller leaf width was
+    /// set with [`StirParameters::with_log_leaf_width`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `i` is larger than the number `M` of full rounds.
+    pub fn committed_matrix_dimensions(&self, i: usize) -> Dimensions {
+        let log_folding_factor = self.parameters.log_folding_factors[i];
+        let log_width = log_folding_factor - self.log_rows_per_fold_group(log_folding_factor);
+
+        Dimensions {
+            width: 1 << log_width,
+            height: 1 << (self.starting_domain_log_size - i - log_width),
+        }
+    }
+
+    // log2 of the number of consecutive leaves (i. e. rows of the committed
+    // matrix) over which the evaluations at the k-th roots of each point are
+    // spread, where k = 2^log_folding_factor is the folding factor the
+    // codeword is committed for
+    pub(crate) fn log_rows_per_fold_group(&self, log_folding_factor: usize) -> usize {
+        self.parameters
+            .log_leaf_width
+            .map_or(0, |log_leaf_width| log_folding_factor - log_leaf_width)
+    }
 }
 
 impl<M: Clone> Display for StirParameters<M> {
@@ -521,7 +1316,11 @@ impl<M: Clone> Display for StirParameters<M> {
             \t- log of the folding factors: {}\n\
             \t- log of the starting inverse rate: {}\n\
             \t- log of inverse rates for non-first codewords: {}\n\
-            \t- proof-of-work bits: {}\n",
+            \t- proof-of-work bits: {}\n\
+            \t- query slack: {}\n\
+            \t- query/proof-of-work schedule: {}\n\
+            \t- out-of-domain samples: {}\n\
+            \t- minimum log domain size: {}\n",
             self.security_level,
             self.security_assumption,
             self.log_starting_degree,
@@ -536,7 +1335,28 @@ impl<M: Clone> Display for StirParameters<M> {
                 .map(|x| format!("{}", x))
                 .collect_vec()
                 .join(", "),
-            self.pow_bits
+            self.pow_bits,
+            self.query_slack,
+            match &self.query_pow_schedule {
+                Some(schedule) => schedule
+                    .iter()
+                    .map(|(q, p)| format!("({}, {})", q, p))
+                    .collect_vec()
+                    .join(", "),
+                None => "none".to_string(),
+            },
+            match &self.num_ood_samples {
+                Some(num_ood_samples) => num_ood_samples
+                    .iter()
+                    .map(|x| format!("{}", x))
+                    .collect_vec()
+                    .join(", "),
+                None => "derived".to_string(),
+            },
+            match self.min_log_domain {
+                Some(min_log_domain) => format!("{}", min_log_domain),
+                None => "none".to_string(),
+            }
         )
     }
 }
@@ -595,6 +1415,42 @@ impl Display for RoundConfig {
     }
 }
 
+// Inflates the minimum number of queries of a round by the given
+// (non-negative) fraction, rounding up, or returns None if the result does not
+// fit in a usize
+fn apply_query_slack(num_queries: usize, query_slack: f64) -> Option<usize> {
+    let extra_queries = (num_queries as f64 * query_slack).ceil();
+
+    // Casting a float to an integer saturates, so it is only exact below the
+    // maximum
+    if extra_queries >= usize::MAX as f64 {
+        return None;
+    }
+
+    num_queries.checked_add(extra_queries as usize)
+}
+
+// Checks that the proof-of-work bits scheduled for a round reach those
+// required to meet the security level with the scheduled number of queries
+fn check_scheduled_pow_bits(
+    // Index of the round (from 1 to M + 1)
+    round: usize,
+    // Proof-of-work bits required with the scheduled number of queries
+    required: usize,
+    // Proof-of-work bits in the schedule
+    scheduled: usize,
+) -> Result<usize, ConfigError> {
+    if scheduled < required {
+        return Err(ConfigError::InsufficientProofOfWork {
+            round,
+            required,
+            scheduled,
+        });
+    }
+
+    Ok(scheduled)
+}
+
 // Have the challenger observe the public parameters at the start of the
 // Fiat-Shamired interaction
 pub(crate) fn observe_public_parameters<F, M>(
@@ -612,12 +1468,42 @@ pub(crate) fn observe_public_parameters<F, M>(
             parameters.log_starting_degree,
             parameters.log_starting_inv_rate,
             parameters.pow_bits,
+            parameters.batched_folding_randomness as usize,
         ],
         false,
     );
     observe_usize_slice(challenger, &parameters.log_folding_factors, false);
     observe_usize_slice(challenger, &parameters.log_inv_rates, false);
 
+    // The schedule, if any, determines the number of queries and grinding of
+    // each round
+    if let Some(schedule) = &parameters.query_pow_schedule {
+        let flattened = schedule.iter().flat_map(|&(q, p)| [q, p]).collect_vec();
+        observe_usize_slice(challenger, &flattened, true);
+    }
+
+    // The same holds for the explicit numbers of out-of-domain samples, if
+    // any. Their number M differs from the length 2 * (M + 1) of the
+    // flattened schedule, so the two cannot be confused in the transcript.
+    if let Some(num_ood_samples) = &parameters.num_ood_samples {
+        observe_usize_slice(challenger, num_ood_samples, true);
+    }
+
+    // Likewise, the query slack determines the number of queries of each
+    // (unscheduled) round, whereas the leaf width and the root generator
+    // determine what the commitments are to. Optional values are preceded by
+    // whether they are present and the bit pattern of the slack is split into
+    // 16-bit limbs, which are smaller than the order of any supported field.
+    let optional = |value: Option<usize>| value.map_or([0, 0], |value| [1, value]);
+    let query_slack_bits = parameters.query_slack.to_bits();
+
+    let values = optional(parameters.log_leaf_width)
+        .into_iter()
+        .chain(optional(parameters.root_generator_exponent))
+        .chain((0..4).map(|i| ((query_slack_bits >> (16 * i)) & 0xffff) as usize))
+        .collect_vec();
+    observe_usize_slice(challenger, &values, false);
+
     // We do not absorb the MMCS configuration, as it would require stringent
     // trait bounds
 }
@@ -1,15 +1,16 @@
 use alloc::vec;
 
+use itertools::Itertools;
 use p3_baby_bear::{BabyBear, Poseidon2BabyBear};
 use p3_commit::ExtensionMmcs;
 use p3_field::extension::BinomialExtensionField;
-use p3_field::Field;
+use p3_field::{Field, TwoAdicField};
 use p3_merkle_tree::MerkleTreeMmcs;
 use p3_symmetric::{PaddingFreeSponge, TruncatedPermutation};
 use rand::SeedableRng;
 use rand_chacha::ChaCha20Rng;
 
-use crate::{SecurityAssumption, StirConfig, StirParameters};
+use crate::{ConfigError, SecurityAssumption, StirConfig, StirParameters};
 
 type Bb = BabyBear;
 type BbExt = BinomialExtensionField<Bb, 4>;
@@ -52,7 +53,7 @@ fn test_config() {
         test_mmcs_config(),
     );
 
-    let config: StirConfig<BbExtMmcs> = StirConfig::new::<BbExt>(parameters);
+    let config: StirConfig<BbExtMmcs> = StirConfig::new::<BbExt>(parameters).unwrap();
 
     assert_eq!(config.starting_domain_log_size(), 19);
     assert_eq!(config.starting_folding_pow_bits(), 30);
@@ -76,3 +77,313 @@ fn test_config() {
         assert_eq!(round_config.num_ood_samples, num_ood_samples);
     }
 }
+
+#[test]
+// Checks that a configuration whose initial domain does not fit in the largest
+// two-adic subgroup of the field is rejected with a clean error
+fn test_config_insufficient_two_adicity() {
+    // The quartic extension of BabyBear has two-adicity 29, whereas the initial
+    // domain has size 2^(28 + 2)
+    let parameters = StirParameters::constant_folding_factor(
+        (128, SecurityAssumption::CapacityBound),
+        28,
+        2,
+        4,
+        4,
+        20,
+        test_mmcs_config(),
+    );
+
+    assert_eq!(
+        StirConfig::new::<BbExt>(parameters).unwrap_err(),
+        ConfigError::InsufficientTwoAdicity {
+            log_domain_size: 30,
+            two_adicity: 29,
+        }
+    );
+}
+
+#[test]
+// Checks that a configuration whose initial domain size overflows a usize is
+// rejected with a clean error rather than producing a nonsensical size
+fn test_config_domain_size_overflow() {
+    for log_starting_degree in [usize::BITS as usize, usize::MAX] {
+        let parameters = StirParameters::constant_folding_factor(
+            (128, SecurityAssumption::CapacityBound),
+            log_starting_degree,
+            1,
+            4,
+            4,
+            20,
+            test_mmcs_config(),
+        );
+
+        assert_eq!(
+            StirConfig::new::<BbExt>(parameters).unwrap_err(),
+            ConfigError::DomainSizeOverflow {
+                log_starting_degree,
+                log_starting_inv_rate: 1,
+            }
+        );
+    }
+}
+
+#[test]
+// Checks that the configuration is rejected if the leaf width exceeds the
+// smallest folding factor
+fn test_config_invalid_leaf_width() {
+    let parameters = StirParameters::variable_folding_factor(
+        (128, SecurityAssumption::CapacityBound),
+        18,
+        1,
+        vec![4, 3, 2],
+        20,
+        test_mmcs_config(),
+    );
+
+    assert!(StirConfig::new::<BbExt>(parameters.clone().with_log_leaf_width(2)).is_ok());
+
+    assert_eq!(
+        StirConfig::new::<BbExt>(parameters.with_log_leaf_width(3)).unwrap_err(),
+        ConfigError::InvalidLeafWidth {
+            log_leaf_width: 3,
+            min_log_folding_factor: 2,
+        }
+    );
+}
+
+#[test]
+#[should_panic = "The generator must have multiplicative order 2^19"]
+// Checks that a root generator whose order does not match the size of the
+// initial domain is rejected
+fn test_config_invalid_root_generator() {
+    let parameters = StirParameters::variable_folding_factor(
+        (128, SecurityAssumption::CapacityBound),
+        18,
+        1,
+        vec![4, 3, 2],
+        20,
+        test_mmcs_config(),
+    );
+
+    parameters.with_root_generator(BbExt::two_adic_generator(18));
+}
+
+#[test]
+// Checks that the configuration is rejected if the proof-of-work bits cover the
+// entire security level, which would result in rounds with no queries
+fn test_config_zero_queries() {
+    let parameters = StirParameters::constant_folding_factor(
+        (20, SecurityAssumption::CapacityBound),
+        18,
+        1,
+        4,
+        3,
+        20,
+        test_mmcs_config(),
+    );
+
+    assert_eq!(
+        StirConfig::new::<BbExt>(parameters).unwrap_err(),
+        ConfigError::ZeroQueries { round: 1 }
+    );
+}
+
+#[test]
+// Checks that a query slack which is negative, not finite or inflates the
+// number of queries beyond the range of usize (e. g. in a deserialised
+// specification, which bypasses with_query_slack) is rejected
+fn test_config_invalid_query_slack() {
+    let parameters = StirParameters::constant_folding_factor(
+        (128, SecurityAssumption::CapacityBound),
+        18,
+        1,
+        4,
+        3,
+        20,
+        test_mmcs_config(),
+    );
+
+    for query_slack in [-0.5, f64::INFINITY, 1e30] {
+        let mut invalid_parameters = parameters.clone();
+        invalid_parameters.query_slack = query_slack;
+
+        assert_eq!(
+            StirConfig::new::<BbExt>(invalid_parameters).unwrap_err(),
+            ConfigError::InvalidQuerySlack { query_slack }
+        );
+    }
+}
+
+#[test]
+// Checks that inconsistent parameters, which the constructors and setters of
+// StirParameters prevent but a deserialised specification may contain, are
+// rejected rather than causing a panic
+fn test_config_inconsistent_parameters() {
+    let parameters = StirParameters::variable_folding_factor(
+        (128, SecurityAssumption::CapacityBound),
+        18,
+        1,
+        vec![4, 3, 2],
+        20,
+        test_mmcs_config(),
+    );
+
+    let new_with = |alter: fn(&mut StirParameters<BbExtMmcs>)| {
+        let mut parameters = parameters.clone();
+        alter(&mut parameters);
+        StirConfig::new::<BbExt>(parameters).unwrap_err()
+    };
+
+    assert_eq!(
+        new_with(|p| p.log_folding_factors.clear()),
+        ConfigError::NoRounds
+    );
+    assert_eq!(
+        new_with(|p| p.log_folding_factors[1] = 0),
+        ConfigError::ZeroLogFoldingFactor { round: 2 }
+    );
+    assert_eq!(
+        new_with(|p| {
+            p.log_inv_rates.pop();
+        }),
+        ConfigError::ParameterCount {
+            parameter: "log_inv_rates",
+            expected: 3,
+            got: 2,
+        }
+    );
+    assert_eq!(
+        new_with(|p| p.num_ood_samples = Some(vec![1])),
+        ConfigError::ParameterCount {
+            parameter: "num_ood_samples",
+            expected: 2,
+            got: 1,
+        }
+    );
+    assert_eq!(
+        new_with(|p| p.query_pow_schedule = Some(vec![(10, 20); 2])),
+        ConfigError::ParameterCount {
+            parameter: "query_pow_schedule",
+            expected: 3,
+            got: 2,
+        }
+    );
+    assert_eq!(
+        new_with(|p| p.log_folding_factors[0] = 14),
+        ConfigError::ExcessiveFolding {
+            total_reduction: 19,
+            log_starting_degree: 18,
+        }
+    );
+    assert_eq!(
+        new_with(|p| p.log_inv_rates[2] += 1),
+        ConfigError::InconsistentInvRates
+    );
+    assert_eq!(
+        new_with(|p| p.root_generator_exponent = Some(2)),
+        ConfigError::InvalidRootGeneratorExponent { exponent: 2 }
+    );
+    assert_eq!(
+        new_with(|p| p.security_level = 10_000),
+        ConfigError::UnreachableOodSecurity { round: 1 }
+    );
+}
+
+#[test]
+// Checks that the estimated verifier cost grows with the number of queries
+// (i. e. with the security level) and with the number of rounds
+fn test_verifier_op_estimate() {
+    let estimate = |security_level, num_rounds| {
+        let parameters = StirParameters::constant_folding_factor(
+            (security_level, SecurityAssumption::CapacityBound),
+            20,
+            1,
+            2,
+            num_rounds,
+            20,
+            test_mmcs_config(),
+        );
+        StirConfig::new::<BbExt>(parameters)
+            .unwrap()
+            .verifier_op_estimate()
+    };
+
+    let base = estimate(100, 3);
+
+    // More queries in every round
+    let more_queries = estimate(128, 3);
+    assert!(more_queries.multiplications > base.multiplications);
+    assert!(more_queries.hashes > base.hashes);
+
+    // One more full round, each with its own Merkle proofs and inversions
+    let more_rounds = estimate(100, 4);
+    assert!(more_rounds.hashes > base.hashes);
+    assert!(more_rounds.inversions > base.inversions);
+}
+
+#[test]
+// Checks that the final degree bound matches the one computed by hand for a
+// multi-round schedule with variable folding factors
+fn test_final_degree_bound() {
+    let log_starting_degree = 18;
+    let log_folding_factors = vec![4, 3, 2, 3];
+
+    let parameters = StirParameters::variable_folding_factor(
+        (128, SecurityAssumption::CapacityBound),
+        log_starting_degree,
+        1,
+        log_folding_factors.clone(),
+        20,
+        test_mmcs_config(),
+    );
+
+    let config = StirConfig::new::<BbExt>(parameters).unwrap();
+
+    // 2^(18 - (4 + 3 + 2 + 3)) = 2^6
+    assert_eq!(config.final_degree_bound(), 64);
+    assert_eq!(
+        config.final_degree_bound(),
+        1 << (log_starting_degree - log_folding_factors.iter().sum::<usize>())
+    );
+}
+
+#[test]
+// Checks that a query/proof-of-work schedule which reduces the number of
+// queries of a round without increasing its proof-of-work bits is rejected
+fn test_config_insufficient_scheduled_pow() {
+    let parameters = StirParameters::constant_folding_factor(
+        (100, SecurityAssumption::CapacityBound),
+        12,
+        1,
+        2,
+        3,
+        16,
+        test_mmcs_config(),
+    );
+
+    let config = StirConfig::new::<BbExt>(parameters.clone()).unwrap();
+
+    let mut schedule = config
+        .round_configs()
+        .iter()
+        .map(|round_config| (round_config.num_queries, round_config.pow_bits))
+        .chain([(config.final_num_queries(), config.final_pow_bits())])
+        .collect_vec();
+
+    // The unmodified schedule reproduces the default configuration
+    let scheduled_config =
+        StirConfig::new::<BbExt>(parameters.clone().with_query_pow_schedule(schedule.clone()))
+            .unwrap();
+    assert_eq!(
+        scheduled_config.pow_bits_all_rounds(),
+        config.pow_bits_all_rounds()
+    );
+
+    schedule[1].0 -= 2;
+
+    assert!(matches!(
+        StirConfig::new::<BbExt>(parameters.with_query_pow_schedule(schedule)).unwrap_err(),
+        ConfigError::InsufficientProofOfWork { round: 2, .. }
+    ));
+}
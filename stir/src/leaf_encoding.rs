@@ -0,0 +1,167 @@
+use alloc::vec::Vec;
+
+use p3_commit::Mmcs;
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::{Dimensions, Matrix};
+
+/// Serialisation of a row of a committed matrix into the leaf which is
+/// actually fed to an MMCS. STIR commits to rows of stacked evaluations (cf.
+/// [`stack_initial_evaluations`](crate::stack_initial_evaluations)) and, by
+/// default, passes them to the MMCS as they are. When commitments are shared
+/// with a system following a different convention (e. g. a different order of
+/// the evaluations within a leaf or a domain-separation tag), the convention
+/// can be described by implementing this trait and wrapping the MMCS in an
+/// [`EncodedLeafMmcs`], which the prover and verifier then use like any other
+/// MMCS.
+pub trait LeafEncoding<T>: Clone {
+    /// Length of the encoding of a row of length `width`.
+    fn encoded_width(&self, width: usize) -> usize;
+
+    /// Encodes a row into the leaf committed to by the underlying MMCS. The
+    /// output must have length `encoded_width(row.len())`.
+    fn encode(&self, row: &[T]) -> Vec<T>;
+
+    /// Recovers the row from its encoding, i. e. the inverse of
+    /// [`LeafEncoding::encode`].
+    fn decode(&self, leaf: &[T]) -> Vec<T>;
+}
+
+/// Default [`LeafEncoding`], which commits to each row as it is. Wrapping an
+/// MMCS in an [`EncodedLeafMmcs`] with this encoding produces the same
+/// commitments and proofs as the MMCS itself.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct IdentityLeafEncoding;
+
+impl<T: Clone> LeafEncoding<T> for IdentityLeafEncoding {
+    fn encoded_width(&self, width: usize) -> usize {
+        width
+    }
+
+    fn encode(&self, row: &[T]) -> Vec<T> {
+        row.to_vec()
+    }
+
+    fn decode(&self, leaf: &[T]) -> Vec<T> {
+        leaf.to_vec()
+    }
+}
+
+/// MMCS committing to the rows of the matrices it receives through an inner
+/// MMCS after encoding them with a [`LeafEncoding`]. Openings are decoded
+/// before being returned and re-encoded before being checked, so that callers
+/// only ever handle the plain rows.
+#[derive(Clone, Debug)]
+pub struct EncodedLeafMmcs<M, E> {
+    inner: M,
+    encoding: E,
+}
+
+impl<M, E> EncodedLeafMmcs<M, E> {
+    /// Wraps `inner` so that it commits to the rows of its inputs encoded with
+    /// `encoding`.
+    pub fn new(inner: M, encoding: E) -> Self {
+        Self { inner, encoding }
+    }
+
+    /// The wrapped MMCS.
+    pub fn inner(&self) -> &M {
+        &self.inner
+    }
+
+    /// The encoding applied to each row before committing to it.
+    pub fn encoding(&self) -> &E {
+        &self.encoding
+    }
+
+    // Returns the matrix whose rows are the encodings of those of the given
+    // matrix
+    fn encode_matrix<T, Mat>(&self, matrix: &Mat) -> RowMajorMatrix<T>
+    where
+        T: Clone + Send + Sync,
+        Mat: Matrix<T>,
+        E: LeafEncoding<T>,
+    {
+        let values = matrix
+            .rows()
+            .flat_map(|row| self.encoding.encode(&row.collect::<Vec<_>>()))
+            .collect();
+
+        RowMajorMatrix::new(values, self.encoding.encoded_width(matrix.width()))
+    }
+}
+
+impl<T, M, E> Mmcs<T> for EncodedLeafMmcs<M, E>
+where
+    T: Clone + Send + Sync,
+    M: Mmcs<T>,
+    E: LeafEncoding<T>,
+{
+    // The prover data of the inner MMCS together with the original matrices,
+    // which get_matrices has to return
+    type ProverData<Mat> = (M::ProverData<RowMajorMatrix<T>>, Vec<Mat>);
+    type Commitment = M::Commitment;
+    type Proof = M::Proof;
+    type Error = M::Error;
+
+    fn commit<Mat: Matrix<T>>(
+        &self,
+        inputs: Vec<Mat>,
+    ) -> (Self::Commitment, Self::ProverData<Mat>) {
+        let encoded = inputs
+            .iter()
+            .map(|matrix| self.encode_matrix(matrix))
+            .collect();
+
+        let (commitment, prover_data) = self.inner.commit(encoded);
+
+        (commitment, (prover_data, inputs))
+    }
+
+    fn open_batch<Mat: Matrix<T>>(
+        &self,
+        index: usize,
+        (prover_data, _): &Self::ProverData<Mat>,
+    ) -> (Vec<Vec<T>>, Self::Proof) {
+        let (leaves, proof) = self.inner.open_batch(index, prover_data);
+
+        (
+            leaves
+                .iter()
+                .map(|leaf| self.encoding.decode(leaf))
+                .collect(),
+            proof,
+        )
+    }
+
+    fn get_matrices<'a, Mat: Matrix<T>>(
+        &self,
+        (_, matrices): &'a Self::ProverData<Mat>,
+    ) -> Vec<&'a Mat> {
+        matrices.iter().collect()
+    }
+
+    fn verify_batch(
+        &self,
+        commit: &Self::Commitment,
+        dimensions: &[Dimensions],
+        index: usize,
+        opened_values: &[Vec<T>],
+        proof: &Self::Proof,
+    ) -> Result<(), Self::Error> {
+        let encoded_dimensions: Vec<Dimensions> = dimensions
+            .iter()
+            .map(|&Dimensions { width, height }| Dimensions {
+                width: self.encoding.encoded_width(width),
+                height,
+            })
+            .collect();
+
+        let leaves: Vec<Vec<T>> = opened_values
+            .iter()
+            .map(|row| self.encoding.encode(row))
+            .collect();
+
+        self.inner
+            .verify_batch(commit, &encoded_dimensions, index, &leaves, proof)
+    }
+}
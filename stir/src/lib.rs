@@ -7,7 +7,13 @@
 
 extern crate alloc;
 
+#[cfg(feature = "std")]
+extern crate std;
+
+#[cfg(any(test, feature = "seeded-challenger"))]
+pub mod challenger;
 mod config;
+mod leaf_encoding;
 mod proof;
 mod prover;
 mod proximity_gaps;
@@ -17,11 +23,33 @@ mod verifier;
 #[cfg(any(test, feature = "test-utils"))]
 pub mod test_utils;
 
-pub use config::{StirConfig, StirParameters};
-pub use proof::StirProof;
-pub use prover::{commit, prove};
+#[cfg(all(feature = "std", any(test, feature = "test-utils")))]
+pub mod test_vectors;
+
+pub use config::{ConfigError, StirConfig, StirParameters, StirParametersSpec, VerifierCost};
+pub use leaf_encoding::{EncodedLeafMmcs, IdentityLeafEncoding, LeafEncoding};
+pub use proof::{MixedDegreeProof, RoundProofView, StirProof, StirProofEnvelope};
+#[cfg(feature = "blinding")]
+pub use prover::prove_blinded;
+#[cfg(any(test, feature = "test-utils"))]
+pub use prover::prove_reference;
+pub use prover::{
+    build_answer_polynomial, commit, commit_and_fold_once, initial_domain, prove,
+    prove_from_bitrev_evals, prove_mixed_degrees, prove_pair, prove_with_committed_input,
+    prove_with_fold_challenge_source, prove_with_public_inputs, stack_initial_evaluations,
+    ChainedProver, CommittedWitness,
+};
 pub use proximity_gaps::SecurityAssumption;
-pub use verifier::verify;
+pub use utils::{batching_challenge, FoldChallengeSource, TranscriptFoldChallenge};
+pub use verifier::error::ShapeError;
+#[cfg(feature = "std")]
+pub use verifier::verify_from_reader;
+pub use verifier::{
+    verify, verify_against_any, verify_cheap_checks, verify_collect_errors, verify_diagnostic,
+    verify_envelope, verify_mixed_degrees, verify_pair, verify_pow_only, verify_with_domains,
+    verify_with_fold_challenge_source, verify_with_limits, verify_with_public_inputs,
+    ChainedVerifier, DeferredQueries, VerificationLimits, MAX_ENVELOPE_SECURITY_LEVEL,
+};
 
 // If the configuration requires the prover to compute a proof of work of more
 // bits than this limit, calling prove() or verify() will log a WARN message (as
@@ -42,4 +70,9 @@ pub(crate) enum Messages {
     ShakeRandomness,
     FinalPolynomial,
     FinalQueryIndices,
+    PairedCommitments,
+    DegreeBounds,
+    CombinationRandomness,
+    CombinationQueryIndices,
+    PublicInputs,
 }
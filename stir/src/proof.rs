@@ -1,9 +1,37 @@
 use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use core::iter;
+#[cfg(feature = "std")]
+use std::io::{Read, Write};
 
 use p3_commit::Mmcs;
 use p3_field::Field;
+use p3_matrix::Dimensions;
 use p3_poly::Polynomial;
-use serde::{Deserialize, Serialize};
+#[cfg(feature = "std")]
+use serde::de::DeserializeOwned;
+use serde::de::Error;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::verifier::error::ShapeError;
+use crate::{StirConfig, StirParametersSpec};
+
+// Current version of the serialization format of StirProof (see
+// StirProof::FORMAT_VERSION). Proofs without shake polynomials (cf. the
+// no-shake feature) have a different layout and transcript, so their version is
+// tagged with NO_SHAKE_FORMAT_FLAG, which keeps the versions of both formats
+// distinct whenever either of them is bumped
+#[cfg(not(feature = "no-shake"))]
+const PROOF_FORMAT_VERSION: u8 = 2;
+#[cfg(feature = "no-shake")]
+const PROOF_FORMAT_VERSION: u8 = NO_SHAKE_FORMAT_FLAG | 2;
+
+// Bit set in the format version of proofs without shake polynomials
+pub(crate) const NO_SHAKE_FORMAT_FLAG: u8 = 0x80;
+
+// Current version of the format of StirProofEnvelope (see
+// StirProofEnvelope::FORMAT_VERSION)
+const ENVELOPE_FORMAT_VERSION: u8 = 1;
 
 /// A STIR proof that the committed polynomial satisfies the configured degree
 /// bound.
@@ -13,6 +41,14 @@ use serde::{Deserialize, Serialize};
     deserialize = "Witness: Deserialize<'de>, RoundProof<F, M, Witness>: Deserialize<'de>, Polynomial<F>: Deserialize<'de>"
 ))]
 pub struct StirProof<F: Field, M: Mmcs<F>, Witness> {
+    // Version tag of the serialization format, which is serialized before
+    // every other field
+    pub(crate) format_version: FormatVersion,
+
+    // Fingerprint of the parameters of the configuration under which the
+    // proof was produced (cf. StirConfig::parameter_fingerprint)
+    pub(crate) parameter_fingerprint: u64,
+
     // Round proofs for the full-rounds i = 1, ..., M
     pub(crate) round_proofs: Vec<RoundProof<F, M, Witness>>,
 
@@ -22,8 +58,504 @@ pub struct StirProof<F: Field, M: Mmcs<F>, Witness> {
     // Proof of work for the final round
     pub(crate) pow_witness: Witness,
 
-    // Merkle proofs for the final-round openings (of g_M)
-    pub(crate) final_round_queries: Vec<(Vec<F>, M::Proof)>,
+    // Final-round openings (of g_M): for each queried point, the evaluations
+    // at its k_M-th roots and the Merkle proofs of the leaves containing them.
+    // As in RoundProof::query_proofs, these follow the order in which the
+    // (deduplicated) queried indices are sampled from the challenger.
+    pub(crate) final_round_queries: Vec<(Vec<F>, Vec<M::Proof>)>,
+}
+
+impl<F: Field, M: Mmcs<F>, Witness> StirProof<F, M, Witness> {
+    /// Version of the serialization format of the proof. It is serialized
+    /// before all other components and deserializing a proof tagged with a
+    /// different version fails with an error, rather than silently
+    /// misinterpreting data laid out according to another format. It is
+    /// bumped whenever the layout of the proof changes. Since the `no-shake`
+    /// feature changes the layout, proofs produced with it are tagged with a
+    /// distinct version (with the top bit set) and are rejected by builds
+    /// without it, and vice versa.
+    pub const FORMAT_VERSION: u8 = PROOF_FORMAT_VERSION;
+
+    /// Returns, for each round `i = 1, ..., M + 1` of the proof, the
+    /// dimensions of the committed matrix whose rows are opened by the queries
+    /// of that round (i. e. that of `f_0` for the first round and that of
+    /// `g_{i - 1}` for the others). Each query of round `i` opens
+    /// `k_i / width` consecutive rows of that matrix, where `k_i` is the
+    /// folding factor of the round. Cf.
+    /// [`StirConfig::committed_matrix_dimensions`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the proof has more full rounds than the configuration.
+    pub fn queried_matrix_dimensions(&self, config: &StirConfig<M>) -> Vec<Dimensions> {
+        (0..=self.round_proofs.len())
+            .map(|i| config.committed_matrix_dimensions(i))
+            .collect()
+    }
+
+    /// Checks the structural consistency of the proof with `config`: the
+    /// number of full-round proofs, the degree of the final polynomial and,
+    /// for each full round, the number of out-of-domain replies and the
+    /// degrees of the polynomials `Ans_i` and of the shake polynomial (unless
+    /// the `no-shake` feature is enabled). These checks involve neither
+    /// Merkle-proof verification nor a challenger and are therefore cheap,
+    /// which makes this function suitable as a pre-filter before full
+    /// verification. A proof passing them may still be rejected by
+    /// [`verify`](crate::verify), which performs them (or stricter versions
+    /// thereof) as well.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first [`ShapeError`] found, checking the number of rounds
+    /// first, then each full round in order and finally the final polynomial.
+    pub fn validate_shape(&self, config: &StirConfig<M>) -> Result<(), ShapeError> {
+        if self.round_proofs.len() != config.num_full_rounds() {
+            return Err(ShapeError::NumRounds {
+                expected: config.num_full_rounds(),
+                got: self.round_proofs.len(),
+            });
+        }
+
+        for (round_config, (i, round_proof)) in config
+            .round_configs()
+            .iter()
+            .zip(self.round_proofs.iter().enumerate())
+        {
+            let round = i + 1;
+
+            if round_proof.betas.len() != round_config.num_ood_samples {
+                return Err(ShapeError::NumBetas {
+                    round,
+                    expected: round_config.num_ood_samples,
+                    got: round_proof.betas.len(),
+                });
+            }
+
+            // Ans_i interpolates at most this many points (fewer if some
+            // queried indices are repeated)
+            let max_num_points = round_config.num_ood_samples + round_config.num_queries;
+
+            if round_proof
+                .ans_polynomial
+                .degree_checked()
+                .is_some_and(|d| d >= max_num_points)
+            {
+                return Err(ShapeError::AnsPolynomialDegree { round });
+            }
+
+            // The shake polynomial is a sum of quotients of Ans_i minus a
+            // constant by linear factors
+            #[cfg(not(feature = "no-shake"))]
+            if round_proof
+                .shake_polynomial
+                .degree_checked()
+                .is_some_and(|d| d + 1 >= max_num_points)
+            {
+                return Err(ShapeError::ShakePolynomialDegree { round });
+            }
+        }
+
+        if self
+            .final_polynomial
+            .degree_checked()
+            .is_some_and(|d| d >= config.final_degree_bound())
+        {
+            return Err(ShapeError::FinalPolynomialDegree);
+        }
+
+        Ok(())
+    }
+
+    /// Checks whether the proof may have been produced under `config`, as a
+    /// precondition to [`verify`](crate::verify) which helps tell a proof
+    /// passed together with the wrong configuration apart from an invalid
+    /// one. On top of the checks of [`StirProof::validate_shape`], each round
+    /// (incl. the final one) must contain between one and the configured
+    /// number of openings (fewer if some queried indices are repeated), each
+    /// of them with as many evaluations as the folding factor of the round
+    /// and as many Merkle proofs as the committed rows they span. No
+    /// Merkle-proof verification or transcript replay is performed, so a
+    /// compatible proof may still be rejected by `verify`, whereas an
+    /// incompatible one always is.
+    pub fn is_compatible_with(&self, config: &StirConfig<M>) -> bool {
+        if self.validate_shape(config).is_err() {
+            return false;
+        }
+
+        let openings_compatible =
+            |round: usize, num_queries: usize, query_proofs: &[(Vec<F>, Vec<M::Proof>)]| {
+                let log_folding_factor = config.log_folding_factors()[round - 1];
+                let rows_per_group = 1 << config.log_rows_per_fold_group(log_folding_factor);
+
+                (1..=num_queries).contains(&query_proofs.len())
+                    && query_proofs.iter().all(|(evals, proofs)| {
+                        evals.len() == 1 << log_folding_factor && proofs.len() == rows_per_group
+                    })
+            };
+
+        config
+            .round_configs()
+            .iter()
+            .zip(&self.round_proofs)
+            .enumerate()
+            .all(|(i, (round_config, round_proof))| {
+                openings_compatible(i + 1, round_config.num_queries, &round_proof.query_proofs)
+            })
+            && openings_compatible(
+                config.total_rounds(),
+                config.final_num_queries(),
+                &self.final_round_queries,
+            )
+    }
+
+    /// Iterates over read-only views of the proofs of the full rounds
+    /// `i = 1, ..., M`, in order. This is meant for tooling inspecting proofs
+    /// (e. g. to break down their size or log their contents), which does not
+    /// need access to the internals of the round proofs.
+    pub fn round_proofs_iter(&self) -> impl Iterator<Item = RoundProofView<'_, F, M>> {
+        self.round_proofs
+            .iter()
+            .enumerate()
+            .map(|(i, round_proof)| RoundProofView {
+                round: i + 1,
+                g_root: &round_proof.g_root,
+                betas: &round_proof.betas,
+                ans_polynomial: &round_proof.ans_polynomial,
+                query_proofs: &round_proof.query_proofs,
+            })
+    }
+}
+
+/// Read-only view of the proof of a full round of STIR, as produced by
+/// [`StirProof::round_proofs_iter`].
+pub struct RoundProofView<'a, F: Field, M: Mmcs<F>> {
+    // Index i of the round, in 1, ..., M
+    round: usize,
+
+    // Cf. the fields of RoundProof of the same name
+    g_root: &'a M::Commitment,
+    betas: &'a [F],
+    ans_polynomial: &'a Polynomial<F>,
+    query_proofs: &'a [(Vec<F>, Vec<M::Proof>)],
+}
+
+impl<'a, F: Field, M: Mmcs<F>> RoundProofView<'a, F, M> {
+    /// Index `i` of the round, between `1` and the number of full rounds `M`.
+    pub fn round(&self) -> usize {
+        self.round
+    }
+
+    /// Commitment to the stacked evaluations of `g_i` over `L_i`.
+    pub fn g_root(&self) -> &'a M::Commitment {
+        self.g_root
+    }
+
+    /// Replies `beta_{i, j}` to the out-of-domain queries to `g_i`.
+    pub fn betas(&self) -> &'a [F] {
+        self.betas
+    }
+
+    /// Degree of the polynomial `Ans_i`, or `None` if it is zero. Leading zero
+    /// coefficients (which a deserialized proof may contain) are disregarded.
+    pub fn ans_polynomial_degree(&self) -> Option<usize> {
+        self.ans_polynomial.degree_checked()
+    }
+
+    /// Number of openings of the round, i. e. of distinct queried indices.
+    /// This is at most the number of queries of the round in the
+    /// configuration, with fewer openings if some indices were sampled more
+    /// than once.
+    pub fn num_queries(&self) -> usize {
+        self.query_proofs.len()
+    }
+}
+
+/// A proof that several polynomials, committed to together, satisfy their
+/// individual degree bounds, as produced by
+/// [`prove_mixed_degrees`](crate::prove_mixed_degrees) and verified by
+/// [`verify_mixed_degrees`](crate::verify_mixed_degrees). It consists of a STIR
+/// proof for a random combination of the polynomials together with openings
+/// binding that combination to the committed polynomials.
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(bound(
+    serialize = "Witness: Serialize, StirProof<F, M, Witness>: Serialize",
+    deserialize = "Witness: Deserialize<'de>, StirProof<F, M, Witness>: Deserialize<'de>"
+))]
+pub struct MixedDegreeProof<F: Field, M: Mmcs<F>, Witness> {
+    // Commitment to the evaluations of the polynomials f_j over L_0: the i-th
+    // row contains the i-th fold group (cf. utils::stack_evaluations) of each
+    // f_j in turn
+    pub(crate) inputs_commitment: M::Commitment,
+
+    // Commitment to the stacked evaluations of the combination g of the f_j
+    // (cf. utils::combine_mixed_degree_evaluations) over L_0, which the STIR
+    // proof refers to
+    pub(crate) combination_commitment: M::Commitment,
+
+    // For each consistency query, the opened row of the matrix of evaluations
+    // of the f_j with its Merkle proof and the opened fold group of g with the
+    // Merkle proofs of its rows. These follow the order in which the queried
+    // indices are sampled from the challenger.
+    pub(crate) consistency_queries: Vec<((Vec<F>, M::Proof), (Vec<F>, Vec<M::Proof>))>,
+
+    // STIR proof of the low-degreeness of g
+    pub(crate) stir_proof: StirProof<F, M, Witness>,
+}
+
+impl<F: Field, M: Mmcs<F>, Witness> MixedDegreeProof<F, M, Witness> {
+    /// Commitment to the evaluations of the batched polynomials over `L_0`,
+    /// which the caller may need to bind to the rest of its protocol.
+    pub fn inputs_commitment(&self) -> &M::Commitment {
+        &self.inputs_commitment
+    }
+}
+
+/// A STIR proof bundled with everything needed to verify it other than the
+/// MMCS configuration, namely the commitment it refers to and the parameters
+/// of the configuration under which it was produced. This is meant for
+/// archiving proofs so that they can be re-verified long after the
+/// configuration which produced them has been discarded, which
+/// [`verify_envelope`](crate::verify_envelope) does by reconstructing that
+/// configuration.
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(bound(
+    serialize = "Witness: Serialize, StirProof<F, M, Witness>: Serialize",
+    deserialize = "Witness: Deserialize<'de>, StirProof<F, M, Witness>: Deserialize<'de>"
+))]
+pub struct StirProofEnvelope<F: Field, M: Mmcs<F>, Witness> {
+    // Version of the format of the envelope (cf.
+    // StirProofEnvelope::FORMAT_VERSION), which is serialized before every
+    // other field
+    pub(crate) format_version: u8,
+
+    // Parameters of the configuration under which the proof was produced,
+    // without the MMCS configuration
+    pub(crate) parameters: StirParametersSpec,
+
+    // Commitment to the evaluations of the polynomial over L_0
+    pub(crate) commitment: M::Commitment,
+
+    // The proof itself
+    pub(crate) proof: StirProof<F, M, Witness>,
+}
+
+impl<F: Field, M: Mmcs<F>, Witness> StirProofEnvelope<F, M, Witness> {
+    /// Version of the format of the envelope, which is checked by
+    /// [`verify_envelope`](crate::verify_envelope). It is bumped whenever the
+    /// layout of the envelope changes (that of the proof being versioned
+    /// separately, cf. [`StirProof::FORMAT_VERSION`]).
+    pub const FORMAT_VERSION: u8 = ENVELOPE_FORMAT_VERSION;
+
+    /// Bundles `proof`, which was produced under `config` for the polynomial
+    /// committed to by `commitment`, into an envelope.
+    pub fn new(
+        config: &StirConfig<M>,
+        commitment: M::Commitment,
+        proof: StirProof<F, M, Witness>,
+    ) -> Self {
+        StirProofEnvelope {
+            format_version: ENVELOPE_FORMAT_VERSION,
+            parameters: config.parameters().spec(),
+            commitment,
+            proof,
+        }
+    }
+
+    /// Parameters of the configuration under which the proof was produced.
+    pub fn parameters(&self) -> &StirParametersSpec {
+        &self.parameters
+    }
+
+    /// Commitment to the polynomial the proof refers to.
+    pub fn commitment(&self) -> &M::Commitment {
+        &self.commitment
+    }
+
+    /// The bundled proof.
+    pub fn proof(&self) -> &StirProof<F, M, Witness> {
+        &self.proof
+    }
+}
+
+#[cfg(feature = "std")]
+impl<F: Field, M: Mmcs<F>, Witness: Serialize + DeserializeOwned> StirProof<F, M, Witness> {
+    /// Writes the proof to `writer` in version 1 of the streaming encoding,
+    /// which can be verified incrementally by
+    /// [`verify_from_reader`](crate::verify_from_reader) without holding the
+    /// whole proof in memory. The encoding is a sequence of newline-separated
+    /// JSON values: the format version, the parameter fingerprint (cf.
+    /// [`StirConfig::parameter_fingerprint`]), the number M of full rounds, the M
+    /// round proofs in order, the final polynomial, the final proof of work
+    /// and the final-round openings.
+    pub fn encode_v1<W: Write>(&self, mut writer: W) -> serde_json::Result<()> {
+        write_v1_value(&mut writer, &self.format_version)?;
+        write_v1_value(&mut writer, &self.parameter_fingerprint)?;
+        write_v1_value(&mut writer, &self.round_proofs.len())?;
+
+        for round_proof in &self.round_proofs {
+            write_v1_value(&mut writer, round_proof)?;
+        }
+
+        write_v1_value(&mut writer, &self.final_polynomial)?;
+        write_v1_value(&mut writer, &self.pow_witness)?;
+        write_v1_value(&mut writer, &self.final_round_queries)
+    }
+
+    /// Size in bytes of the proof in the streaming encoding v1 (cf.
+    /// [`StirProof::encode_v1`]).
+    pub fn size_in_bytes(&self) -> usize {
+        Vec::<u8>::from(self).len()
+    }
+
+    /// Returns the number of bytes taken in the streaming encoding v1 (cf.
+    /// [`StirProof::encode_v1`]) by each round proof `i = 1, ..., M` in order,
+    /// followed by that of the final-round payload (the final polynomial, the
+    /// final proof of work and the final-round openings). This shows which
+    /// rounds dominate the size of the proof. The sizes add up to
+    /// [`StirProof::size_in_bytes`] minus that of the header of the encoding
+    /// (the format version, the parameter fingerprint and the number of
+    /// rounds).
+    pub fn per_round_sizes(&self) -> Vec<usize> {
+        let final_round_size = v1_value_size(&self.final_polynomial)
+            + v1_value_size(&self.pow_witness)
+            + v1_value_size(&self.final_round_queries);
+
+        self.round_proofs
+            .iter()
+            .map(v1_value_size)
+            .chain(iter::once(final_round_size))
+            .collect()
+    }
+
+    /// Reads a full proof encoded with [`StirProof::encode_v1`] from `reader`.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the data is not a valid encoding of a proof (including if it
+    /// is tagged with an unsupported format version or followed by trailing
+    /// data) or if reading from `reader` fails.
+    pub fn decode_v1<R: Read>(reader: R) -> serde_json::Result<Self> {
+        let mut decoder = V1Decoder::new(reader);
+
+        let format_version = decoder.read_next()?;
+        let parameter_fingerprint = decoder.read_next()?;
+        let num_round_proofs: usize = decoder.read_next()?;
+
+        let round_proofs = (0..num_round_proofs)
+            .map(|_| decoder.read_next())
+            .collect::<serde_json::Result<_>>()?;
+
+        let proof = StirProof {
+            format_version,
+            parameter_fingerprint,
+            round_proofs,
+            final_polynomial: decoder.read_next()?,
+            pow_witness: decoder.read_next()?,
+            final_round_queries: decoder.read_next()?,
+        };
+
+        decoder.end()?;
+
+        Ok(proof)
+    }
+}
+
+/// Decodes a proof from its streaming encoding v1, as in
+/// [`StirProof::decode_v1`]. Fails if the bytes are not a valid encoding of a
+/// proof.
+#[cfg(feature = "std")]
+impl<F: Field, M: Mmcs<F>, Witness: Serialize + DeserializeOwned> TryFrom<&[u8]>
+    for StirProof<F, M, Witness>
+{
+    type Error = serde_json::Error;
+
+    fn try_from(bytes: &[u8]) -> serde_json::Result<Self> {
+        Self::decode_v1(bytes)
+    }
+}
+
+/// Encodes a proof in the streaming encoding v1, as in
+/// [`StirProof::encode_v1`].
+#[cfg(feature = "std")]
+impl<F: Field, M: Mmcs<F>, Witness: Serialize + DeserializeOwned> From<&StirProof<F, M, Witness>>
+    for Vec<u8>
+{
+    fn from(proof: &StirProof<F, M, Witness>) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        proof
+            .encode_v1(&mut bytes)
+            .expect("Encoding to a vector cannot fail");
+        bytes
+    }
+}
+
+// Writes one value of the streaming encoding v1, followed by the separator
+#[cfg(feature = "std")]
+fn write_v1_value<W: Write, T: Serialize>(writer: &mut W, value: &T) -> serde_json::Result<()> {
+    serde_json::to_writer(&mut *writer, value)?;
+    writer.write_all(b"\n").map_err(serde_json::Error::io)
+}
+
+// Number of bytes taken by one value of the streaming encoding v1, including
+// the separator
+#[cfg(feature = "std")]
+fn v1_value_size<T: Serialize>(value: &T) -> usize {
+    serde_json::to_vec(value)
+        .expect("Serializing to a vector cannot fail")
+        .len()
+        + 1
+}
+
+// Reads the successive values of the streaming encoding v1 from a reader, one
+// at a time
+#[cfg(feature = "std")]
+pub(crate) struct V1Decoder<R: Read> {
+    deserializer: serde_json::Deserializer<serde_json::de::IoRead<R>>,
+}
+
+#[cfg(feature = "std")]
+impl<R: Read> V1Decoder<R> {
+    pub(crate) fn new(reader: R) -> Self {
+        V1Decoder {
+            deserializer: serde_json::Deserializer::from_reader(reader),
+        }
+    }
+
+    // Decodes the next value, which must be of type T
+    pub(crate) fn read_next<T: DeserializeOwned>(&mut self) -> serde_json::Result<T> {
+        T::deserialize(&mut self.deserializer)
+    }
+
+    // Checks that the reader contains nothing but whitespace after the last
+    // decoded value
+    pub(crate) fn end(mut self) -> serde_json::Result<()> {
+        self.deserializer.end()
+    }
+}
+
+// Zero-sized marker which serializes as the current proof format version and
+// only deserializes successfully from that same version
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct FormatVersion;
+
+impl Serialize for FormatVersion {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u8(PROOF_FORMAT_VERSION)
+    }
+}
+
+impl<'de> Deserialize<'de> for FormatVersion {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let version = u8::deserialize(deserializer)?;
+
+        if version != PROOF_FORMAT_VERSION {
+            return Err(D::Error::custom(format_args!(
+                "unsupported STIR proof format version {version} (expected {PROOF_FORMAT_VERSION})"
+            )));
+        }
+
+        Ok(FormatVersion)
+    }
 }
 
 // A proof for one of the M full rounds of the protocol
@@ -51,11 +583,17 @@ pub(crate) struct RoundProof<F: Field, M: Mmcs<F>, Witness> {
 
     // Merkle proofs of the committed evaluations of g_{i - 1} necessary to
     // compute f_{i - 1} at the k_i-th roots of the in-domain queried points
-    // r_{i, j}^shift
-    pub(crate) query_proofs: Vec<(Vec<F>, M::Proof)>,
+    // r_{i, j}^shift, together with the Merkle proofs of the leaves containing
+    // them. The j-th entry corresponds to the j-th queried index in the order
+    // in which the indices are sampled from the challenger (after removing
+    // repetitions): the verifier relies on this ordering, which the prover
+    // must therefore preserve.
+    pub(crate) query_proofs: Vec<(Vec<F>, Vec<M::Proof>)>,
 
     // Auxiliary polynomial helping the verifier evaluate ans_polynomial at the
-    // queried points
+    // queried points. It is absent with the no-shake feature, in which case
+    // the verifier evaluates ans_polynomial at those points directly
+    #[cfg(not(feature = "no-shake"))]
     pub(crate) shake_polynomial: Polynomial<F>,
 
     // Solution to the proof-of-work challenge in round i
@@ -1,22 +1,38 @@
 use alloc::vec;
 use alloc::vec::Vec;
 
-use itertools::Itertools;
+use itertools::{iterate, Itertools};
 use p3_challenger::{CanObserve, FieldChallenger, GrindingChallenger};
 use p3_commit::Mmcs;
 use p3_coset::TwoAdicCoset;
 use p3_field::{ExtensionField, Field, TwoAdicField};
 use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::{Dimensions, Matrix};
 use p3_poly::Polynomial;
+#[cfg(feature = "blinding")]
+use rand::distr::{Distribution, StandardUniform};
+#[cfg(feature = "blinding")]
+use rand::Rng;
 
 use crate::config::{observe_public_parameters, RoundConfig};
-use crate::proof::RoundProof;
-use crate::utils::{fold_polynomial, multiply_by_power_polynomial, observe_ext_slice_with_size};
-use crate::{Messages, StirConfig, StirProof, POW_BITS_WARNING};
-
+use crate::proof::{FormatVersion, RoundProof};
+use crate::utils::{
+    fold_polynomial, interpolate_bitrev_evals, multiply_by_power_polynomial,
+    num_combination_queries, observe_ext_slice_with_size, observe_paired_commitments,
+    observe_public_inputs, open_fold_group, open_fold_groups, sample_combination_queries,
+    sample_combination_randomness, sample_folding_randomness, sample_next_folding_randomness,
+    sample_ood_points, stack_evaluations, FoldChallengeSource, TranscriptFoldChallenge,
+};
+use crate::{Messages, MixedDegreeProof, StirConfig, StirProof, POW_BITS_WARNING};
+
+#[cfg(any(test, feature = "test-utils"))]
+mod reference;
 #[cfg(test)]
 mod tests;
 
+#[cfg(any(test, feature = "test-utils"))]
+pub use reference::prove_reference;
+
 /// Prover witness for the STIR protocol produced by the [`commit`] method.
 pub struct StirWitness<F: TwoAdicField, M: Mmcs<F>> {
     // Domain L_0
@@ -55,6 +71,224 @@ pub(crate) struct StirRoundWitness<F: TwoAdicField, M: Mmcs<F>> {
     pub(crate) folding_randomness: F,
 }
 
+/// Prover witness together with the commitment shared with the verifier, as
+/// received by [`prove_with_committed_input`]. Unlike the output of [`commit`],
+/// it can be assembled from a commitment to the evaluations of `f_0` produced
+/// by an external component (cf. [`CommittedWitness::new`]).
+pub struct CommittedWitness<F: TwoAdicField, M: Mmcs<F>> {
+    // Witness for the prover
+    witness: StirWitness<F, M>,
+
+    // Commitment to the stacked evaluations of f_0 over L_0
+    commitment: M::Commitment,
+}
+
+impl<F: TwoAdicField, M: Mmcs<F>> CommittedWitness<F, M> {
+    /// Assemble the witness from an existing commitment to the evaluations of
+    /// the initial polynomial `f_0` over the domain [`initial_domain`].
+    ///
+    /// # Parameters
+    ///
+    /// - `config`: Full STIR configuration
+    /// - `polynomial`: Initial polynomial `f_0`
+    /// - `commitment`: Commitment produced by `config.mmcs_config()` to the
+    ///   matrix returned by [`stack_initial_evaluations`]
+    /// - `merkle_tree`: MMCS prover data produced alongside `commitment`
+    ///
+    /// # Panics
+    ///
+    /// Panics if the degree of `polynomial` is too large for the configuration
+    /// or if the committed matrix does not have the dimensions of the one
+    /// returned by [`stack_initial_evaluations`]. The consistency between
+    /// `polynomial` and the committed evaluations is not checked (an
+    /// inconsistent witness simply results in a proof which does not verify).
+    pub fn new(
+        config: &StirConfig<M>,
+        polynomial: Polynomial<F>,
+        commitment: M::Commitment,
+        merkle_tree: M::ProverData<RowMajorMatrix<F>>,
+    ) -> Self {
+        check_initial_degree(config, &polynomial);
+
+        let log_size = config.starting_domain_log_size();
+        let log_width = config.log_starting_folding_factor()
+            - config.log_rows_per_fold_group(config.log_starting_folding_factor());
+
+        assert_eq!(
+            config.mmcs_config().get_matrices(&merkle_tree)[0].dimensions(),
+            Dimensions {
+                width: 1 << log_width,
+                height: 1 << (log_size - log_width),
+            },
+            "The committed matrix does not have the dimensions expected by the configuration"
+        );
+
+        CommittedWitness {
+            witness: StirWitness {
+                domain: initial_domain(config),
+                polynomial,
+                merkle_tree,
+            },
+            commitment,
+        }
+    }
+
+    /// Commitment to the stacked evaluations of `f_0`, to be shared with the
+    /// verifier.
+    pub fn commitment(&self) -> &M::Commitment {
+        &self.commitment
+    }
+}
+
+/// Returns the domain `L_0` over which the initial polynomial `f_0` is
+/// evaluated and committed to.
+pub fn initial_domain<F: TwoAdicField, M: Clone>(config: &StirConfig<M>) -> TwoAdicCoset<F> {
+    let log_size = config.starting_domain_log_size();
+
+    // Initial domain L_0. Letting w be the root generator (cf.
+    // StirConfig::root_generator), the chosen sequence of domains is:
+    //   - L_0 = w * <w> = <w>
+    //   - L_1 = w * <w^2>
+    //   - L_2 = w * <w^4> ...
+    //   - L_i = w * <w^{2^i}> This guarantees that, for all i >= 0, (L_i)^{k_i}
+    // is disjoint from L_{i + 1} (where k_i is the folding factor of the i-th
+    // round), as required for the optimisation mentioned in the article (i. e.
+    // avoiding the use of the Fill polynomials).
+    //
+    // N.B.: Defining L_0 with shift w or 1 is equivalent mathematically, but
+    // the former allows one to always use the method shrink_subgroup in the
+    // following rounds. This shift does not cause significant extra work in
+    // coset.evaluate as it is treated as a special case therein.
+    let generator = config.root_generator();
+    TwoAdicCoset::new_with_generator(generator, generator, log_size)
+}
+
+/// Arrange the evaluations of the initial polynomial `f_0` over
+/// [`initial_domain`] (in the order given by the iterator of the latter) into
+/// the matrix which [`commit`] commits to. Components producing the
+/// commitment externally should commit to the output of this function.
+///
+/// # Panics
+///
+/// Panics if the number of evaluations is not the size of `L_0`.
+pub fn stack_initial_evaluations<F: TwoAdicField, M: Clone>(
+    config: &StirConfig<M>,
+    evals: Vec<F>,
+) -> RowMajorMatrix<F> {
+    assert_eq!(
+        evals.len(),
+        1 << config.starting_domain_log_size(),
+        "The number of evaluations must be the size of the initial domain"
+    );
+
+    // The evaluations are stacked so that each fold group (i. e. the set of
+    // evaluations at the k_1-th roots of a point of L_0^{k_1}) is contained in
+    // consecutive leaves, which facilitates opening values so that the verifier
+    // can check the first folding
+    let log_folding_factor = config.log_starting_folding_factor();
+    stack_evaluations(
+        evals,
+        log_folding_factor,
+        config.log_rows_per_fold_group(log_folding_factor),
+    )
+}
+
+// Compute the output of stack_initial_evaluations on the evaluations of f_0
+// over L_0 tile by tile, without ever holding those evaluations in canonical
+// order next to the stacked matrix. Writing L_0 = w * <g>, of size n = 2^d * R
+// where 2^d is the degree bound, the points of L_0 whose index is congruent
+// to c modulo R form the coset w * g^c * <g^R> of size 2^d, over which f_0 is
+// evaluated with a single DFT and no zero padding. Each such tile is written
+// into the stacked matrix directly, so that the peak memory usage is that of
+// the matrix plus a few tiles rather than twice that of the matrix. The output
+// is identical to that of the one-shot computation.
+#[cfg(feature = "low-memory")]
+pub(crate) fn stack_initial_lde_tiled<F: TwoAdicField, M: Clone>(
+    // Full STIR configuration
+    config: &StirConfig<M>,
+    // Initial polynomial f_0, within the degree bound of the configuration
+    polynomial: &Polynomial<F>,
+) -> RowMajorMatrix<F> {
+    let domain: TwoAdicCoset<F> = initial_domain(config);
+    let log_size = domain.log_size();
+    let log_tile_size = config.log_starting_degree();
+    let log_num_tiles = log_size - log_tile_size;
+
+    // log2 of the folding factor k and of the number n / k of fold groups
+    let log_folding_factor = config.log_starting_folding_factor();
+    let log_num_groups = log_size - log_folding_factor;
+
+    let tile_generator = domain.generator().exp_power_of_2(log_num_tiles);
+    let mut values = vec![F::ZERO; 1 << log_size];
+
+    for c in 0..1 << log_num_tiles {
+        let tile_shift = domain.shift() * domain.generator().exp_u64(c as u64);
+        let tile_evals =
+            TwoAdicCoset::new_with_generator(tile_shift, tile_generator, log_tile_size)
+                .evaluate_polynomial(polynomial.coeffs().to_vec());
+
+        // The m-th evaluation of the tile is that at the point of index
+        // i = c + R * m of L_0, which is the (i div (n / k))-th element of the
+        // (i mod (n / k))-th fold group (cf. stack_evaluations)
+        for (m, eval) in tile_evals.into_iter().enumerate() {
+            let i = c + (m << log_num_tiles);
+            let group = i & ((1 << log_num_groups) - 1);
+            values[(group << log_folding_factor) + (i >> log_num_groups)] = eval;
+        }
+    }
+
+    RowMajorMatrix::new(
+        values,
+        1 << (log_folding_factor - config.log_rows_per_fold_group(log_folding_factor)),
+    )
+}
+
+// Panic if the degree of the polynomial exceeds that supported by the
+// configuration
+fn check_initial_degree<F: TwoAdicField, M: Clone>(
+    // Full STIR configuration
+    config: &StirConfig<M>,
+    // Initial polynomial f_0
+    polynomial: &Polynomial<F>,
+) {
+    assert!(
+        polynomial
+            .degree()
+            .is_none_or(|d| d < (1 << config.log_starting_degree())),
+        "The degree of the polynomial ({}) is too large: the configuration \
+        only supports polynomials of degree up to 2^{} - 1 = {}",
+        polynomial.degree().unwrap(),
+        config.log_starting_degree(),
+        (1 << config.log_starting_degree()) - 1
+    );
+}
+
+// Panic if the witness does not correspond to a polynomial within the degree
+// bound of the configuration, i. e. if its polynomial is too large for it
+// (e. g. because it was committed to under a different configuration) or if
+// the committed codeword is not the evaluation of that polynomial over L_0
+// (e. g. because an external commitment was made to a different codeword).
+// Proving such a witness would produce a proof of a false statement. Since the
+// codeword is recomputed, this is only done with the degree-checks feature.
+#[cfg(feature = "degree-checks")]
+fn check_witness_degree<F: TwoAdicField, M: Mmcs<F>>(
+    // Full STIR configuration
+    config: &StirConfig<M>,
+    // Witness for the prover
+    witness: &StirWitness<F, M>,
+) {
+    check_initial_degree(config, &witness.polynomial);
+
+    let evals = initial_domain(config).evaluate_polynomial(witness.polynomial.coeffs().to_vec());
+
+    assert!(
+        config.mmcs_config().get_matrices(&witness.merkle_tree)[0].values
+            == stack_initial_evaluations(config, evals).values,
+        "The committed codeword is not the evaluation of a polynomial within \
+        the degree bound of the configuration"
+    );
+}
+
 /// Commit to the initial polynomial `f_0` whose low-degreeness is being
 /// asserted. Returns the witness for the prover and the commitment to the
 /// evaluations to be shared with the verifier.
@@ -76,48 +310,26 @@ where
     F: TwoAdicField,
     M: Mmcs<F>,
 {
-    assert!(
-        polynomial
-            .degree()
-            .is_none_or(|d| d < (1 << config.log_starting_degree())),
-        "The degree of the polynomial ({}) is too large: the configuration \
-        only supports polynomials of degree up to 2^{} - 1 = {}",
-        polynomial.degree().unwrap(),
-        config.log_starting_degree(),
-        (1 << config.log_starting_degree()) - 1
-    );
-
-    let log_size = config.log_starting_degree() + config.log_starting_inv_rate();
-
-    // Initial domain L_0. The chosen sequence of domains is:
-    //   - L_0 = w * <w> = <w>
-    //   - L_1 = w * <w^2>
-    //   - L_2 = w * <w^4> ...
-    //   - L_i = w * <w^{2^i}> This guarantees that, for all i >= 0, (L_i)^{k_i}
-    // is disjoint from L_{i + 1} (where k_i is the folding factor of the i-th
-    // round), as required for the optimisation mentioned in the article (i. e.
-    // avoiding the use of the Fill polynomials).
-    //
-    // N.B.: Defining L_0 with shift w or 1 is equivalent mathematically, but
-    // the former allows one to always use the method shrink_subgroup in the
-    // following rounds. This shift does not cause significant extra work in
-    // coset.evaluate as it is treated as a special case therein.
-    let mut domain = TwoAdicCoset::new(F::two_adic_generator(log_size), log_size);
-
-    // Committing to the evaluations of f_0 over L_0.
-    let evals = domain.evaluate_polynomial(polynomial.coeffs().to_vec());
+    check_initial_degree(config, &polynomial);
+
+    // Committing to the evaluations of f_0 over L_0 (cf. initial_domain for
+    // the chosen sequence of domains L_0, L_1, ...). With the low-memory
+    // feature, these are computed tile by tile directly into the stacked
+    // matrix.
+    #[cfg(not(feature = "low-memory"))]
+    let (domain, stacked_evals) = {
+        let mut domain = initial_domain(config);
+        let evals = domain.evaluate_polynomial(polynomial.coeffs().to_vec());
+        (domain, stack_initial_evaluations(config, evals))
+    };
 
-    // The stacking width is
-    //   k_0 = 2^{log_size - config.log_starting_folding_factor},
-    // which facilitates opening values so that the prover can verify the first
-    // folding
-    let stacked_evals = RowMajorMatrix::new(
-        evals,
-        1 << (log_size - config.log_starting_folding_factor()),
-    )
-    .transpose();
+    #[cfg(feature = "low-memory")]
+    let (domain, stacked_evals) = (
+        initial_domain(config),
+        stack_initial_lde_tiled(config, &polynomial),
+    );
 
-    let (commitment, merkle_tree) = config.mmcs_config().commit_matrix(stacked_evals.clone());
+    let (commitment, merkle_tree) = config.mmcs_config().commit_matrix(stacked_evals);
 
     (
         StirWitness {
@@ -129,6 +341,468 @@ where
     )
 }
 
+/// Commit to the initial polynomial `f_0` as in [`commit`] and fold it once
+/// with the given folding randomness, as done at the start of the first round
+/// of [`prove`]. Returns the commitment to `f_0` and the codeword of the folded
+/// polynomial `g_1`, i. e. its evaluations over `L_1` (in the order given by
+/// the iterator of the latter).
+///
+/// These are the dominant costs of the prover. This function lets benchmarks
+/// measure them in isolation and involves no challenger.
+///
+/// # Parameters
+///
+/// - `config`: Full STIR configuration
+/// - `polynomial`: Initial polynomial `f_0`
+/// - `folding_randomness`: Folding randomness `r_0` used to fold `f_0` into
+///   `g_1`
+///
+/// # Panics
+///
+/// Panics if the degree of `polynomial` is too large, as in [`commit`].
+pub fn commit_and_fold_once<F, M>(
+    config: &StirConfig<M>,
+    polynomial: Polynomial<F>,
+    folding_randomness: F,
+) -> (M::Commitment, Vec<F>)
+where
+    F: TwoAdicField,
+    M: Mmcs<F>,
+{
+    let (witness, commitment) = commit(config, polynomial);
+
+    // Obtain g_1 as the folding of f_0
+    let folded_polynomial = fold_polynomial(
+        &witness.polynomial,
+        folding_randomness,
+        config.log_starting_folding_factor(),
+    );
+
+    // Evaluate g_1 over L_1 = w * <w^2>
+    let folded_evals = witness
+        .domain
+        .shrink_subgroup(1)
+        .evaluate_polynomial(folded_polynomial.coeffs().to_vec());
+
+    (commitment, folded_evals)
+}
+
+// Computes, in coefficient form, the final polynomial p = g_{M + 1} sent by the
+// prover from the polynomial f_M of the last round and the folding randomness
+// r_M, i. e. f_M folded with the last folding factor k_M. In a configuration
+// without full rounds, f_M is the input polynomial f_0. Otherwise, f_M depends
+// on the answer polynomials and the randomness of all full rounds and must be
+// computed by the caller.
+#[cfg(test)]
+pub(crate) fn final_polynomial_from_last_round<F: TwoAdicField, M: Clone>(
+    config: &StirConfig<M>,
+    last_round_polynomial: &Polynomial<F>,
+    folding_randomness: F,
+) -> Polynomial<F> {
+    let log_last_folding_factor = *config
+        .log_folding_factors()
+        .last()
+        .expect("Configurations have at least one folding factor");
+
+    fold_polynomial(
+        last_round_polynomial,
+        folding_randomness,
+        log_last_folding_factor,
+    )
+}
+
+/// Prove that the committed polynomial satisfies the low-degreeness bound
+/// specified in the configuration, starting from a [`CommittedWitness`] (which
+/// may have been assembled from an external commitment). This is equivalent to
+/// calling [`prove`] with the witness and commitment contained therein.
+///
+/// # Parameters
+///
+/// - `config`: Full STIR configuration, including the degree bound
+/// - `committed`: Witness for the prover together with the commitment to the
+///   evaluations of the polynomial over `L_0`
+/// - `challenger`: Challenger which produces the transcript of the
+///   Fiat-Shamired interaction
+pub fn prove_with_committed_input<F, EF, M, C>(
+    config: &StirConfig<M>,
+    committed: CommittedWitness<EF, M>,
+    challenger: &mut C,
+) -> StirProof<EF, M, C::Witness>
+where
+    F: Field,
+    EF: TwoAdicField + ExtensionField<F>,
+    M: Mmcs<EF>,
+    C: FieldChallenger<F> + GrindingChallenger + CanObserve<M::Commitment>,
+{
+    let CommittedWitness {
+        witness,
+        commitment,
+    } = committed;
+
+    prove(config, witness, commitment, challenger)
+}
+
+/// Commit to and prove the low-degreeness of the initial polynomial `f_0`
+/// given by its evaluations in bit-reversed order (as output by most DFT
+/// implementations) over the two-adic subgroup of order
+/// `2^config.log_starting_degree()` generated by `EF::two_adic_generator`.
+/// The interpolation consumes the evaluations in that order directly, so that
+/// no bit-reversal permutation is needed. The result is identical to that of
+/// [`commit`] followed by [`prove`] on the interpolated polynomial.
+///
+/// # Parameters
+///
+/// - `config`: Full STIR configuration, including the degree bound
+/// - `evals_bitrev`: Evaluations of `f_0` over the subgroup, the one at
+///   `w^i` being at the bit-reversal of `i` (as a `log_starting_degree`-bit
+///   integer)
+/// - `challenger`: Challenger which produces the transcript of the
+///   Fiat-Shamired interaction
+///
+/// # Panics
+///
+/// Panics if the number of evaluations is not `2^config.log_starting_degree()`.
+pub fn prove_from_bitrev_evals<F, EF, M, C>(
+    config: &StirConfig<M>,
+    evals_bitrev: Vec<EF>,
+    challenger: &mut C,
+) -> (StirProof<EF, M, C::Witness>, M::Commitment)
+where
+    F: Field,
+    EF: TwoAdicField + ExtensionField<F>,
+    M: Mmcs<EF>,
+    C: FieldChallenger<F> + GrindingChallenger + CanObserve<M::Commitment>,
+{
+    assert_eq!(
+        evals_bitrev.len(),
+        1 << config.log_starting_degree(),
+        "The number of evaluations must be the degree bound of the configuration"
+    );
+
+    let polynomial = interpolate_bitrev_evals(evals_bitrev);
+    let (witness, commitment) = commit(config, polynomial);
+    let proof = prove(config, witness, commitment.clone(), challenger);
+
+    (proof, commitment)
+}
+
+/// Commit to and prove the low-degreeness of a blinded version of the initial
+/// polynomial `f_0`. The top `num_blinding_coeffs` coefficients below the
+/// degree bound (i. e. those of `x^d` for
+/// `2^config.log_starting_degree() - num_blinding_coeffs <= d <
+/// 2^config.log_starting_degree()`) are set to values sampled from `rng`
+/// before committing, so that two calls on the same polynomial produce
+/// different commitments and proofs. The blinded polynomial is still within
+/// the degree bound of the configuration, hence the proof is verified by
+/// [`verify`](crate::verify) as usual. The price is that the degree bound
+/// available to `f_0` itself shrinks by `num_blinding_coeffs`.
+///
+/// This does **not** make the proof zero-knowledge unless almost all
+/// coefficients are blinding ones: folding maps the top coefficients of `f_0`
+/// to the top coefficients of the folded polynomials, so the low coefficients
+/// of the final polynomial, which is sent in plain, are combinations of
+/// unblinded coefficients of `f_0` only.
+///
+/// # Parameters
+///
+/// - `config`: Full STIR configuration, including the degree bound
+/// - `polynomial`: Initial polynomial `f_0`
+/// - `num_blinding_coeffs`: Number of random coefficients to append
+/// - `rng`: Source of the blinding coefficients
+/// - `challenger`: Challenger which produces the transcript of the
+///   Fiat-Shamired interaction
+///
+/// # Panics
+///
+/// Panics if `num_blinding_coeffs` exceeds the degree bound or if the degree
+/// of `polynomial` is at least `2^config.log_starting_degree() -
+/// num_blinding_coeffs`, i. e. if it would overlap with the blinding
+/// coefficients.
+#[cfg(feature = "blinding")]
+pub fn prove_blinded<F, EF, M, C, R>(
+    config: &StirConfig<M>,
+    polynomial: Polynomial<EF>,
+    num_blinding_coeffs: usize,
+    rng: &mut R,
+    challenger: &mut C,
+) -> (StirProof<EF, M, C::Witness>, M::Commitment)
+where
+    F: Field,
+    EF: TwoAdicField + ExtensionField<F>,
+    M: Mmcs<EF>,
+    C: FieldChallenger<F> + GrindingChallenger + CanObserve<M::Commitment>,
+    R: Rng,
+    StandardUniform: Distribution<EF>,
+{
+    let degree_bound = 1 << config.log_starting_degree();
+
+    assert!(
+        num_blinding_coeffs <= degree_bound,
+        "The number of blinding coefficients ({num_blinding_coeffs}) exceeds \
+        the degree bound of the configuration ({degree_bound})"
+    );
+
+    let unblinded_bound = degree_bound - num_blinding_coeffs;
+
+    assert!(
+        polynomial.degree().is_none_or(|d| d < unblinded_bound),
+        "The degree of the polynomial ({}) is too large: with {} blinding \
+        coefficients, the configuration only supports polynomials of degree \
+        less than {}",
+        polynomial.degree().unwrap(),
+        num_blinding_coeffs,
+        unblinded_bound
+    );
+
+    let mut coeffs = polynomial.coeffs().to_vec();
+    coeffs.resize(unblinded_bound, EF::ZERO);
+    coeffs.extend((0..num_blinding_coeffs).map(|_| rng.random::<EF>()));
+
+    let (witness, commitment) = commit(config, Polynomial::from_coeffs(coeffs));
+    let proof = prove(config, witness, commitment.clone(), challenger);
+
+    (proof, commitment)
+}
+
+/// Prove two statements, each consisting of a committed polynomial and a
+/// configuration with its own degree bound, under a single shared transcript.
+/// Both commitments are observed before either proof is produced and the two
+/// proofs are then computed one after the other with the same challenger, so
+/// that each of them is bound to both statements. The resulting pair of proofs
+/// is verified with [`verify_pair`](crate::verify_pair).
+///
+/// # Parameters
+///
+/// - `configs`: Full STIR configurations of the first and second statements
+/// - `witnesses`: Witnesses for the prover for the first and second statements
+/// - `commitments`: Commitments to the evaluations of the first and second
+///   polynomials over their respective domains L_0
+/// - `challenger`: Challenger which produces the shared transcript
+pub fn prove_pair<F, EF, M, C>(
+    configs: (&StirConfig<M>, &StirConfig<M>),
+    witnesses: (StirWitness<EF, M>, StirWitness<EF, M>),
+    commitments: (M::Commitment, M::Commitment),
+    challenger: &mut C,
+) -> (StirProof<EF, M, C::Witness>, StirProof<EF, M, C::Witness>)
+where
+    F: Field,
+    EF: TwoAdicField + ExtensionField<F>,
+    M: Mmcs<EF>,
+    C: FieldChallenger<F> + GrindingChallenger + CanObserve<M::Commitment>,
+{
+    observe_paired_commitments::<F, _, _>(challenger, (&commitments.0, &commitments.1));
+
+    let first_proof = prove(configs.0, witnesses.0, commitments.0, challenger);
+    let second_proof = prove(configs.1, witnesses.1, commitments.1, challenger);
+
+    (first_proof, second_proof)
+}
+
+/// Prover for a sequence of related statements whose proofs are chained
+/// through a single transcript: each proof is produced with the challenger in
+/// the state left by the previous one, so that it is bound to all previous
+/// statements and proofs, and the final state of the challenger binds them
+/// all. Unlike [`prove_pair`], the statements need not be known in advance.
+/// The chain is verified with [`ChainedVerifier`](crate::ChainedVerifier),
+/// which rejects proofs that are replayed out of their chain or reordered.
+pub struct ChainedProver<C> {
+    // Challenger threaded through all the proofs of the chain
+    challenger: C,
+
+    // Number of proofs produced so far
+    num_proofs: usize,
+}
+
+impl<C> ChainedProver<C> {
+    /// Starts a chain of proofs whose transcript is produced by `challenger`.
+    pub fn new(challenger: C) -> Self {
+        Self {
+            challenger,
+            num_proofs: 0,
+        }
+    }
+
+    /// Proves the next statement of the chain as in [`prove`].
+    ///
+    /// # Parameters
+    ///
+    /// - `config`: Full STIR configuration of the statement
+    /// - `witness`: Witness for the prover
+    /// - `commitment`: Commitment to the evaluations of the polynomial over L_0
+    pub fn prove<F, EF, M>(
+        &mut self,
+        config: &StirConfig<M>,
+        witness: StirWitness<EF, M>,
+        commitment: M::Commitment,
+    ) -> StirProof<EF, M, C::Witness>
+    where
+        F: Field,
+        EF: TwoAdicField + ExtensionField<F>,
+        M: Mmcs<EF>,
+        C: FieldChallenger<F> + GrindingChallenger + CanObserve<M::Commitment>,
+    {
+        self.num_proofs += 1;
+        prove(config, witness, commitment, &mut self.challenger)
+    }
+
+    /// Number of proofs in the chain so far.
+    pub fn num_proofs(&self) -> usize {
+        self.num_proofs
+    }
+
+    /// Ends the chain, returning the challenger in its final state, which
+    /// binds all the proofs of the chain (e. g. to continue a larger protocol).
+    pub fn into_challenger(self) -> C {
+        self.challenger
+    }
+}
+
+/// Commit to several polynomials `f_j` with individual degree bounds `d_j`
+/// (e. g. the trace and quotient chunks of a STARK) and prove that each of them
+/// satisfies its bound with a single STIR instance. The polynomials are padded
+/// to the common degree bound `D = 2^config.log_starting_degree()` by shifting
+/// each `f_j` by `x^(D - d_j)` and combined with the powers of a random
+/// `alpha`:
+///   `g = f_0 * x^(D - d_0) + alpha * f_1 * x^(D - d_1) + alpha^2 * ...`,
+/// which has degree less than `D` if and only if (with high probability) each
+/// `f_j` has degree less than `d_j`. The low-degreeness of `g` is proven with
+/// [`prove`], and `g` is checked against the committed `f_j` at random points
+/// of `L_0` (as many as the first round queries), so that the verifier
+/// re-checks each individual bound. The result is verified with
+/// [`verify_mixed_degrees`](crate::verify_mixed_degrees).
+///
+/// # Parameters
+///
+/// - `config`: Full STIR configuration, whose degree bound is the common
+///   bound `D`
+/// - `polys_with_bounds`: The polynomials `f_j` together with their degree
+///   bounds `d_j` (i. e. `f_j` must have degree less than `d_j`)
+/// - `challenger`: Challenger which produces the transcript of the
+///   Fiat-Shamired interaction
+///
+/// # Panics
+///
+/// Panics if `polys_with_bounds` is empty, if some bound `d_j` is greater than
+/// `D` or if some polynomial does not satisfy its bound.
+pub fn prove_mixed_degrees<F, EF, M, C>(
+    config: &StirConfig<M>,
+    polys_with_bounds: Vec<(Polynomial<EF>, usize)>,
+    challenger: &mut C,
+) -> MixedDegreeProof<EF, M, C::Witness>
+where
+    F: Field,
+    EF: TwoAdicField + ExtensionField<F>,
+    M: Mmcs<EF>,
+    C: FieldChallenger<F> + GrindingChallenger + CanObserve<M::Commitment>,
+{
+    let degree_bound = 1 << config.log_starting_degree();
+
+    assert!(
+        !polys_with_bounds.is_empty(),
+        "At least one polynomial must be provided"
+    );
+
+    for (polynomial, bound) in &polys_with_bounds {
+        assert!(
+            *bound <= degree_bound,
+            "The degree bound {bound} exceeds that of the configuration ({degree_bound})"
+        );
+        assert!(
+            polynomial.degree().is_none_or(|d| d < *bound),
+            "The degree of the polynomial ({}) is not lower than its bound ({bound})",
+            polynomial.degree().unwrap()
+        );
+    }
+
+    let degree_bounds = polys_with_bounds
+        .iter()
+        .map(|(_, bound)| *bound)
+        .collect_vec();
+
+    let log_folding_factor = config.log_starting_folding_factor();
+    let folding_factor = 1 << log_folding_factor;
+
+    let mut domain = initial_domain(config);
+    let log_num_groups = domain.log_size() - log_folding_factor;
+    let num_groups = 1 << log_num_groups;
+
+    // Committing to the evaluations of the f_j over L_0 in a single matrix
+    // whose i-th row contains the i-th fold group of each f_j in turn, so that
+    // each consistency query opens a single row. The t-th element of the i-th
+    // fold group is the evaluation at the (i + t * num_groups)-th point of L_0.
+    let evals = polys_with_bounds
+        .iter()
+        .map(|(polynomial, _)| domain.evaluate_polynomial(polynomial.coeffs().to_vec()))
+        .collect_vec();
+
+    let inputs_matrix = RowMajorMatrix::new(
+        (0..num_groups)
+            .flat_map(|i| {
+                evals.iter().flat_map(move |f_evals| {
+                    (0..folding_factor).map(move |t| f_evals[i + t * num_groups])
+                })
+            })
+            .collect(),
+        evals.len() * folding_factor,
+    );
+
+    let (inputs_commitment, inputs_data) = config.mmcs_config().commit_matrix(inputs_matrix);
+
+    // Combining the f_j into g (cf. utils::combine_mixed_degree_evaluations)
+    let alpha: EF = sample_combination_randomness::<F, EF, _, _>(
+        challenger,
+        &degree_bounds,
+        &inputs_commitment,
+    );
+
+    let combination = polys_with_bounds
+        .iter()
+        .zip(iterate(EF::ONE, |&c| c * alpha))
+        .fold(
+            Polynomial::zero(),
+            |mut acc, ((polynomial, bound), coeff)| {
+                acc.add_scaled_assign(coeff, &polynomial.shift_up(degree_bound - bound));
+                acc
+            },
+        );
+
+    let (witness, combination_commitment) = commit(config, combination);
+
+    // Opening the f_j and g at the fold groups of the consistency queries
+    let queried_indices = sample_combination_queries::<F, _, _>(
+        challenger,
+        &combination_commitment,
+        log_num_groups,
+        num_combination_queries(config),
+    );
+
+    let consistency_queries = queried_indices
+        .into_iter()
+        .map(|i| {
+            let (mut openings, proof) = config.mmcs_config().open_batch(i, &inputs_data);
+
+            (
+                (openings.remove(0), proof),
+                open_fold_group(
+                    config.mmcs_config(),
+                    i,
+                    config.log_rows_per_fold_group(log_folding_factor),
+                    &witness.merkle_tree,
+                ),
+            )
+        })
+        .collect();
+
+    let stir_proof = prove(config, witness, combination_commitment.clone(), challenger);
+
+    MixedDegreeProof {
+        inputs_commitment,
+        combination_commitment,
+        consistency_queries,
+        stir_proof,
+    }
+}
+
 /// Prove that the committed polynomial satisfies the low-degreeness bound
 /// specified in the configuration.
 ///
@@ -139,7 +813,19 @@ where
 ///   prover data
 /// - `commitment`: Commitment to the evaluations of the polynomial over L_0
 /// - `challenger`: Challenger which produces the transcript of the
-///   Fiat-Shamired interaction
+///   Fiat-Shamired interaction. Any challenger over the base field `F` of
+///   `EF` which can grind proof-of-work witnesses and observe `M`'s
+///   commitments works: `FieldChallenger<F>` is only the umbrella trait for
+///   `CanObserve<F> + CanSample<F> + CanSampleBits<usize>`, which also pins
+///   down `F`.
+///
+/// # Panics
+///
+/// With the `degree-checks` feature, panics if the polynomial of the witness
+/// is too large for the configuration or if the committed codeword is not its
+/// evaluation over `L_0` (e. g. after an external commitment through
+/// [`CommittedWitness::new`]), in which case the proof would assert a false
+/// statement. These checks recompute the initial codeword.
 pub fn prove<F, EF, M, C>(
     config: &StirConfig<M>,
     witness: StirWitness<EF, M>,
@@ -152,6 +838,81 @@ where
     M: Mmcs<EF>,
     C: FieldChallenger<F> + GrindingChallenger + CanObserve<M::Commitment>,
 {
+    prove_with_fold_challenge_source(
+        config,
+        witness,
+        commitment,
+        challenger,
+        &TranscriptFoldChallenge,
+    )
+}
+
+/// Variant of [`prove`] for statements with public inputs, which are observed
+/// by the challenger (preceded by their number) before anything else, so that
+/// the proof is bound to them. The resulting proof can only be verified with
+/// [`verify_with_public_inputs`](crate::verify_with_public_inputs) and the
+/// same public inputs. Passing no public inputs is equivalent to calling
+/// [`prove`].
+///
+/// # Parameters
+///
+/// - `config`, `witness`, `commitment`, `challenger`: Same as in [`prove`].
+/// - `public_inputs`: Public context of the statement, in the base field of
+///   the challenger.
+///
+/// # Panics
+///
+/// Same as [`prove`].
+pub fn prove_with_public_inputs<F, EF, M, C>(
+    config: &StirConfig<M>,
+    witness: StirWitness<EF, M>,
+    commitment: M::Commitment,
+    public_inputs: &[F],
+    challenger: &mut C,
+) -> StirProof<EF, M, C::Witness>
+where
+    F: Field,
+    EF: TwoAdicField + ExtensionField<F>,
+    M: Mmcs<EF>,
+    C: FieldChallenger<F> + GrindingChallenger + CanObserve<M::Commitment>,
+{
+    observe_public_inputs(challenger, public_inputs);
+
+    prove(config, witness, commitment, challenger)
+}
+
+/// Variant of [`prove`] deriving the folding randomness through the given
+/// [`FoldChallengeSource`] rather than sampling it directly from the
+/// challenger. The resulting proof can only be verified with
+/// [`verify_with_fold_challenge_source`](crate::verify_with_fold_challenge_source)
+/// and an equivalent source.
+///
+/// # Parameters
+///
+/// - `config`, `witness`, `commitment`, `challenger`: Same as in [`prove`].
+/// - `source`: Derivation of the folding randomness `r_i` from the
+///   transcript.
+///
+/// # Panics
+///
+/// Same as [`prove`].
+pub fn prove_with_fold_challenge_source<F, EF, M, C, S>(
+    config: &StirConfig<M>,
+    witness: StirWitness<EF, M>,
+    commitment: M::Commitment,
+    challenger: &mut C,
+    source: &S,
+) -> StirProof<EF, M, C::Witness>
+where
+    F: Field,
+    EF: TwoAdicField + ExtensionField<F>,
+    M: Mmcs<EF>,
+    C: FieldChallenger<F> + GrindingChallenger + CanObserve<M::Commitment>,
+    S: FoldChallengeSource<F>,
+{
+    #[cfg(feature = "degree-checks")]
+    check_witness_degree(config, &witness);
+
     // Inform the prover if the configuration requires a proof of work larger
     // than the POW_BITS_WARNING constant. This is only logged if the tracing
     // module has been init()ialised.
@@ -174,8 +935,7 @@ where
     challenger.observe(commitment.clone());
 
     // Sample the folding randomness r_0
-    challenger.observe(F::from_u8(Messages::FoldingRandomness as u8));
-    let folding_randomness: EF = challenger.sample_algebra_element();
+    let folding_randomness: EF = sample_folding_randomness(challenger, source, 0);
 
     // Enriching the initial witness into a full round witness that prove_round
     // can receive.
@@ -189,8 +949,8 @@ where
 
     // Prove each full round i = 1, ..., M of the protocol
     let mut round_proofs = vec![];
-    for _ in 1..=config.num_rounds() - 1 {
-        let (new_witness, round_proof) = prove_round(config, witness, challenger);
+    for _ in 1..=config.num_full_rounds() {
+        let (new_witness, round_proof) = prove_round(config, witness, challenger, source);
 
         witness = new_witness;
         round_proofs.push(round_proof);
@@ -225,20 +985,23 @@ where
 
     // Opening the cosets of evaluations of g_M at each k_M-th root of the
     // points queried
-    let queries_to_final: Vec<(Vec<EF>, M::Proof)> = queried_indices
-        .into_iter()
-        .map(|index| {
-            config
-                .mmcs_config()
-                .open_batch(index as usize, &witness.merkle_tree)
-        })
-        .map(|(mut k, v)| (k.remove(0), v))
-        .collect();
+    let log_rows_per_group = config.log_rows_per_fold_group(log_last_folding_factor);
+    let queries_to_final: Vec<(Vec<EF>, Vec<M::Proof>)> = open_fold_groups(
+        config.mmcs_config(),
+        &queried_indices
+            .into_iter()
+            .map(|index| index as usize)
+            .collect_vec(),
+        log_rows_per_group,
+        &witness.merkle_tree,
+    );
 
     // Compute the proof-of-work for the final round
     let pow_witness = challenger.grind(config.final_pow_bits());
 
     StirProof {
+        format_version: FormatVersion,
+        parameter_fingerprint: config.parameter_fingerprint(),
         round_proofs,
         final_polynomial,
         pow_witness,
@@ -246,9 +1009,37 @@ where
     }
 }
 
+/// Builds the polynomial `Ans_i` sent by the prover in full round `i`, namely
+/// the interpolant of `g_i` at both the out-of-domain points and the in-domain
+/// points `r^shift_{i, j}` obtained from the queried indices. This is exactly
+/// the computation performed by the prover, so its output matches the
+/// `ans_polynomial` of an honest round proof.
+///
+/// # Parameters
+///
+/// - `ood`: The pairs `(x, beta)` of out-of-domain points and replies to them.
+///   Repeated pairs are only taken into account once.
+/// - `shifts`: The pairs `(r^shift_{i, j}, g_i(r^shift_{i, j}))` of (distinct)
+///   in-domain queried points and the evaluations of `g_i` at them.
+///
+/// # Panics
+///
+/// Panics if both `ood` and `shifts` are empty or if some point occurs with two
+/// different values (in which case no interpolant exists).
+pub fn build_answer_polynomial<F: Field>(ood: &[(F, F)], shifts: &[(F, F)]) -> Polynomial<F> {
+    let answers = ood
+        .iter()
+        .copied()
+        .unique()
+        .chain(shifts.iter().copied())
+        .collect_vec();
+
+    Polynomial::lagrange_interpolation(answers)
+}
+
 /// Prove a single full round, taking in a witness for the previous round and
 /// returning a witness for the new one as well as the round proof.
-pub(crate) fn prove_round<F, EF, M, C>(
+pub(crate) fn prove_round<F, EF, M, C, S>(
     // Full STIR configuration from which the round-specific configuration is
     // extracted
     config: &StirConfig<M>,
@@ -256,12 +1047,15 @@ pub(crate) fn prove_round<F, EF, M, C>(
     witness: StirRoundWitness<EF, M>,
     // FS challenger
     challenger: &mut C,
+    // Derivation of the folding randomness from the transcript
+    source: &S,
 ) -> (StirRoundWitness<EF, M>, RoundProof<EF, M, C::Witness>)
 where
     F: Field,
     EF: TwoAdicField + ExtensionField<F>,
     M: Mmcs<EF>,
     C: FieldChallenger<F> + GrindingChallenger + CanObserve<M::Commitment>,
+    S: FoldChallengeSource<F>,
 {
     let round = witness.round + 1;
 
@@ -297,11 +1091,11 @@ where
     // Stack the evaluations, commit to them (in preparation for
     // next-round-folding verification, and therefore with width equal to the
     // folding factor of the next round) and then observe the commitment
-    let new_stacked_evals = RowMajorMatrix::new(
+    let new_stacked_evals = stack_evaluations(
         folded_evals,
-        1 << (new_domain.log_size() - log_next_folding_factor),
-    )
-    .transpose();
+        log_next_folding_factor,
+        config.log_rows_per_fold_group(log_next_folding_factor),
+    );
 
     let (new_commitment, new_merkle_tree) = config
         .mmcs_config()
@@ -313,15 +1107,14 @@ where
 
     // ======================== Out-of-domain sampling ========================
 
-    let mut ood_samples = Vec::new();
+    let ood_samples = sample_ood_points(challenger, &new_domain, num_ood_samples);
 
-    challenger.observe(F::from_u8(Messages::OodSamples as u8));
-    while ood_samples.len() < num_ood_samples {
-        let el: EF = challenger.sample_algebra_element();
-        if !new_domain.contains(el) {
-            ood_samples.push(el);
-        }
-    }
+    // Repeated points are resampled, so this can only fail with a degenerate
+    // challenger
+    assert!(
+        ood_samples.iter().all_unique(),
+        "Failed to sample distinct out-of-domain points in round {round}"
+    );
 
     // Evaluate the polynomial at the out-of-domain sampled points
     let betas: Vec<EF> = ood_samples
@@ -342,8 +1135,13 @@ where
     let comb_randomness = challenger.sample_algebra_element();
 
     // Sample folding randomness for the next round
-    challenger.observe(F::from_u8(Messages::FoldingRandomness as u8));
-    let new_folding_randomness = challenger.sample_algebra_element();
+    let new_folding_randomness = sample_next_folding_randomness(
+        config.batched_folding_randomness(),
+        folding_randomness,
+        challenger,
+        source,
+        round,
+    );
 
     // Sample queried indices of elements in L_{i - 1}^k_{i - 1}
     let log_query_domain_size = domain.log_size() - log_folding_factor;
@@ -359,18 +1157,18 @@ where
 
     // ======================= Open queried evaluations =======================
 
-    // Open the Merkle paths for the queried indices
-    let query_proofs: Vec<(Vec<EF>, M::Proof)> = queried_indices
-        .clone()
-        .into_iter()
-        .map(|index| config.mmcs_config().open_batch(index, &merkle_tree))
-        .map(|(mut k, v)| (k.remove(0), v))
-        .collect();
+    // Open the Merkle paths for all the queried indices together
+    let query_proofs: Vec<(Vec<EF>, Vec<M::Proof>)> = open_fold_groups(
+        config.mmcs_config(),
+        &queried_indices,
+        config.log_rows_per_fold_group(log_folding_factor),
+        &merkle_tree,
+    );
 
     // ============= Computing the Quot, Ans and shake polynomials =============
 
     // Compute the domain L_{i - 1}^{k_{i - 1}}
-    let mut domain_k = domain.shrink_coset(log_folding_factor);
+    let mut domain_k = domain.fold(log_folding_factor);
 
     // Get the domain elements at the queried indices (i.e r^shift_i in the paper)
     let stir_randomness: Vec<EF> = queried_indices
@@ -384,35 +1182,51 @@ where
         .map(|x| folded_polynomial.evaluate(x))
         .collect();
 
+    let stir_answers = stir_randomness
+        .into_iter()
+        .zip(stir_randomness_evals)
+        .collect_vec();
+    let beta_answers = ood_samples.into_iter().zip(betas.clone()).collect_vec();
+
+    // Compute the Ans polynomial and add it to the transcript
+    let ans_polynomial = build_answer_polynomial(&beta_answers, &stir_answers);
+
     // stir_answers has been dedup-ed but beta_answers has not yet:
-    let stir_answers = stir_randomness.into_iter().zip(stir_randomness_evals);
-    let beta_answers = ood_samples.into_iter().zip(betas.clone()).unique();
-    let quotient_answers = beta_answers.chain(stir_answers).collect_vec();
+    let quotient_answers = beta_answers
+        .into_iter()
+        .unique()
+        .chain(stir_answers)
+        .collect_vec();
 
     // Compute the quotient set, \mathcal{G}_i in the notation of the article
     let quotient_set = quotient_answers.iter().map(|(x, _)| *x).collect_vec();
     let quotient_set_size = quotient_set.len();
 
-    // Compute the Ans polynomial and add it to the transcript
-    let ans_polynomial = Polynomial::<EF>::lagrange_interpolation(quotient_answers.clone());
     challenger.observe(F::from_u8(Messages::AnsPolynomial as u8));
     observe_ext_slice_with_size(challenger, ans_polynomial.coeffs());
 
-    // Compute the shake polynomial and add it to the transcript
-    let shake_polynomial = compute_shake_polynomial(&ans_polynomial, quotient_answers.into_iter());
-    challenger.observe(F::from_u8(Messages::ShakePolynomial as u8));
-    observe_ext_slice_with_size(challenger, shake_polynomial.coeffs());
-
-    // Shake randomness: this is only used by the verifier, but it doesn't need
-    // to be kept private. Therefore, the verifier can sample it from the
-    // challenger, in which case the prover must follow suit to keep the
-    // challengers in sync.
-    challenger.observe(F::from_u8(Messages::ShakeRandomness as u8));
-    let _shake_randomness: EF = challenger.sample_algebra_element();
+    // Compute the shake polynomial and add it to the transcript. With the
+    // no-shake feature, the verifier evaluates Ans directly instead and the
+    // shake polynomial is neither computed nor sent.
+    #[cfg(not(feature = "no-shake"))]
+    let shake_polynomial = {
+        let shake_polynomial =
+            compute_shake_polynomial(&ans_polynomial, quotient_answers.into_iter());
+        challenger.observe(F::from_u8(Messages::ShakePolynomial as u8));
+        observe_ext_slice_with_size(challenger, shake_polynomial.coeffs());
+
+        // Shake randomness: this is only used by the verifier, but it doesn't
+        // need to be kept private. Therefore, the verifier can sample it from
+        // the challenger, in which case the prover must follow suit to keep
+        // the challengers in sync.
+        challenger.observe(F::from_u8(Messages::ShakeRandomness as u8));
+        let _shake_randomness: EF = challenger.sample_algebra_element();
+
+        shake_polynomial
+    };
 
     // Compute the Quot polynomial
-    let vanishing_polynomial = Polynomial::vanishing_polynomial(quotient_set);
-    let quotient_polynomial = &(&folded_polynomial - &ans_polynomial) / &vanishing_polynomial;
+    let quotient_polynomial = fold_quotient(&folded_polynomial, &ans_polynomial, quotient_set);
 
     // Correct the degree by multiplying by the scaling polynomial,
     //   1 + rx + r^2 x^2 + ... + r^n x^n
@@ -445,14 +1259,37 @@ where
             betas,
             ans_polynomial,
             query_proofs,
+            #[cfg(not(feature = "no-shake"))]
             shake_polynomial,
             pow_witness,
         },
     )
 }
 
+/// Compute the quotient `Quot(g_i, Ans_i) = (g_i - Ans_i) / V` used in the
+/// fold step, where `V` is the vanishing polynomial of `query_points` (i. e.
+/// of the quotient set `\mathcal{G}_i` in the notation of the article).
+///
+/// # Panics
+///
+/// Panics if `query_points` is empty or if `polynomial - ans_polynomial` is not
+/// divisible by `V`, i. e. if `ans_polynomial` does not agree with
+/// `polynomial` at every point of `query_points`.
+pub(crate) fn fold_quotient<F: TwoAdicField>(
+    // The polynomial g_i being quotiented
+    polynomial: &Polynomial<F>,
+    // The polynomial Ans_i interpolating g_i at the queried points
+    ans_polynomial: &Polynomial<F>,
+    // The queried points, which form the quotient set
+    query_points: impl IntoIterator<Item = F>,
+) -> Polynomial<F> {
+    let vanishing_polynomial = Polynomial::vanishing_polynomial(query_points);
+    &(polynomial - ans_polynomial) / &vanishing_polynomial
+}
+
 // Compute the shake polynomial which allows the verifier to evaluate the Ans
 // polynomial at all points which it purportedly interpolates.
+#[cfg(not(feature = "no-shake"))]
 fn compute_shake_polynomial<F: TwoAdicField>(
     ans_polynomial: &Polynomial<F>,
     quotient_answers: impl Iterator<Item = (F, F)>,
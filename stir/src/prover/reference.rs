@@ -0,0 +1,283 @@
+use alloc::vec::Vec;
+
+use itertools::Itertools;
+use p3_challenger::{CanObserve, FieldChallenger, GrindingChallenger};
+use p3_commit::Mmcs;
+use p3_coset::TwoAdicCoset;
+use p3_field::{ExtensionField, Field, TwoAdicField};
+use p3_matrix::dense::RowMajorMatrix;
+use p3_poly::Polynomial;
+
+use super::{build_answer_polynomial, StirWitness};
+use crate::config::{observe_public_parameters, RoundConfig};
+use crate::proof::{FormatVersion, RoundProof};
+use crate::utils::{
+    observe_ext_slice_with_size, open_fold_group, sample_folding_randomness,
+    sample_next_folding_randomness, sample_ood_points, stack_evaluations, TranscriptFoldChallenge,
+};
+use crate::{Messages, StirConfig, StirProof};
+
+/// Slow but simple version of [`prove`](crate::prove), meant as an oracle for
+/// differential testing of the latter. It follows the same protocol with the
+/// same transcript, but computes everything with direct polynomial operations:
+/// codewords are evaluated point by point with Horner's method rather than with
+/// FFTs, polynomials are folded from their strided sub-polynomials, quotients
+/// are computed by dividing by one linear factor at a time and products are
+/// computed naively. Merkle openings are made one fold group at a time. Given
+/// the same witness and challenger, it therefore produces the same proof as
+/// [`prove`](crate::prove), which can be checked with tests.
+///
+/// This is quadratic in the degree bound and only intended for tests.
+///
+/// # Parameters
+///
+/// Same as [`prove`](crate::prove).
+pub fn prove_reference<F, EF, M, C>(
+    config: &StirConfig<M>,
+    witness: StirWitness<EF, M>,
+    commitment: M::Commitment,
+    challenger: &mut C,
+) -> StirProof<EF, M, C::Witness>
+where
+    F: Field,
+    EF: TwoAdicField + ExtensionField<F>,
+    M: Mmcs<EF>,
+    C: FieldChallenger<F> + GrindingChallenger + CanObserve<M::Commitment>,
+{
+    observe_public_parameters(config.parameters(), challenger);
+
+    challenger.observe(F::from_u8(Messages::Commitment as u8));
+    challenger.observe(commitment);
+
+    let mut folding_randomness: EF =
+        sample_folding_randomness(challenger, &TranscriptFoldChallenge, 0);
+
+    let StirWitness {
+        mut domain,
+        mut polynomial,
+        mut merkle_tree,
+    } = witness;
+
+    let mut round_proofs = Vec::new();
+
+    // ============================== Full rounds ==============================
+    for round in 1..=config.num_full_rounds() {
+        let RoundConfig {
+            log_folding_factor,
+            log_next_folding_factor,
+            pow_bits,
+            num_queries,
+            num_ood_samples,
+            ..
+        } = config.round_config(round).clone();
+
+        // Fold f_{i - 1} into g_i and commit to its evaluations over L_i
+        let folded_polynomial = fold_reference(&polynomial, folding_randomness, log_folding_factor);
+        let new_domain = domain.shrink_subgroup(1);
+
+        let (new_commitment, new_merkle_tree) =
+            config.mmcs_config().commit_matrix(stack_evaluations(
+                evaluate_reference(&folded_polynomial, &new_domain),
+                log_next_folding_factor,
+                config.log_rows_per_fold_group(log_next_folding_factor),
+            ));
+
+        challenger.observe(F::from_u8(Messages::RoundCommitment as u8));
+        challenger.observe(new_commitment.clone());
+
+        // Out-of-domain samples and replies
+        let ood_samples = sample_ood_points(challenger, &new_domain, num_ood_samples);
+        let betas = ood_samples
+            .iter()
+            .map(|x| folded_polynomial.evaluate(x))
+            .collect_vec();
+
+        challenger.observe(F::from_u8(Messages::Betas as u8));
+        for &beta in &betas {
+            challenger.observe_algebra_element(beta);
+        }
+
+        challenger.observe(F::from_u8(Messages::CombRandomness as u8));
+        let comb_randomness: EF = challenger.sample_algebra_element();
+
+        let new_folding_randomness = sample_next_folding_randomness(
+            config.batched_folding_randomness(),
+            folding_randomness,
+            challenger,
+            &TranscriptFoldChallenge,
+            round,
+        );
+
+        // Queries to L_{i - 1}^{k_{i - 1}}
+        let mut domain_k = domain.fold(log_folding_factor);
+
+        challenger.observe(F::from_u8(Messages::QueryIndices as u8));
+        let queried_indices = (0..num_queries)
+            .map(|_| challenger.sample_bits(domain_k.log_size()))
+            .unique()
+            .collect_vec();
+
+        let pow_witness = challenger.grind(pow_bits);
+
+        let query_proofs =
+            open_reference(config, &queried_indices, log_folding_factor, &merkle_tree);
+
+        // Ans_i, interpolating g_i at the out-of-domain and queried points
+        let stir_answers = queried_indices
+            .iter()
+            .map(|&index| {
+                let point = domain_k.element(index);
+                (point, folded_polynomial.evaluate(&point))
+            })
+            .collect_vec();
+        let beta_answers = ood_samples.into_iter().zip(betas.clone()).collect_vec();
+
+        let ans_polynomial = build_answer_polynomial(&beta_answers, &stir_answers);
+
+        let quotient_answers = beta_answers
+            .into_iter()
+            .unique()
+            .chain(stir_answers)
+            .collect_vec();
+
+        challenger.observe(F::from_u8(Messages::AnsPolynomial as u8));
+        observe_ext_slice_with_size(challenger, ans_polynomial.coeffs());
+
+        #[cfg(not(feature = "no-shake"))]
+        let shake_polynomial = {
+            let shake_polynomial =
+                quotient_answers
+                    .iter()
+                    .fold(Polynomial::zero(), |acc, &(x, y)| {
+                        let (quotient, _) =
+                            (&ans_polynomial - &y).divide_by_vanishing_linear_polynomial(x);
+                        &acc + &quotient
+                    });
+
+            challenger.observe(F::from_u8(Messages::ShakePolynomial as u8));
+            observe_ext_slice_with_size(challenger, shake_polynomial.coeffs());
+
+            challenger.observe(F::from_u8(Messages::ShakeRandomness as u8));
+            let _shake_randomness: EF = challenger.sample_algebra_element();
+
+            shake_polynomial
+        };
+
+        // f_i = Quot(g_i, Ans_i) * (1 + r x + ... + r^n x^n), n = |G_i|
+        let quotient_polynomial =
+            quotient_answers
+                .iter()
+                .fold(&folded_polynomial - &ans_polynomial, |acc, &(x, _)| {
+                    let (quotient, remainder) = acc.divide_by_vanishing_linear_polynomial(x);
+                    assert!(remainder.is_zero(), "Ans_i does not interpolate g_i");
+                    quotient
+                });
+
+        polynomial = quotient_polynomial.mul_naive(&Polynomial::power_polynomial(
+            comb_randomness,
+            quotient_answers.len(),
+        ));
+
+        round_proofs.push(RoundProof {
+            g_root: new_commitment,
+            betas,
+            ans_polynomial,
+            query_proofs,
+            #[cfg(not(feature = "no-shake"))]
+            shake_polynomial,
+            pow_witness,
+        });
+
+        domain = new_domain;
+        merkle_tree = new_merkle_tree;
+        folding_randomness = new_folding_randomness;
+    }
+
+    // ============================== Final round ==============================
+    let log_last_folding_factor = config.log_last_folding_factor();
+
+    let final_polynomial = fold_reference(&polynomial, folding_randomness, log_last_folding_factor);
+
+    challenger.observe(F::from_u8(Messages::FinalPolynomial as u8));
+    observe_ext_slice_with_size(challenger, final_polynomial.coeffs());
+
+    challenger.observe(F::from_u8(Messages::FinalQueryIndices as u8));
+    let queried_indices = (0..config.final_num_queries())
+        .map(|_| challenger.sample_bits(domain.log_size() - log_last_folding_factor))
+        .unique()
+        .collect_vec();
+
+    let final_round_queries = open_reference(
+        config,
+        &queried_indices,
+        log_last_folding_factor,
+        &merkle_tree,
+    );
+
+    let pow_witness = challenger.grind(config.final_pow_bits());
+
+    StirProof {
+        format_version: FormatVersion,
+        parameter_fingerprint: config.parameter_fingerprint(),
+        round_proofs,
+        final_polynomial,
+        pow_witness,
+        final_round_queries,
+    }
+}
+
+// Folds the polynomial f into sum_j c^j * f_j, where f_0, ..., f_{k - 1} are
+// the strided sub-polynomials of f (cf. Polynomial::split_radix)
+fn fold_reference<F: TwoAdicField>(
+    // The polynomial to fold
+    polynomial: &Polynomial<F>,
+    // The folding coefficient
+    c: F,
+    // The log2 of the folding factor
+    log_folding_factor: usize,
+) -> Polynomial<F> {
+    let mut folded = Polynomial::zero();
+    let mut power = F::ONE;
+
+    for sub_polynomial in polynomial.split_radix(log_folding_factor) {
+        folded.add_scaled_assign(power, &sub_polynomial);
+        power *= c;
+    }
+
+    folded
+}
+
+// Evaluates the polynomial at each point of the domain (in the order given by
+// the iterator of the latter) with Horner's method
+fn evaluate_reference<F: TwoAdicField>(
+    // The polynomial to evaluate
+    polynomial: &Polynomial<F>,
+    // The evaluation domain
+    domain: &TwoAdicCoset<F>,
+) -> Vec<F> {
+    domain.iter().map(|x| polynomial.evaluate(&x)).collect()
+}
+
+// Opens the fold groups at the given indices one at a time
+fn open_reference<F: Field, M: Mmcs<F>>(
+    // Full STIR configuration
+    config: &StirConfig<M>,
+    // The indices of the fold groups to open
+    indices: &[usize],
+    // The log2 of the folding factor of the committed codeword
+    log_folding_factor: usize,
+    // The prover data produced when committing to the codeword
+    prover_data: &M::ProverData<RowMajorMatrix<F>>,
+) -> Vec<(Vec<F>, Vec<M::Proof>)> {
+    indices
+        .iter()
+        .map(|&index| {
+            open_fold_group(
+                config.mmcs_config(),
+                index,
+                config.log_rows_per_fold_group(log_folding_factor),
+                prover_data,
+            )
+        })
+        .collect()
+}
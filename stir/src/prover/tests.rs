@@ -1,22 +1,30 @@
 use alloc::vec::Vec;
 
 use itertools::Itertools;
-use p3_challenger::MockChallenger;
+use p3_challenger::{CanObserve, MockChallenger};
 use p3_commit::Mmcs;
 use p3_coset::TwoAdicCoset;
-use p3_field::PrimeCharacteristicRing;
+use p3_field::{Field, PrimeCharacteristicRing, TwoAdicField};
 use p3_matrix::dense::RowMajorMatrix;
 use p3_matrix::Matrix;
 use p3_poly::test_utils::rand_poly;
 use p3_poly::Polynomial;
 use rand::{rng, Rng};
 
-use super::{prove_round, RoundConfig};
+use super::{final_polynomial_from_last_round, fold_quotient, prove_round, RoundConfig};
+use crate::config::observe_public_parameters;
 use crate::proof::RoundProof;
-use crate::prover::{commit, prove, StirRoundWitness};
+#[cfg(feature = "blinding")]
+use crate::prover::prove_blinded;
+#[cfg(feature = "low-memory")]
+use crate::prover::stack_initial_lde_tiled;
+use crate::prover::{
+    commit, commit_and_fold_once, initial_domain, prove, prove_from_bitrev_evals, prove_reference,
+    prove_with_committed_input, stack_initial_evaluations, CommittedWitness, StirRoundWitness,
+};
 use crate::test_utils::*;
-use crate::utils::fold_polynomial;
-use crate::SecurityAssumption;
+use crate::utils::{fold_polynomial, sample_folding_randomness, TranscriptFoldChallenge};
+use crate::{verify, Messages, SecurityAssumption, StirConfig, StirParameters};
 
 // Auxiliary test function which checks that prove_round transforms the round
 // polynomial f_i into the expected polynomial f_{i + 1} and produces the right
@@ -96,11 +104,14 @@ fn test_prove_round_aux(repeat_queries: bool, degree_slack: usize) {
     let comb_randomness = rng.random();
 
     // Shake randomness (which is squeezed but not used by the prover)
+    #[cfg(not(feature = "no-shake"))]
     let shake_randomness = rng.random();
 
     let mut field_replies = ood_randomness.clone();
     field_replies.push(comb_randomness);
     field_replies.push(r_1);
+
+    #[cfg(not(feature = "no-shake"))]
     field_replies.push(shake_randomness);
 
     // Random queried indices (in the form of bits, not field elements)
@@ -131,7 +142,8 @@ fn test_prove_round_aux(repeat_queries: bool, degree_slack: usize) {
 
     // ====================== prove_round for round i = 1 ======================
 
-    let (witness, round_proof) = prove_round(&config, witness, &mut challenger);
+    let (witness, round_proof) =
+        prove_round(&config, witness, &mut challenger, &TranscriptFoldChallenge);
 
     // ============================ Witness checks ============================
 
@@ -185,17 +197,21 @@ fn test_prove_round_aux(repeat_queries: bool, degree_slack: usize) {
     let RoundProof {
         query_proofs,
         ans_polynomial,
+        #[cfg(not(feature = "no-shake"))]
         shake_polynomial,
         ..
     } = round_proof;
 
-    for (&i, (leaf, proof)) in bit_replies.iter().unique().zip(query_proofs) {
+    // Without an explicit leaf width, each fold group is a single leaf
+    for (&i, (leaf, proofs)) in bit_replies.iter().unique().zip(query_proofs) {
+        assert_eq!(proofs.len(), 1);
         config
             .mmcs_config()
-            .verify_batch(&root, &[dimensions], i, &[leaf], &proof)
+            .verify_batch(&root, &[dimensions], i, &[leaf], &proofs[0])
             .unwrap();
     }
 
+    #[cfg(not(feature = "no-shake"))]
     let expected_shake_polynomial = quotient_set_points
         .into_iter()
         .map(|(x, y)| {
@@ -206,6 +222,8 @@ fn test_prove_round_aux(repeat_queries: bool, degree_slack: usize) {
         .fold(Polynomial::zero(), |sum, next_poly| &sum + &next_poly);
 
     assert_eq!(ans_polynomial, expected_ans_polynomial);
+
+    #[cfg(not(feature = "no-shake"))]
     assert_eq!(shake_polynomial, expected_shake_polynomial);
 }
 
@@ -230,6 +248,223 @@ fn test_prove_round_degree_slack() {
     test_prove_round_aux(false, 10);
 }
 
+#[test]
+#[cfg(feature = "degree-checks")]
+#[should_panic(expected = "The degree of the polynomial (4095) is too large")]
+// Checks that, with degree checks, proving a witness committed to under a
+// configuration with a larger degree bound panics
+fn test_degree_checks_over_degree_polynomial() {
+    let config = test_bb_stir_config(
+        BB_EXT_SEC_LEVEL,
+        SecurityAssumption::CapacityBound,
+        10,
+        1,
+        2,
+        3,
+    );
+    let larger_config = test_bb_stir_config(
+        BB_EXT_SEC_LEVEL,
+        SecurityAssumption::CapacityBound,
+        12,
+        1,
+        2,
+        3,
+    );
+
+    let polynomial = rand_poly::<BbExt>((1 << 12) - 1);
+    let (witness, commitment) = commit(&larger_config, polynomial);
+
+    prove(&config, witness, commitment, &mut test_bb_challenger());
+}
+
+#[test]
+#[cfg(feature = "degree-checks")]
+#[should_panic(expected = "The committed codeword is not the evaluation")]
+// Checks that, with degree checks, proving a witness whose (externally)
+// committed codeword is that of a polynomial of too large a degree panics
+fn test_degree_checks_over_degree_codeword() {
+    let config = test_bb_stir_config(
+        BB_EXT_SEC_LEVEL,
+        SecurityAssumption::CapacityBound,
+        10,
+        1,
+        2,
+        3,
+    );
+
+    let over_degree_polynomial = rand_poly::<BbExt>(1 << config.log_starting_degree());
+    let evals =
+        initial_domain(&config).evaluate_polynomial(over_degree_polynomial.coeffs().to_vec());
+    let (commitment, merkle_tree) = config
+        .mmcs_config()
+        .commit_matrix(stack_initial_evaluations(&config, evals));
+
+    // The polynomial claimed by the witness is within the bound, but the
+    // codeword is not
+    let polynomial = rand_poly((1 << config.log_starting_degree()) - 1);
+    let committed = CommittedWitness::new(&config, polynomial, commitment, merkle_tree);
+
+    prove_with_committed_input(&config, committed, &mut test_bb_challenger());
+}
+
+#[test]
+#[cfg(feature = "degree-checks")]
+// Checks that, with degree checks, proving an honest witness does not panic
+// and yields a proof which verifies
+fn test_degree_checks_valid_input() {
+    let config = test_bb_stir_config(
+        BB_EXT_SEC_LEVEL,
+        SecurityAssumption::CapacityBound,
+        10,
+        1,
+        2,
+        3,
+    );
+
+    let polynomial = rand_poly::<BbExt>((1 << config.log_starting_degree()) - 1);
+    let (witness, commitment) = commit(&config, polynomial);
+    let proof = prove(&config, witness, commitment, &mut test_bb_challenger());
+
+    assert!(verify(&config, commitment, &proof, &mut test_bb_challenger()).is_ok());
+}
+
+#[test]
+// Checks that committing to the initial polynomial externally and proving with
+// prove_with_committed_input produces the same commitment and proof as the
+// all-in-one commit and prove, and that the resulting proof verifies
+fn test_prove_with_committed_input() {
+    let config = test_bb_stir_config(
+        BB_EXT_SEC_LEVEL,
+        SecurityAssumption::CapacityBound,
+        10,
+        1,
+        2,
+        3,
+    );
+
+    let polynomial = rand_poly((1 << config.log_starting_degree()) - 1);
+
+    // All-in-one
+    let (witness, commitment) = commit(&config, polynomial.clone());
+    let proof = prove(&config, witness, commitment, &mut test_bb_challenger());
+
+    // External LDE and commitment
+    let evals = initial_domain(&config).evaluate_polynomial(polynomial.coeffs().to_vec());
+    let (external_commitment, merkle_tree) = config
+        .mmcs_config()
+        .commit_matrix(stack_initial_evaluations(&config, evals));
+
+    let committed = CommittedWitness::new(&config, polynomial, external_commitment, merkle_tree);
+    assert_eq!(*committed.commitment(), commitment);
+
+    let external_proof = prove_with_committed_input(&config, committed, &mut test_bb_challenger());
+
+    assert_eq!(
+        serde_json::to_string(&external_proof).unwrap(),
+        serde_json::to_string(&proof).unwrap()
+    );
+
+    assert!(verify(
+        &config,
+        commitment,
+        &external_proof,
+        &mut test_bb_challenger()
+    )
+    .is_ok());
+}
+
+#[test]
+// Checks that proving from the bit-reversed evaluations of a polynomial over
+// the subgroup produces the same commitment and proof as committing to and
+// proving the polynomial itself, and that the resulting proof verifies
+fn test_prove_from_bitrev_evals() {
+    let config = test_bb_stir_config(
+        BB_EXT_SEC_LEVEL,
+        SecurityAssumption::CapacityBound,
+        10,
+        1,
+        2,
+        3,
+    );
+
+    let log_degree = config.log_starting_degree();
+    let polynomial = rand_poly((1 << log_degree) - 1);
+
+    // Natural-order path
+    let (witness, commitment) = commit(&config, polynomial.clone());
+    let proof = prove(&config, witness, commitment, &mut test_bb_challenger());
+
+    // Evaluations over the subgroup, the one at w^i placed at the bit-reversal
+    // of i
+    let generator = BbExt::two_adic_generator(log_degree);
+    let evals_bitrev = (0..1usize << log_degree)
+        .map(|i| {
+            let exponent = i.reverse_bits() >> (usize::BITS as usize - log_degree);
+            polynomial.evaluate(&generator.exp_u64(exponent as u64))
+        })
+        .collect_vec();
+
+    let (bitrev_proof, bitrev_commitment) =
+        prove_from_bitrev_evals(&config, evals_bitrev, &mut test_bb_challenger());
+
+    assert_eq!(bitrev_commitment, commitment);
+    assert_eq!(
+        serde_json::to_string(&bitrev_proof).unwrap(),
+        serde_json::to_string(&proof).unwrap()
+    );
+
+    assert!(verify(
+        &config,
+        commitment,
+        &bitrev_proof,
+        &mut test_bb_challenger()
+    )
+    .is_ok());
+}
+
+#[test]
+// Checks that commit_and_fold_once returns the same commitment as commit (and
+// hence a reproducible one) and the evaluations of the folded polynomial g_1
+// over L_1
+fn test_commit_and_fold_once() {
+    let config = test_bb_stir_config(
+        BB_EXT_SEC_LEVEL,
+        SecurityAssumption::CapacityBound,
+        10,
+        2,
+        3,
+        2,
+    );
+
+    let polynomial = rand_poly((1 << config.log_starting_degree()) - 1);
+    let folding_randomness: BbExt = rng().random();
+
+    let (commitment, folded_evals) =
+        commit_and_fold_once(&config, polynomial.clone(), folding_randomness);
+
+    let (_, expected_commitment) = commit(&config, polynomial.clone());
+    assert_eq!(commitment, expected_commitment);
+
+    let (repeated_commitment, repeated_folded_evals) =
+        commit_and_fold_once(&config, polynomial.clone(), folding_randomness);
+    assert_eq!(commitment, repeated_commitment);
+    assert_eq!(folded_evals, repeated_folded_evals);
+
+    let folded_polynomial = fold_polynomial(
+        &polynomial,
+        folding_randomness,
+        config.log_starting_folding_factor(),
+    );
+
+    let domain = initial_domain::<BbExt, _>(&config).shrink_subgroup(1);
+    assert_eq!(folded_evals.len(), domain.size());
+
+    assert!(domain
+        .into_iter()
+        .zip(folded_evals)
+        .all(|(x, eval)| folded_polynomial.evaluate(&x) == eval));
+}
+
 #[test]
 // Checks that prove runs from beginning to end and performs a degree check on
 // the final polynomial p = g_{num_rounds} (where num_rounds = M + 1 in the
@@ -291,7 +526,6 @@ fn test_prove_final_polynomial() {
     let mut round_ood_replies = Vec::new();
     let mut round_comb_replies = Vec::new();
     let mut round_r_replies = Vec::new();
-    let mut round_shake_replies = Vec::new();
     let mut round_bit_replies = Vec::new();
 
     let r_0 = rng.random();
@@ -317,18 +551,17 @@ fn test_prove_final_polynomial() {
         // Folding randomness
         let r: BbExt = rng.random();
 
-        // Shake randomness (which is squeezed but not used by the prover)
-        let shake_randomness = rng.random();
-
         field_replies.extend(ood_randomness.clone());
         field_replies.push(comb_randomness);
         field_replies.push(r);
-        field_replies.push(shake_randomness);
+
+        // Shake randomness (which is squeezed but not used by the prover)
+        #[cfg(not(feature = "no-shake"))]
+        field_replies.push(rng.random());
 
         round_ood_replies.push(ood_randomness);
         round_comb_replies.push(comb_randomness);
         round_r_replies.push(r);
-        round_shake_replies.push(shake_randomness);
 
         // Random queried indices (in the form of bits, not field elements)
 
@@ -412,6 +645,43 @@ fn test_prove_final_polynomial() {
     let expected_final_polynomial = fold_polynomial(&f_2, round_r_replies[2], log_folding_factor);
 
     assert_eq!(proof.final_polynomial, expected_final_polynomial);
+    assert_eq!(
+        final_polynomial_from_last_round(&config, &f_2, round_r_replies[2]),
+        expected_final_polynomial
+    );
+}
+
+#[test]
+// Checks that, in a configuration without full rounds, the final polynomial
+// p = g_1 sent by the prover is the input f_0 folded with the folding
+// randomness r_0 replayed from the transcript
+fn test_final_polynomial_without_full_rounds() {
+    let config = test_bb_stir_config(
+        BB_EXT_SEC_LEVEL,
+        SecurityAssumption::CapacityBound,
+        10,
+        1,
+        2,
+        1,
+    );
+    assert_eq!(config.num_full_rounds(), 0);
+
+    let polynomial = rand_poly((1 << config.log_starting_degree()) - 1);
+    let (witness, commitment) = commit(&config, polynomial.clone());
+    let proof = prove(&config, witness, commitment, &mut test_bb_challenger());
+
+    // Replaying the transcript up to the folding randomness r_0
+    let mut challenger = test_bb_challenger();
+    observe_public_parameters(config.parameters(), &mut challenger);
+    challenger.observe(Bb::from_u8(Messages::Commitment as u8));
+    challenger.observe(commitment);
+    let folding_randomness: BbExt =
+        sample_folding_randomness(&mut challenger, &TranscriptFoldChallenge, 0);
+
+    assert_eq!(
+        proof.final_polynomial,
+        final_polynomial_from_last_round(&config, &polynomial, folding_randomness)
+    );
 }
 
 #[test]
@@ -432,3 +702,290 @@ fn test_incorrect_polynomial() {
 
     commit(&config, polynomial);
 }
+
+#[test]
+// Checks that fold_quotient returns the polynomial (f - ans) / V, where V is the
+// vanishing polynomial of the queried points, by evaluating both sides at a
+// point outside the query set
+fn test_fold_quotient() {
+    let mut rng = rng();
+
+    let polynomial: Polynomial<BbExt> = rand_poly(63);
+
+    let query_points: Vec<BbExt> = (0..10).map(|_| rng.random()).collect();
+
+    let ans_polynomial = Polynomial::lagrange_interpolation(
+        query_points
+            .iter()
+            .map(|x| (*x, polynomial.evaluate(x)))
+            .collect_vec(),
+    );
+
+    let quotient = fold_quotient(&polynomial, &ans_polynomial, query_points.clone());
+
+    // The quotient has degree deg(f) - |query_points|
+    assert_eq!(quotient.degree(), Some(63 - query_points.len()));
+
+    let mut point: BbExt = rng.random();
+    while query_points.contains(&point) {
+        point = rng.random();
+    }
+
+    let vanishing_eval = query_points.iter().map(|q| point - *q).product::<BbExt>();
+
+    let expected =
+        (polynomial.evaluate(&point) - ans_polynomial.evaluate(&point)) * vanishing_eval.inverse();
+
+    assert_eq!(quotient.evaluate(&point), expected);
+}
+
+#[test]
+// Differential test of the prover against the reference prover: both must
+// produce identical proofs from the same witness and challenger, for several
+// folding schedules and with batched folding randomness and narrower leaves
+fn test_prove_reference() {
+    let configs = [
+        test_bb_stir_config(
+            BB_EXT_SEC_LEVEL,
+            SecurityAssumption::CapacityBound,
+            10,
+            1,
+            2,
+            3,
+        ),
+        test_bb_stir_config_folding_factors(
+            BB_EXT_SEC_LEVEL,
+            SecurityAssumption::JohnsonBound,
+            10,
+            2,
+            vec![3, 2, 1],
+        ),
+        StirConfig::new::<BbExt>(
+            StirParameters::constant_folding_factor(
+                (BB_EXT_SEC_LEVEL, SecurityAssumption::CapacityBound),
+                9,
+                1,
+                2,
+                2,
+                20,
+                test_bb_mmcs_config(),
+            )
+            .with_batched_folding_randomness()
+            .with_log_leaf_width(1),
+        )
+        .unwrap(),
+    ];
+
+    for config in configs {
+        let polynomial = rand_poly((1 << config.log_starting_degree()) - 1);
+
+        let (witness, commitment) = commit(&config, polynomial.clone());
+        let proof = prove(&config, witness, commitment, &mut test_bb_challenger());
+
+        let (witness, commitment) = commit(&config, polynomial);
+        let reference_proof =
+            prove_reference(&config, witness, commitment, &mut test_bb_challenger());
+
+        assert_eq!(
+            serde_json::to_string(&reference_proof).unwrap(),
+            serde_json::to_string(&proof).unwrap()
+        );
+    }
+}
+
+#[test]
+#[cfg(feature = "blinding")]
+// Checks that proofs produced with blinding coefficients verify and that two
+// such proofs of the same polynomial (and their commitments) differ
+fn test_prove_blinded() {
+    let config = test_bb_stir_config(
+        BB_EXT_SEC_LEVEL,
+        SecurityAssumption::CapacityBound,
+        10,
+        1,
+        2,
+        3,
+    );
+
+    let num_blinding_coeffs = 4;
+    let polynomial =
+        rand_poly::<BbExt>((1 << config.log_starting_degree()) - num_blinding_coeffs - 1);
+
+    let (proof_1, commitment_1) = prove_blinded(
+        &config,
+        polynomial.clone(),
+        num_blinding_coeffs,
+        &mut rng(),
+        &mut test_bb_challenger(),
+    );
+    let (proof_2, commitment_2) = prove_blinded(
+        &config,
+        polynomial,
+        num_blinding_coeffs,
+        &mut rng(),
+        &mut test_bb_challenger(),
+    );
+
+    verify(&config, commitment_1, &proof_1, &mut test_bb_challenger()).unwrap();
+    verify(&config, commitment_2, &proof_2, &mut test_bb_challenger()).unwrap();
+
+    assert_ne!(commitment_1, commitment_2);
+    assert_ne!(
+        serde_json::to_string(&proof_1).unwrap(),
+        serde_json::to_string(&proof_2).unwrap()
+    );
+}
+
+#[test]
+#[cfg(feature = "blinding")]
+#[should_panic(expected = "with 4 blinding coefficients")]
+// Checks that prove_blinded panics if the polynomial would overlap with the
+// blinding coefficients
+fn test_prove_blinded_overlapping_blinding() {
+    let config = test_bb_stir_config(
+        BB_EXT_SEC_LEVEL,
+        SecurityAssumption::CapacityBound,
+        10,
+        1,
+        2,
+        3,
+    );
+
+    let polynomial = rand_poly::<BbExt>((1 << config.log_starting_degree()) - 4);
+
+    prove_blinded(
+        &config,
+        polynomial,
+        4,
+        &mut rng(),
+        &mut test_bb_challenger(),
+    );
+}
+
+// Allocator counting the bytes currently allocated by each thread and their
+// peak, so that the memory usage of a computation can be measured while other
+// tests run concurrently. It replaces the global allocator of the whole test
+// binary, hence it is only compiled in when the feature it serves is enabled.
+#[cfg(all(feature = "low-memory", feature = "std"))]
+mod alloc_counter {
+    use core::alloc::{GlobalAlloc, Layout};
+    use core::cell::Cell;
+    use std::alloc::System;
+
+    struct CountingAllocator;
+
+    std::thread_local! {
+        static CURRENT: Cell<isize> = const { Cell::new(0) };
+        static PEAK: Cell<isize> = const { Cell::new(0) };
+    }
+
+    unsafe impl GlobalAlloc for CountingAllocator {
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+            // The thread-local counters may already have been destroyed if
+            // this is called during thread teardown
+            let _ = CURRENT.try_with(|current| {
+                let new = current.get() + layout.size() as isize;
+                current.set(new);
+                let _ = PEAK.try_with(|peak| peak.set(peak.get().max(new)));
+            });
+            System.alloc(layout)
+        }
+
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+            let _ = CURRENT.try_with(|current| current.set(current.get() - layout.size() as isize));
+            System.dealloc(ptr, layout)
+        }
+    }
+
+    #[global_allocator]
+    static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+    // Runs f and returns its output together with the peak number of bytes
+    // allocated by the current thread in the meantime (on top of those
+    // allocated beforehand)
+    pub(super) fn peak_allocation<T>(f: impl FnOnce() -> T) -> (T, usize) {
+        let start = CURRENT.with(Cell::get);
+        PEAK.with(|peak| peak.set(start));
+
+        let output = f();
+
+        (output, (PEAK.with(Cell::get) - start) as usize)
+    }
+}
+
+#[test]
+#[cfg(feature = "low-memory")]
+// Checks that the tiled initial low-degree extension produces the same matrix
+// as the one-shot one, for several rates, folding factors, leaf widths, root
+// generators and polynomial degrees
+fn test_stack_initial_lde_tiled() {
+    for (log_inv_rate, log_folding_factor, log_leaf_width) in
+        [(1, 2, 2), (2, 2, 1), (3, 3, 3), (2, 4, 2)]
+    {
+        let parameters = StirParameters::constant_folding_factor(
+            (BB_EXT_SEC_LEVEL, SecurityAssumption::CapacityBound),
+            10,
+            log_inv_rate,
+            log_folding_factor,
+            2,
+            20,
+            test_bb_mmcs_config(),
+        )
+        .with_log_leaf_width(log_leaf_width);
+
+        let log_size = 10 + log_inv_rate;
+        let other_generator = BbExt::two_adic_generator(log_size).exp_u64(3);
+
+        for parameters in [
+            parameters.clone(),
+            parameters.with_root_generator(other_generator),
+        ] {
+            let config = StirConfig::new::<BbExt>(parameters).unwrap();
+
+            for degree in [0, 100, (1 << 10) - 1] {
+                let polynomial = rand_poly::<BbExt>(degree);
+
+                let expected = stack_initial_evaluations(
+                    &config,
+                    initial_domain(&config).evaluate_polynomial(polynomial.coeffs().to_vec()),
+                );
+                let tiled = stack_initial_lde_tiled(&config, &polynomial);
+
+                assert_eq!(tiled.width(), expected.width());
+                assert_eq!(tiled.values, expected.values);
+            }
+        }
+    }
+}
+
+#[test]
+#[cfg(all(feature = "low-memory", feature = "std"))]
+// Checks that the peak memory usage of the tiled initial low-degree extension
+// stays below twice the size of the stacked matrix, which the one-shot one
+// necessarily reaches since it holds the evaluations in canonical order and
+// their stacked copy at the same time
+fn test_stack_initial_lde_tiled_peak_memory() {
+    let config = test_bb_stir_config(
+        BB_EXT_SEC_LEVEL,
+        SecurityAssumption::CapacityBound,
+        10,
+        3,
+        2,
+        3,
+    );
+    let polynomial = rand_poly::<BbExt>((1 << config.log_starting_degree()) - 1);
+    let matrix_bytes = core::mem::size_of::<BbExt>() << config.starting_domain_log_size();
+
+    let (one_shot, one_shot_peak) = alloc_counter::peak_allocation(|| {
+        stack_initial_evaluations(
+            &config,
+            initial_domain(&config).evaluate_polynomial(polynomial.coeffs().to_vec()),
+        )
+    });
+    let (tiled, tiled_peak) =
+        alloc_counter::peak_allocation(|| stack_initial_lde_tiled(&config, &polynomial));
+
+    assert_eq!(tiled.values, one_shot.values);
+    assert!(one_shot_peak >= 2 * matrix_bytes);
+    assert!(tiled_peak < 2 * matrix_bytes);
+}
@@ -4,6 +4,8 @@ use core::f64::consts::LOG2_10;
 use core::fmt::{Debug, Display};
 use core::str::FromStr;
 
+use serde::{Deserialize, Serialize};
+
 // This module, used to determine proximity gaps in the relevant Reed-Solomon
 // codes based on various security assumptions (and therefore the necessary
 // number of queries and proof-of-work bits), is directly taken from the
@@ -11,7 +13,7 @@ use core::str::FromStr;
 // [implementation](https://github.com/WizardOfMenlo/stir-whir-scripts/blob/main/src/errors.rs)
 
 /// Security assumptions determines which proximity parameters and conjectures are assumed by the error computation.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum SecurityAssumption {
     /// Unique decoding assumes that the distance of each oracle is within the UDR of the code.
     /// We refer to this configuration as UD for short.
@@ -166,19 +168,28 @@ impl SecurityAssumption {
         log_inv_rate: usize,
         field_size_bits: usize,
     ) -> usize {
+        self.try_determine_ood_samples(security_level, log_degree, log_inv_rate, field_size_bits)
+            .expect("Could not find an appropriate number of OOD samples")
+    }
+
+    /// Same as [`SecurityAssumption::determine_ood_samples`], but returns `None`
+    /// instead of panicking if fewer than 64 OOD samples cannot achieve
+    /// security_level bits of security
+    pub fn try_determine_ood_samples(
+        &self,
+        security_level: usize,
+        log_degree: usize,
+        log_inv_rate: usize,
+        field_size_bits: usize,
+    ) -> Option<usize> {
         if matches!(self, Self::UniqueDecoding) {
-            return 0;
+            return Some(0);
         }
 
-        for ood_samples in 1..64 {
-            if self.ood_error(log_degree, log_inv_rate, field_size_bits, ood_samples)
+        (1..64).find(|&ood_samples| {
+            self.ood_error(log_degree, log_inv_rate, field_size_bits, ood_samples)
                 >= security_level as f64
-            {
-                return ood_samples;
-            }
-        }
-
-        panic!("Could not find an appropriate number of OOD samples");
+        })
     }
 }
 
@@ -1,17 +1,32 @@
+use alloc::sync::Arc;
+use alloc::vec;
 use alloc::vec::Vec;
-
-use p3_baby_bear::{BabyBear, Poseidon2BabyBear};
-use p3_challenger::DuplexChallenger;
-use p3_commit::ExtensionMmcs;
-use p3_field::extension::BinomialExtensionField;
-use p3_field::Field;
+use core::marker::PhantomData;
+
+use p3_baby_bear::BabyBear;
+use p3_challenger::{
+    CanObserve, CanSample, CanSampleBits, DuplexChallenger, FieldChallenger, GrindingChallenger,
+    HashChallenger, SerializingChallenger32,
+};
+use p3_commit::{ExtensionMmcs, Mmcs};
+use p3_field::extension::{BinomialExtensionField, Complex};
+use p3_field::{ExtensionField, Field, TwoAdicField};
 use p3_goldilocks::{Goldilocks, Poseidon2Goldilocks};
+use p3_keccak::Keccak256Hash;
+use p3_matrix::{Dimensions, Matrix};
 use p3_merkle_tree::MerkleTreeMmcs;
-use p3_symmetric::{PaddingFreeSponge, TruncatedPermutation};
+use p3_mersenne_31::{Mersenne31, Poseidon2Mersenne31};
+use p3_symmetric::{
+    CompressionFunctionFromHasher, PaddingFreeSponge, SerializingHasher32, TruncatedPermutation,
+};
 use rand::SeedableRng;
 use rand_chacha::ChaCha20Rng;
 
-use crate::{SecurityAssumption, StirConfig, StirParameters};
+pub use crate::challenger::BbChallenger;
+use crate::challenger::{challenger_from_seed, BbPerm};
+use crate::prover::StirWitness;
+use crate::verifier::error::VerificationError;
+use crate::{prove, verify, SecurityAssumption, StirConfig, StirParameters, StirProof};
 
 // Security levels used in the tests in bits.
 
@@ -30,13 +45,17 @@ pub const BB_EXT_SEC_LEVEL_LOWER: usize = 100;
 /// challenges.
 pub const GL_EXT_SEC_LEVEL: usize = 80;
 
+/// The complex extension of Mersenne31 has only 62 bits, so we set the tests
+/// to 60 bits of security to keep the proof-of-work challenges within what the
+/// Mersenne31 challenger can grind.
+pub const M31_EXT_SEC_LEVEL: usize = 60;
+
 /// The BabyBear field
 pub type Bb = BabyBear;
 
 /// A quintic extension of BabyBear
 pub type BbExt = BinomialExtensionField<Bb, 5>;
 
-type BbPerm = Poseidon2BabyBear<16>;
 type BbHash = PaddingFreeSponge<BbPerm, 16, 8, 8>;
 type BbCompress = TruncatedPermutation<BbPerm, 2, 8, 16>;
 type BbPacking = <Bb as Field>::Packing;
@@ -46,8 +65,20 @@ type BbMmcs = MerkleTreeMmcs<BbPacking, BbPacking, BbHash, BbCompress, 8>;
 /// A Mixed Matrix Commitment Scheme over the quintic extension of BabyBear
 pub type BbExtMmcs = ExtensionMmcs<Bb, BbExt, BbMmcs>;
 
-/// A challenger for the BabyBear field and its quintic extension
-pub type BbChallenger = DuplexChallenger<Bb, BbPerm, 16, 8>;
+type BbKeccakHash = SerializingHasher32<Keccak256Hash>;
+type BbKeccakCompress = CompressionFunctionFromHasher<Keccak256Hash, 2, 32>;
+
+type BbKeccakMmcs = MerkleTreeMmcs<Bb, u8, BbKeccakHash, BbKeccakCompress, 32>;
+
+/// A Keccak-based Mixed Matrix Commitment Scheme over the quintic extension of
+/// BabyBear
+pub type BbKeccakExtMmcs = ExtensionMmcs<Bb, BbExt, BbKeccakMmcs>;
+
+/// A byte-oriented, Keccak-based challenger for the BabyBear field and its
+/// quintic extension. Unlike [`BbChallenger`], it is not a duplex sponge over
+/// the field, which serves to test that the protocol does not depend on the
+/// particular challenger.
+pub type BbKeccakChallenger = SerializingChallenger32<Bb, HashChallenger<u8, Keccak256Hash, 32>>;
 
 /// The Goldilocks field
 pub type Gl = Goldilocks;
@@ -68,6 +99,266 @@ pub type GlExtMmcs = ExtensionMmcs<Gl, GlExt, GlMmcs>;
 /// A challenger for the Goldilocks field and its quadratic extension
 pub type GlChallenger = DuplexChallenger<Gl, GlPerm, 8, 4>;
 
+/// A quintic extension of Goldilocks. Nothing in the protocol relies on the
+/// extension being binomial or of low degree, and this higher-degree extension
+/// (used with [`GlChallenger`]) serves to test it over a field other than the
+/// usual ones.
+pub type GlQuinticExt = BinomialExtensionField<Gl, 5>;
+
+/// A Mixed Matrix Commitment Scheme over the quintic extension of Goldilocks
+pub type GlQuinticExtMmcs = ExtensionMmcs<Gl, GlQuinticExt, GlMmcs>;
+
+/// The Mersenne31 field
+pub type M31 = Mersenne31;
+
+/// The complex (i. e. quadratic) extension of Mersenne31. Mersenne31 itself has
+/// two-adicity 1 and hence cannot host the evaluation domains, but all of these
+/// live in the extension field, whose two-adicity is 32.
+pub type M31Ext = Complex<M31>;
+
+type M31Perm = Poseidon2Mersenne31<16>;
+type M31Hash = PaddingFreeSponge<M31Perm, 16, 8, 8>;
+type M31Compress = TruncatedPermutation<M31Perm, 2, 8, 16>;
+type M31Packing = <M31 as Field>::Packing;
+
+type M31Mmcs = MerkleTreeMmcs<M31Packing, M31Packing, M31Hash, M31Compress, 8>;
+
+/// A Mixed Matrix Commitment Scheme over the complex extension of Mersenne31
+pub type M31ExtMmcs = ExtensionMmcs<M31, M31Ext, M31Mmcs>;
+
+/// A challenger for the Mersenne31 field and its complex extension
+pub type M31Challenger = DuplexChallenger<M31, M31Perm, 16, 8>;
+
+/// A minimal in-memory Mixed Matrix Commitment Scheme over an extension `EF`
+/// of `F`, meant for fast tests of the protocol logic. The "commitment" is the
+/// full committed matrix (with each row given by the basis coefficients of its
+/// entries), so that opening a row requires no proof and verifying it amounts
+/// to comparing it with the commitment. It is therefore binding, but neither
+/// hiding nor succinct. Only one matrix can be committed to at a time.
+#[derive(Debug)]
+pub struct MockMmcs<F, EF> {
+    _marker: PhantomData<(F, EF)>,
+}
+
+impl<F, EF> MockMmcs<F, EF> {
+    /// Creates a new `MockMmcs`, which requires no configuration
+    pub fn new() -> Self {
+        MockMmcs {
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<F, EF> Default for MockMmcs<F, EF> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F, EF> Clone for MockMmcs<F, EF> {
+    fn clone(&self) -> Self {
+        Self::new()
+    }
+}
+
+/// Error returned by [`MockMmcs::verify_batch`]
+#[derive(Debug, PartialEq, Eq)]
+pub enum MockMmcsError {
+    /// The opening does not consist of exactly one row of the expected width
+    WrongShape,
+    /// The opened row differs from the committed one (or does not exist)
+    WrongRow,
+}
+
+impl<F: Field, EF: ExtensionField<F>> Mmcs<EF> for MockMmcs<F, EF> {
+    type ProverData<M> = Arc<M>;
+    type Commitment = Vec<Vec<F>>;
+    type Proof = ();
+    type Error = MockMmcsError;
+
+    fn commit<M: Matrix<EF>>(&self, inputs: Vec<M>) -> (Self::Commitment, Self::ProverData<M>) {
+        let [matrix]: [M; 1] = inputs
+            .try_into()
+            .unwrap_or_else(|_| panic!("MockMmcs only supports committing to one matrix"));
+
+        let commitment = matrix
+            .rows()
+            .map(|row| {
+                row.into_iter()
+                    .flat_map(|x| x.as_basis_coefficients_slice().to_vec())
+                    .collect()
+            })
+            .collect();
+
+        (commitment, Arc::new(matrix))
+    }
+
+    fn open_batch<M: Matrix<EF>>(
+        &self,
+        index: usize,
+        prover_data: &Self::ProverData<M>,
+    ) -> (Vec<Vec<EF>>, Self::Proof) {
+        (vec![prover_data.row(index).into_iter().collect()], ())
+    }
+
+    fn get_matrices<'a, M: Matrix<EF>>(&self, prover_data: &'a Self::ProverData<M>) -> Vec<&'a M> {
+        vec![prover_data.as_ref()]
+    }
+
+    fn verify_batch(
+        &self,
+        commit: &Self::Commitment,
+        dimensions: &[Dimensions],
+        index: usize,
+        opened_values: &[Vec<EF>],
+        _proof: &Self::Proof,
+    ) -> Result<(), Self::Error> {
+        let ([dimensions], [row]) = (dimensions, opened_values) else {
+            return Err(MockMmcsError::WrongShape);
+        };
+
+        if row.len() != dimensions.width || commit.len() != dimensions.height {
+            return Err(MockMmcsError::WrongShape);
+        }
+
+        let flat_row: Vec<F> = row
+            .iter()
+            .flat_map(|x| x.as_basis_coefficients_slice().to_vec())
+            .collect();
+
+        if commit.get(index) != Some(&flat_row) {
+            return Err(MockMmcsError::WrongRow);
+        }
+
+        Ok(())
+    }
+}
+
+/// A [`MockMmcs`] over the quintic extension of BabyBear
+pub type BbMockMmcs = MockMmcs<Bb, BbExt>;
+
+/// Returns a [`MockMmcs`] over the quintic extension of BabyBear, which can be
+/// used in place of [`test_bb_mmcs_config`] with the same challenger
+pub fn test_bb_mock_mmcs_config() -> BbMockMmcs {
+    MockMmcs::new()
+}
+
+// Challenger wrapper whose grinding skips the proof-of-work search and returns
+// a zero witness instead. The witness is still observed (and the bits sampled)
+// as in honest grinding so that the rest of the transcript stays well-formed.
+#[derive(Clone)]
+struct NonGrindingChallenger<C>(C);
+
+impl<T, C: CanObserve<T>> CanObserve<T> for NonGrindingChallenger<C> {
+    fn observe(&mut self, value: T) {
+        self.0.observe(value);
+    }
+}
+
+impl<T, C: CanSample<T>> CanSample<T> for NonGrindingChallenger<C> {
+    fn sample(&mut self) -> T {
+        self.0.sample()
+    }
+}
+
+impl<C: CanSampleBits<usize>> CanSampleBits<usize> for NonGrindingChallenger<C> {
+    fn sample_bits(&mut self, bits: usize) -> usize {
+        self.0.sample_bits(bits)
+    }
+}
+
+impl<F: Field, C: FieldChallenger<F>> FieldChallenger<F> for NonGrindingChallenger<C> {}
+
+impl<C: GrindingChallenger> GrindingChallenger for NonGrindingChallenger<C> {
+    type Witness = C::Witness;
+
+    fn grind(&mut self, bits: usize) -> Self::Witness {
+        let _ = self.check_witness(bits, C::Witness::ZERO);
+        C::Witness::ZERO
+    }
+}
+
+/// Challenger wrapper which logs the base field elements sampled from the
+/// wrapped challenger (including those making up extension field challenges),
+/// so that tests can check that a prover and a verifier derive the same
+/// challenges from their transcripts. Samples drawn internally by the wrapped
+/// challenger during grinding are not logged.
+#[derive(Clone)]
+pub struct TranscriptLogChallenger<F, C> {
+    /// The wrapped challenger
+    pub inner: C,
+
+    /// The base field elements sampled so far, in order
+    pub samples: Vec<F>,
+}
+
+impl<F, C> TranscriptLogChallenger<F, C> {
+    /// Wraps `inner` with an empty log
+    pub fn new(inner: C) -> Self {
+        Self {
+            inner,
+            samples: Vec::new(),
+        }
+    }
+}
+
+impl<F, T, C: CanObserve<T>> CanObserve<T> for TranscriptLogChallenger<F, C> {
+    fn observe(&mut self, value: T) {
+        self.inner.observe(value);
+    }
+}
+
+impl<F: Clone, C: CanSample<F>> CanSample<F> for TranscriptLogChallenger<F, C> {
+    fn sample(&mut self) -> F {
+        let sample = self.inner.sample();
+        self.samples.push(sample.clone());
+        sample
+    }
+}
+
+impl<F, C: CanSampleBits<usize>> CanSampleBits<usize> for TranscriptLogChallenger<F, C> {
+    fn sample_bits(&mut self, bits: usize) -> usize {
+        self.inner.sample_bits(bits)
+    }
+}
+
+impl<F: Field, C: FieldChallenger<F>> FieldChallenger<F> for TranscriptLogChallenger<F, C> {}
+
+impl<F: Sync + Clone, C: GrindingChallenger> GrindingChallenger for TranscriptLogChallenger<F, C> {
+    type Witness = C::Witness;
+
+    fn grind(&mut self, bits: usize) -> Self::Witness {
+        self.inner.grind(bits)
+    }
+}
+
+/// Adversarial variant of [`prove`] which skips all proof-of-work computations
+/// and emits zero proof-of-work witnesses instead, but is otherwise honest. Its
+/// proofs must be rejected by the verifier (except in the unlikely event that
+/// zero happens to be a valid witness for every round), which allows testing
+/// that the latter does check the witnesses. This is only meant for tests.
+///
+/// # Parameters
+///
+/// Same as [`prove`].
+pub fn prove_without_grinding<F, EF, M, C>(
+    config: &StirConfig<M>,
+    witness: StirWitness<EF, M>,
+    commitment: M::Commitment,
+    challenger: &mut C,
+) -> StirProof<EF, M, C::Witness>
+where
+    F: Field,
+    EF: TwoAdicField + ExtensionField<F>,
+    M: Mmcs<EF>,
+    C: FieldChallenger<F> + GrindingChallenger + CanObserve<M::Commitment>,
+{
+    let mut non_grinding_challenger = NonGrindingChallenger(challenger.clone());
+    let proof = prove(config, witness, commitment, &mut non_grinding_challenger);
+    *challenger = non_grinding_challenger.0;
+    proof
+}
+
 // This produces an MMCS for the chosen field. Computing it in a macro avoids
 // some generic-related pains. We seed the generator in order to make the tests
 // deterministic, but this is not necessary.
@@ -83,13 +374,18 @@ macro_rules! impl_test_mmcs_config {
     };
 }
 
-// This produces a challenger for the chosen field and its extension. We seed
-// the generator in order to make the tests deterministic, but this is not
+// This produces a challenger for the chosen field and its extension, as well as
+// a variant whose permutation is derived from a caller-chosen seed. We seed the
+// generator in order to make the tests deterministic, but this is not
 // necessary.
 macro_rules! impl_test_challenger {
-    ($name:ident, $challenger:ty, $perm:ty) => {
+    ($name:ident, $name_from_seed:ident, $challenger:ty, $perm:ty) => {
         pub fn $name() -> $challenger {
-            let mut rng = ChaCha20Rng::seed_from_u64(0);
+            $name_from_seed(0)
+        }
+
+        pub fn $name_from_seed(seed: u64) -> $challenger {
+            let mut rng = ChaCha20Rng::seed_from_u64(seed);
             let perm = <$perm>::new_from_rng_128(&mut rng);
             <$challenger>::new(perm)
         }
@@ -119,7 +415,7 @@ macro_rules! impl_test_stir_config {
                 $mmcs_config_fn(),
             );
 
-            StirConfig::new::<$ext>(parameters)
+            StirConfig::new::<$ext>(parameters).unwrap()
         }
     };
 }
@@ -145,7 +441,7 @@ macro_rules! impl_test_stir_config_folding_factors {
                 $mmcs_config_fn(),
             );
 
-            StirConfig::new::<$ext>(parameters)
+            StirConfig::new::<$ext>(parameters).unwrap()
         }
     };
 }
@@ -168,11 +464,115 @@ impl_test_mmcs_config!(
     GlMmcs
 );
 
-impl_test_challenger!(test_bb_challenger, BbChallenger, BbPerm);
-impl_test_challenger!(test_gl_challenger, GlChallenger, GlPerm);
+impl_test_mmcs_config!(
+    test_gl_quintic_mmcs_config,
+    GlQuinticExtMmcs,
+    GlPerm,
+    GlHash,
+    GlCompress,
+    GlMmcs
+);
+
+impl_test_mmcs_config!(
+    test_m31_mmcs_config,
+    M31ExtMmcs,
+    M31Perm,
+    M31Hash,
+    M31Compress,
+    M31Mmcs
+);
+
+/// Produces a Keccak-based MMCS over the quintic extension of BabyBear
+pub fn test_bb_keccak_mmcs_config() -> BbKeccakExtMmcs {
+    let hash = BbKeccakHash::new(Keccak256Hash {});
+    let compress = BbKeccakCompress::new(Keccak256Hash {});
+    BbKeccakExtMmcs::new(BbKeccakMmcs::new(hash, compress))
+}
+
+/// Produces a Keccak-based challenger for BabyBear and its quintic extension
+pub fn test_bb_keccak_challenger() -> BbKeccakChallenger {
+    BbKeccakChallenger::from_hasher(vec![], Keccak256Hash {})
+}
+
+/// The default challenger (cf. [`challenger_from_seed`]) built from the seed
+/// `0`
+pub fn test_bb_challenger() -> BbChallenger {
+    test_bb_challenger_from_seed(0)
+}
+
+/// The default challenger built from the little-endian bytes of `seed` with
+/// [`challenger_from_seed`]
+pub fn test_bb_challenger_from_seed(seed: u64) -> BbChallenger {
+    challenger_from_seed(&seed.to_le_bytes())
+}
+
+impl_test_challenger!(
+    test_gl_challenger,
+    test_gl_challenger_from_seed,
+    GlChallenger,
+    GlPerm
+);
+impl_test_challenger!(
+    test_m31_challenger,
+    test_m31_challenger_from_seed,
+    M31Challenger,
+    M31Perm
+);
+
+/// Verifies a BabyBear proof with a fresh challenger built internally from
+/// `seed` (cf. [`test_bb_challenger_from_seed`]), so that the entire
+/// verification is reproducible from the seed, configuration, commitment and
+/// proof alone. This is meant for test vectors and cross-implementation
+/// conformance: a prover targets the same transcript by proving with
+/// `test_bb_challenger_from_seed(seed)`.
+///
+/// # Parameters
+///
+/// Same as [`verify`], except that the challenger is replaced by its `seed`.
+///
+/// # Errors
+///
+/// Same as [`verify`].
+pub fn verify_deterministic(
+    config: &StirConfig<BbExtMmcs>,
+    commitment: <BbExtMmcs as Mmcs<BbExt>>::Commitment,
+    proof: &StirProof<BbExt, BbExtMmcs, Bb>,
+    seed: u64,
+) -> Result<(), VerificationError> {
+    verify(
+        config,
+        commitment,
+        proof,
+        &mut test_bb_challenger_from_seed(seed),
+    )
+}
 
 impl_test_stir_config!(test_bb_stir_config, BbExt, BbExtMmcs, test_bb_mmcs_config);
 impl_test_stir_config!(test_gl_stir_config, GlExt, GlExtMmcs, test_gl_mmcs_config);
+impl_test_stir_config!(
+    test_gl_quintic_stir_config,
+    GlQuinticExt,
+    GlQuinticExtMmcs,
+    test_gl_quintic_mmcs_config
+);
+impl_test_stir_config!(
+    test_bb_keccak_stir_config,
+    BbExt,
+    BbKeccakExtMmcs,
+    test_bb_keccak_mmcs_config
+);
+impl_test_stir_config!(
+    test_m31_stir_config,
+    M31Ext,
+    M31ExtMmcs,
+    test_m31_mmcs_config
+);
+impl_test_stir_config!(
+    test_bb_mock_stir_config,
+    BbExt,
+    BbMockMmcs,
+    test_bb_mock_mmcs_config
+);
 
 impl_test_stir_config_folding_factors!(
     test_bb_stir_config_folding_factors,
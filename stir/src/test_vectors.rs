@@ -0,0 +1,227 @@
+//! Canonical test vectors pinning the wire format and the Fiat-Shamir
+//! transcript of STIR, so that other implementations (such as the reference
+//! one by Giacomo Fenzi) can check their conformance.
+//!
+//! A test vector consists of a fixed seed, a fixed configuration over the
+//! quintic extension of BabyBear (cf. [`test_vector_config`]), the commitment
+//! to a polynomial derived from the seed, the bytes of the proof in the
+//! streaming encoding v1 (cf. [`StirProof::encode_v1`]) and the expected
+//! verification outcome. The challenger is built from the seed as in
+//! [`test_bb_challenger_from_seed`] and the configuration requires no
+//! proof-of-work, so that the proof (and therefore its bytes) is fully
+//! determined by the seed.
+//!
+//! The canonical vector is meant to be committed to the repository under
+//! `test_vectors/bb_v1.jsonl` and checked against a freshly generated one in
+//! the tests of this module, which fail if it is missing. It is (re)written
+//! only by running these tests with the environment variable
+//! `STIR_UPDATE_TEST_VECTORS` set. Until it is committed, the comparison test
+//! is ignored.
+
+use alloc::vec::Vec;
+
+use p3_commit::Mmcs;
+use p3_poly::Polynomial;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha20Rng;
+
+use crate::test_utils::{
+    prove_without_grinding, test_bb_challenger_from_seed, test_bb_mmcs_config,
+    verify_deterministic, Bb, BbExt, BbExtMmcs,
+};
+use crate::verifier::error::VerificationError;
+use crate::{commit, SecurityAssumption, StirConfig, StirParameters, StirProof};
+
+/// Seed of the canonical test vector.
+pub const TEST_VECTOR_SEED: u64 = 0;
+
+/// A test vector: a proof for a polynomial derived from `seed` under
+/// [`test_vector_config`], together with the expected verification outcome.
+pub struct TestVector {
+    /// Seed from which the polynomial and the challenger are derived
+    pub seed: u64,
+
+    /// Commitment to the polynomial
+    pub commitment: <BbExtMmcs as Mmcs<BbExt>>::Commitment,
+
+    /// Proof in the streaming encoding v1
+    pub proof: Vec<u8>,
+
+    /// Whether the proof is expected to verify
+    pub accepted: bool,
+}
+
+impl TestVector {
+    /// Serializes the test vector as a sequence of newline-separated JSON
+    /// values: the seed, the commitment and the expected outcome, followed by
+    /// the proof in the streaming encoding v1.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+
+        for value in [
+            serde_json::to_vec(&self.seed),
+            serde_json::to_vec(&self.commitment),
+            serde_json::to_vec(&self.accepted),
+        ] {
+            bytes.extend(value.expect("Serializing to a vector cannot fail"));
+            bytes.push(b'\n');
+        }
+
+        bytes.extend(&self.proof);
+        bytes
+    }
+
+    /// Decodes the proof of the test vector and verifies it with the
+    /// challenger built from its seed, as in [`verify_deterministic`].
+    ///
+    /// # Errors
+    ///
+    /// Same as [`verify_deterministic`], as well as
+    /// [`VerificationError::Decoding`] if the proof cannot be decoded.
+    pub fn verify(&self) -> Result<(), VerificationError> {
+        let proof = StirProof::<BbExt, BbExtMmcs, Bb>::decode_v1(self.proof.as_slice())
+            .map_err(|_| VerificationError::Decoding)?;
+
+        verify_deterministic(&test_vector_config(), self.commitment, &proof, self.seed)
+    }
+}
+
+/// The fixed configuration of the test vectors: 80 bits of security under the
+/// capacity bound, initial degree bound `2^8`, initial inverse rate `2`, two
+/// rounds of folding factor `4` and no proof-of-work.
+pub fn test_vector_config() -> StirConfig<BbExtMmcs> {
+    let parameters = StirParameters::constant_folding_factor(
+        (80, SecurityAssumption::CapacityBound),
+        8,
+        1,
+        2,
+        2,
+        0,
+        test_bb_mmcs_config(),
+    );
+
+    StirConfig::new::<BbExt>(parameters).expect("The test vector configuration is valid")
+}
+
+/// Generates the canonical test vector (with seed [`TEST_VECTOR_SEED`]).
+pub fn generate() -> TestVector {
+    generate_with_seed(TEST_VECTOR_SEED)
+}
+
+/// Generates the test vector with the given seed: the coefficients of the
+/// polynomial are sampled from a ChaCha20 generator seeded with `seed` and the
+/// proof is produced with the challenger built from `seed`.
+///
+/// # Panics
+///
+/// Panics if the test vector configuration requires some proof-of-work, which
+/// would make the proof depend on the (possibly parallel) grinding.
+pub fn generate_with_seed(seed: u64) -> TestVector {
+    let config = test_vector_config();
+
+    assert!(
+        config.pow_bits_all_rounds().iter().all(|&bits| bits == 0),
+        "Test vectors must not require proof-of-work"
+    );
+
+    let mut rng = ChaCha20Rng::seed_from_u64(seed);
+    let coeffs: Vec<BbExt> = (0..1 << config.log_starting_degree())
+        .map(|_| rng.random())
+        .collect();
+
+    let (witness, commitment) = commit(&config, Polynomial::from_coeffs(coeffs));
+
+    // Without proof-of-work, skipping the grinding yields the same proof
+    let proof = prove_without_grinding(
+        &config,
+        witness,
+        commitment,
+        &mut test_bb_challenger_from_seed(seed),
+    );
+
+    let mut proof_bytes = Vec::new();
+    proof
+        .encode_v1(&mut proof_bytes)
+        .expect("Encoding to a vector cannot fail");
+
+    let accepted = verify_deterministic(&config, commitment, &proof, seed).is_ok();
+
+    TestVector {
+        seed,
+        commitment,
+        proof: proof_bytes,
+        accepted,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+    use std::{env, fs};
+
+    use super::*;
+
+    // Path of the committed canonical test vector
+    fn canonical_path() -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("test_vectors/bb_v1.jsonl")
+    }
+
+    #[test]
+    #[cfg(not(feature = "no-shake"))]
+    #[ignore = "the canonical test vector test_vectors/bb_v1.jsonl has not been generated yet"]
+    // Checks that regenerating the canonical test vector reproduces the
+    // committed bytes and that its proof is accepted. It is skipped with the
+    // no-shake feature, since the committed vector pins the default wire format.
+    // It is ignored until the vector is generated (by running it with
+    // --ignored and STIR_UPDATE_TEST_VECTORS set) and committed
+    fn test_canonical_test_vector() {
+        let vector = generate();
+        let bytes = vector.to_bytes();
+
+        assert!(vector.accepted);
+        assert_eq!(vector.verify(), Ok(()));
+
+        // Generation is deterministic
+        assert_eq!(generate().to_bytes(), bytes);
+
+        let path = canonical_path();
+
+        if env::var_os("STIR_UPDATE_TEST_VECTORS").is_some() {
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(&path, &bytes).unwrap();
+        }
+
+        let committed = fs::read(&path).unwrap_or_else(|_| {
+            panic!(
+                "The canonical test vector {} is missing: generate it by rerunning \
+                the tests with STIR_UPDATE_TEST_VECTORS set and commit it",
+                path.display()
+            )
+        });
+
+        assert_eq!(
+            committed, bytes,
+            "The canonical test vector changed: if this is intended (e. g. due \
+            to a change in the wire format or transcript), rerun the tests with \
+            STIR_UPDATE_TEST_VECTORS set"
+        );
+    }
+
+    #[test]
+    // Checks that tampering with the proof bytes of a test vector makes it
+    // rejected
+    fn test_tampered_test_vector() {
+        let mut vector = generate();
+
+        // Alter the last digit of the encoding, which belongs to the
+        // final-round openings
+        let position = vector.proof.iter().rposition(u8::is_ascii_digit).unwrap();
+        vector.proof[position] = if vector.proof[position] == b'1' {
+            b'2'
+        } else {
+            b'1'
+        };
+
+        assert!(vector.verify().is_err());
+    }
+}
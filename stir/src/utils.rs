@@ -1,18 +1,174 @@
+use alloc::collections::BTreeMap;
 use alloc::vec;
 use alloc::vec::Vec;
 use core::iter;
 
 use itertools::{iterate, izip, Itertools};
 use p3_challenger::{CanObserve, FieldChallenger};
+use p3_commit::Mmcs;
+use p3_coset::TwoAdicCoset;
 use p3_field::{ExtensionField, Field, TwoAdicField};
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::Matrix;
 use p3_poly::Polynomial;
 
+use crate::{Messages, StirConfig};
+
 // Syntactic sugar for the proof-of-work computation
 #[inline]
 pub(crate) fn compute_pow(security_level: usize, error: f64) -> f64 {
     0f64.max(security_level as f64 - error)
 }
 
+// Returns the size 2^log_size of a domain, or None if it does not fit in a
+// usize (in which case 1 << log_size would overflow)
+pub(crate) fn checked_domain_size(log_size: usize) -> Option<usize> {
+    u32::try_from(log_size)
+        .ok()
+        .and_then(|log_size| 1usize.checked_shl(log_size))
+}
+
+// Maximum number of times (per round) that an out-of-domain point coinciding
+// with a previously sampled one is resampled. A collision only happens with
+// negligible probability for an actual challenger, so this limit only prevents
+// a degenerate one from making the sampling loop forever.
+const MAX_OOD_RESAMPLES: usize = 16;
+
+// Samples the out-of-domain points of a round, which have to lie outside the
+// given domain and be pairwise distinct (otherwise Ans_i would be asked to
+// interpolate two values at the same point). Points in the domain and repeated
+// points are rejected and resampled, the latter at most MAX_OOD_RESAMPLES times
+// in total, after which repeated points are accepted and the output may
+// therefore contain repetitions (which the prover and verifier must check for).
+// The prover and verifier call this in the same transcript state, thus
+// obtaining the same points.
+pub(crate) fn sample_ood_points<F, EF, C>(
+    // Challenger for the transcript
+    challenger: &mut C,
+    // The domain the points must lie outside of
+    domain: &TwoAdicCoset<EF>,
+    // The number of points to sample
+    num_ood_samples: usize,
+) -> Vec<EF>
+where
+    F: Field,
+    EF: TwoAdicField + ExtensionField<F>,
+    C: FieldChallenger<F>,
+{
+    let mut ood_samples = Vec::with_capacity(num_ood_samples);
+    let mut num_resamples = 0;
+
+    challenger.observe(F::from_u8(Messages::OodSamples as u8));
+    while ood_samples.len() < num_ood_samples {
+        let el: EF = challenger.sample_algebra_element();
+
+        // Rejection sampling: these points have to be outside the domain
+        if domain.contains(el) {
+            continue;
+        }
+
+        if ood_samples.contains(&el) && num_resamples < MAX_OOD_RESAMPLES {
+            num_resamples += 1;
+            continue;
+        }
+
+        ood_samples.push(el);
+    }
+
+    ood_samples
+}
+
+// Samples the folding randomness r_i for the next round given the folding
+// randomness r_{i - 1} of the current one. If the folding randomness is
+// batched (cf. StirParameters::batched_folding_randomness), the challenger is
+// not invoked and r_i = r_{i - 1}^2 is returned instead, so that r_i = r_0^(2^i)
+pub(crate) fn sample_next_folding_randomness<F, EF, C, S>(
+    // Whether the folding randomness is derived from the seed r_0
+    batched: bool,
+    // The folding randomness r_{i - 1} of the current round
+    folding_randomness: EF,
+    // Challenger for the transcript
+    challenger: &mut C,
+    // Derivation of the folding randomness from the transcript
+    source: &S,
+    // The index i of the folding randomness r_i to sample
+    round: usize,
+) -> EF
+where
+    F: Field,
+    EF: ExtensionField<F>,
+    C: FieldChallenger<F>,
+    S: FoldChallengeSource<F>,
+{
+    if batched {
+        return folding_randomness.square();
+    }
+
+    sample_folding_randomness(challenger, source, round)
+}
+
+// Samples the folding randomness r_i from the given source after observing the
+// corresponding domain separator. This is shared by the prover and verifier for
+// r_0 as well as for the later r_i when these are not batched.
+pub(crate) fn sample_folding_randomness<F, EF, C, S>(
+    // Challenger for the transcript
+    challenger: &mut C,
+    // Derivation of the folding randomness from the transcript
+    source: &S,
+    // The index i of the folding randomness r_i to sample
+    round: usize,
+) -> EF
+where
+    F: Field,
+    EF: ExtensionField<F>,
+    C: FieldChallenger<F>,
+    S: FoldChallengeSource<F>,
+{
+    challenger.observe(F::from_u8(Messages::FoldingRandomness as u8));
+    source.fold_challenge(challenger, round)
+}
+
+/// Derivation of the folding randomness `r_i` from the transcript.
+///
+/// By default, [`prove`](crate::prove) and [`verify`](crate::verify) sample
+/// each `r_i` directly from the challenger (cf. [`TranscriptFoldChallenge`]).
+/// Implementing this trait allows callers to inject a domain-specific
+/// derivation instead, for instance one binding additional public data into
+/// the folding randomness. Prover and verifier must use the same source, as
+/// otherwise their transcripts diverge and verification fails.
+///
+/// When the folding randomness is batched (cf.
+/// [`StirParameters::batched_folding_randomness`](crate::StirParameters)), only
+/// `r_0` is obtained from the source and the subsequent `r_i` are its powers.
+pub trait FoldChallengeSource<F: Field> {
+    /// Derives the folding randomness `r_round` (with `r_0` used to fold the
+    /// committed polynomial) from `challenger`, which has just observed the
+    /// corresponding domain separator.
+    ///
+    /// The output must be a deterministic function of the challenger state and
+    /// `round`: sampling is expected to go through `challenger` so that every
+    /// later challenge depends on the folding randomness.
+    fn fold_challenge<EF, C>(&self, challenger: &mut C, round: usize) -> EF
+    where
+        EF: ExtensionField<F>,
+        C: FieldChallenger<F>;
+}
+
+/// Default [`FoldChallengeSource`], which samples the folding randomness from
+/// the challenger and nothing else.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct TranscriptFoldChallenge;
+
+impl<F: Field> FoldChallengeSource<F> for TranscriptFoldChallenge {
+    fn fold_challenge<EF, C>(&self, challenger: &mut C, _round: usize) -> EF
+    where
+        EF: ExtensionField<F>,
+        C: FieldChallenger<F>,
+    {
+        challenger.sample_algebra_element()
+    }
+}
+
 // Given a polynomial f and a folding coefficient c, this function computes the usual folding
 // (same as in FRI) of the requested arity/folding factor:
 //   folded(x) = f_0(x) + c * f_1(x) + ... + c^(arity - 1) * f_(arity - 1)(x)
@@ -50,6 +206,51 @@ pub(crate) fn fold_polynomial<F: TwoAdicField>(
     Polynomial::from_coeffs(folded_coeffs)
 }
 
+// Interpolates the polynomial of degree less than n taking the given values
+// over the subgroup <w> of order n = 2^log_n generated by
+// F::two_adic_generator(log_n), where the value at w^i is found at the
+// bit-reversal of i in evals. The inverse DFT is computed with an iterative
+// decimation-in-time butterfly network, which consumes its input in
+// bit-reversed order and produces its output in natural order, so that no
+// permutation pass is needed.
+//
+// Panics if the number of values is not a power of two.
+pub(crate) fn interpolate_bitrev_evals<F: TwoAdicField>(
+    // The evaluations at the elements of the subgroup, in bit-reversed order
+    mut evals: Vec<F>,
+) -> Polynomial<F> {
+    let n = evals.len();
+    assert!(
+        n.is_power_of_two(),
+        "The number of evaluations must be a power of two"
+    );
+
+    for log_block_size in 1..=n.ilog2() as usize {
+        let half_block_size = 1 << (log_block_size - 1);
+
+        // Powers of the inverse of a primitive root of unity of the block size
+        let inv_root = F::two_adic_generator(log_block_size).inverse();
+        let twiddles = iterate(F::ONE, |&x| x * inv_root)
+            .take(half_block_size)
+            .collect_vec();
+
+        for block in evals.chunks_mut(2 * half_block_size) {
+            let (low, high) = block.split_at_mut(half_block_size);
+
+            for ((a, b), &twiddle) in low.iter_mut().zip(high).zip(&twiddles) {
+                let t = *b * twiddle;
+                *b = *a - t;
+                *a += t;
+            }
+        }
+    }
+
+    let n_inv = F::from_usize(n).inverse();
+    evals.iter_mut().for_each(|coeff| *coeff *= n_inv);
+
+    Polynomial::from_coeffs(evals)
+}
+
 // Multiply the given polynomial by the power polynomial
 //   1 + c * x + c^2 * x^2 + ... + c^d * x^d
 // using the more efficient method of multiplying by
@@ -192,6 +393,112 @@ fn fold_evaluation_pair<F: TwoAdicField>(
     two_inv * (eval_1 + eval_2 + gamma * (eval_1 - eval_2))
 }
 
+// Arrange the evaluations of a polynomial over a domain L = w * <g> of size n
+// into the matrix whose rows are the leaves of the MMCS commitment. The
+// evaluations at the k-th roots of each point of L^k, i. e. at
+//   w * g^j * {1, g^{n/k}, ..., g^{(k - 1) * n/k}},
+// form the j-th fold group, which is split into 2^log_rows_per_group
+// consecutive rows (in the above order) of width k / 2^log_rows_per_group.
+pub(crate) fn stack_evaluations<F: Field>(
+    // The evaluations over L in the canonical order
+    evals: Vec<F>,
+    // The log2 of the folding factor k
+    log_folding_factor: usize,
+    // The log2 of the number of rows each fold group is split into
+    log_rows_per_group: usize,
+) -> RowMajorMatrix<F> {
+    let num_groups = evals.len() >> log_folding_factor;
+
+    // The j-th row of this matrix is the j-th fold group
+    let fold_groups = RowMajorMatrix::new(evals, num_groups).transpose();
+
+    RowMajorMatrix::new(
+        fold_groups.values,
+        1 << (log_folding_factor - log_rows_per_group),
+    )
+}
+
+// Open the j-th fold group (cf. stack_evaluations) of a committed matrix,
+// returning the evaluations therein and the Merkle proof of each of the rows
+// it is split into.
+pub(crate) fn open_fold_group<F: Field, M: Mmcs<F>>(
+    // The MMCS used to commit to the matrix
+    mmcs: &M,
+    // The index j of the fold group
+    index: usize,
+    // The log2 of the number of rows each fold group is split into
+    log_rows_per_group: usize,
+    // The prover data produced when committing to the matrix
+    prover_data: &M::ProverData<RowMajorMatrix<F>>,
+) -> (Vec<F>, Vec<M::Proof>) {
+    let mut evals = Vec::new();
+    let mut proofs = Vec::new();
+
+    for row in (index << log_rows_per_group)..((index + 1) << log_rows_per_group) {
+        let (mut openings, proof) = mmcs.open_batch(row, prover_data);
+        evals.extend(openings.remove(0));
+        proofs.push(proof);
+    }
+
+    (evals, proofs)
+}
+
+// Open the fold groups (cf. open_fold_group) at all the queried indices of a
+// round at once. The rows to be opened are collected beforehand so that each
+// of them is opened only once, even if it belongs to the fold groups of
+// several (repeated) indices. The Mmcs trait offers no multi-index opening, so
+// the rows are then opened one by one.
+pub(crate) fn open_fold_groups<F: Field, M: Mmcs<F>>(
+    // The MMCS used to commit to the matrix
+    mmcs: &M,
+    // The indices of the fold groups to open
+    indices: &[usize],
+    // The log2 of the number of rows each fold group is split into
+    log_rows_per_group: usize,
+    // The prover data produced when committing to the matrix
+    prover_data: &M::ProverData<RowMajorMatrix<F>>,
+) -> Vec<(Vec<F>, Vec<M::Proof>)> {
+    let openings: BTreeMap<usize, (Vec<F>, M::Proof)> = indices
+        .iter()
+        .flat_map(|&index| (index << log_rows_per_group)..((index + 1) << log_rows_per_group))
+        .unique()
+        .map(|row| {
+            let (mut openings, proof) = mmcs.open_batch(row, prover_data);
+            (row, (openings.remove(0), proof))
+        })
+        .collect();
+
+    let fold_groups: Vec<(Vec<F>, Vec<M::Proof>)> = indices
+        .iter()
+        .map(|&index| {
+            ((index << log_rows_per_group)..((index + 1) << log_rows_per_group))
+                .map(|row| openings[&row].clone())
+                .fold(
+                    (Vec::new(), Vec::new()),
+                    |(mut evals, mut proofs), (row_evals, proof)| {
+                        evals.extend(row_evals);
+                        proofs.push(proof);
+                        (evals, proofs)
+                    },
+                )
+        })
+        .collect();
+
+    // The verifier matches the j-th opened fold group with the j-th queried
+    // index (in the order in which the indices were sampled from the
+    // challenger), so the openings must follow the order of indices
+    debug_assert!(izip!(indices, &fold_groups).all(|(&index, (evals, _))| {
+        let matrix = mmcs.get_matrices(prover_data)[0];
+        let rows = (index << log_rows_per_group)..((index + 1) << log_rows_per_group);
+        evals
+            .iter()
+            .copied()
+            .eq(rows.flat_map(|row| matrix.row(row)))
+    }));
+
+    fold_groups
+}
+
 // Observe a list of extension field elements, preceded by its length for
 // security
 pub(crate) fn observe_ext_slice_with_size<F: Field, E: ExtensionField<F>, C: FieldChallenger<F>>(
@@ -205,6 +512,163 @@ pub(crate) fn observe_ext_slice_with_size<F: Field, E: ExtensionField<F>, C: Fie
         .for_each(|&v| challenger.observe_algebra_element(v));
 }
 
+// Observe the public inputs of the statement, preceded by their number, ahead
+// of the rest of the transcript, so that the proof is bound to them. Nothing is
+// observed if there are none, which leaves the transcript of proofs without
+// public inputs unchanged.
+pub(crate) fn observe_public_inputs<F: Field, C: CanObserve<F>>(
+    challenger: &mut C,
+    public_inputs: &[F],
+) {
+    if public_inputs.is_empty() {
+        return;
+    }
+
+    challenger.observe(F::from_u8(Messages::PublicInputs as u8));
+    let size: u64 = public_inputs
+        .len()
+        .try_into()
+        .expect("Slice too long to observe");
+    challenger.observe(F::from_u64(size));
+    challenger.observe_slice(public_inputs);
+}
+
+// Observe the commitments of two STIR instances proven under a shared
+// transcript, so that both proofs are bound to both commitments
+pub(crate) fn observe_paired_commitments<F: Field, Commitment, C>(
+    challenger: &mut C,
+    commitments: (&Commitment, &Commitment),
+) where
+    Commitment: Clone,
+    C: CanObserve<F> + CanObserve<Commitment>,
+{
+    challenger.observe(F::from_u8(Messages::PairedCommitments as u8));
+    challenger.observe(commitments.0.clone());
+    challenger.observe(commitments.1.clone());
+}
+
+// Observe the degree bounds of the polynomials batched by prove_mixed_degrees
+// and the commitment to their evaluations, and sample the randomness alpha with
+// which they are combined
+pub(crate) fn sample_combination_randomness<F, EF, Commitment, C>(
+    // Challenger for the transcript
+    challenger: &mut C,
+    // Degree bounds d_j of the batched polynomials f_j
+    degree_bounds: &[usize],
+    // Commitment to the evaluations of the f_j over L_0
+    inputs_commitment: &Commitment,
+) -> EF
+where
+    F: Field,
+    EF: ExtensionField<F>,
+    Commitment: Clone,
+    C: FieldChallenger<F> + CanObserve<Commitment>,
+{
+    challenger.observe(F::from_u8(Messages::DegreeBounds as u8));
+    observe_usize_slice(challenger, degree_bounds, true);
+    challenger.observe(inputs_commitment.clone());
+
+    batching_challenge::<F, EF, C>(challenger, degree_bounds.len())
+}
+
+/// Samples the coefficient `alpha` of the random linear combination
+/// `f_0 + alpha * f_1 + alpha^2 * f_2 + ...` of `num_polys` batched
+/// polynomials. This is the challenge used by both
+/// [`prove_mixed_degrees`](crate::prove_mixed_degrees) and
+/// [`verify_mixed_degrees`](crate::verify_mixed_degrees), which makes it
+/// available to callers batching polynomials on their own.
+///
+/// For the combination to be sound, the challenge must be squeezed after all
+/// commitments to the batched polynomials (and anything else the prover could
+/// choose based on `alpha`) have been observed by `challenger`: a prover
+/// knowing `alpha` in advance could pick polynomials whose high-degree terms
+/// cancel out in the combination. This function only observes a domain
+/// separator and `num_polys` before sampling.
+///
+/// # Parameters
+///
+/// - `challenger`: Challenger which has already observed the commitments to
+///   the batched polynomials.
+/// - `num_polys`: Number of polynomials combined with the challenge.
+///
+/// # Panics
+///
+/// Panics if `num_polys` is zero.
+pub fn batching_challenge<F, EF, C>(challenger: &mut C, num_polys: usize) -> EF
+where
+    F: Field,
+    EF: ExtensionField<F>,
+    C: FieldChallenger<F>,
+{
+    assert!(num_polys > 0, "At least one polynomial must be batched");
+
+    challenger.observe(F::from_u8(Messages::CombinationRandomness as u8));
+    observe_usize_slice(challenger, &[num_polys], false);
+    challenger.sample_algebra_element()
+}
+
+// Observe the commitment to the combination g of the polynomials batched by
+// prove_mixed_degrees and sample the indices of the fold groups of L_0 (i. e.
+// of points of L_0^k_0) at which g is checked against them
+pub(crate) fn sample_combination_queries<F, Commitment, C>(
+    // Challenger for the transcript
+    challenger: &mut C,
+    // Commitment to the evaluations of g over L_0
+    combination_commitment: &Commitment,
+    // log2 of the number of fold groups of L_0
+    log_num_groups: usize,
+    // Number of indices to sample
+    num_queries: usize,
+) -> Vec<usize>
+where
+    F: Field,
+    Commitment: Clone,
+    C: FieldChallenger<F> + CanObserve<Commitment>,
+{
+    challenger.observe(F::from_u8(Messages::CombinationQueryIndices as u8));
+    challenger.observe(combination_commitment.clone());
+
+    (0..num_queries)
+        .map(|_| challenger.sample_bits(log_num_groups))
+        .collect()
+}
+
+// Number of consistency queries with which the combination g of the
+// polynomials batched by prove_mixed_degrees is checked against them: as many
+// as the first round makes to the codeword of g over L_0, whose rate they
+// share
+pub(crate) fn num_combination_queries<M: Clone>(config: &StirConfig<M>) -> usize {
+    config
+        .round_configs()
+        .first()
+        .map_or(config.final_num_queries(), |round_config| {
+            round_config.num_queries
+        })
+}
+
+// Compute the value at x of the combination
+//   g = f_0 * x^(D - d_0) + alpha * f_1 * x^(D - d_1) + alpha^2 * ...
+// of the polynomials batched by prove_mixed_degrees, given their values at x.
+// The shifts pad each f_j to the common degree bound D, so that g has degree
+// less than D if and only if (with high probability over alpha) each f_j has
+// degree less than d_j.
+pub(crate) fn combine_mixed_degree_evaluations<F: Field>(
+    // The point x
+    point: F,
+    // The combination randomness alpha
+    alpha: F,
+    // The common degree bound D
+    degree_bound: usize,
+    // The degree bounds d_j of the f_j
+    degree_bounds: &[usize],
+    // The values f_j(x)
+    evals: impl IntoIterator<Item = F>,
+) -> F {
+    izip!(evals, degree_bounds, iterate(F::ONE, |&c| c * alpha))
+        .map(|(eval, &bound, coeff)| coeff * point.exp_u64((degree_bound - bound) as u64) * eval)
+        .sum()
+}
+
 // Observe a list of usize, preceded by its length for security.
 pub(crate) fn observe_usize_slice<F: Field, C: CanObserve<F>>(
     challenger: &mut C,
@@ -230,16 +694,33 @@ mod tests {
 
     use iter::Iterator;
     use p3_baby_bear::BabyBear;
+    use p3_challenger::{CanSample, CanSampleBits};
     use p3_coset::TwoAdicCoset;
     use p3_field::PrimeCharacteristicRing;
+    use p3_matrix::Matrix;
     use p3_poly::test_utils::rand_poly;
     use p3_poly::Polynomial;
     use rand::Rng;
 
     use super::*;
+    use crate::test_utils::{test_bb_mmcs_config, BbExt};
 
     type BB = BabyBear;
 
+    #[test]
+    // Checks that checked_domain_size returns the correct size whenever it fits
+    // in a usize and None otherwise
+    fn test_checked_domain_size() {
+        assert_eq!(checked_domain_size(0), Some(1));
+        assert_eq!(checked_domain_size(20), Some(1 << 20));
+        assert_eq!(
+            checked_domain_size(usize::BITS as usize - 1),
+            Some(1 << (usize::BITS - 1))
+        );
+        assert_eq!(checked_domain_size(usize::BITS as usize), None);
+        assert_eq!(checked_domain_size(usize::MAX), None);
+    }
+
     #[test]
     // Checks that fold_polynomial returns the correct polynomial computed
     // manually. All polynomials are hard-coded, but the folding randomness is
@@ -318,6 +799,73 @@ mod tests {
         );
     }
 
+    #[test]
+    // Checks that fold_polynomial computes the linear combination of the
+    // strided sub-polynomials returned by Polynomial::split_radix with the
+    // powers of the folding randomness as coefficients
+    fn test_fold_polynomial_split_radix() {
+        let mut rng = rand::rng();
+
+        for log_folding_factor in 1..5 {
+            let polynomial = rand_poly::<BB>(rng.random_range(0..100));
+            let folding_randomness: BB = rng.random();
+
+            let expected = polynomial
+                .split_radix(log_folding_factor)
+                .iter()
+                .rev()
+                .fold(Polynomial::zero(), |acc, part| {
+                    &(&acc * &folding_randomness) + part
+                });
+
+            assert_eq!(
+                fold_polynomial(&polynomial, folding_randomness, log_folding_factor),
+                expected
+            );
+        }
+
+        let polynomial = rand_poly::<BB>(31);
+        let folding_randomness: BB = rng.random();
+        let (even, odd) = polynomial.split_even_odd();
+
+        assert_eq!(
+            fold_polynomial(&polynomial, folding_randomness, 1),
+            &even + &(&odd * &folding_randomness)
+        );
+    }
+
+    #[test]
+    // Checks that interpolate_bitrev_evals recovers a random polynomial from
+    // its evaluations over the subgroup in bit-reversed order, as well as the
+    // zero polynomial and a constant from a single evaluation
+    fn test_interpolate_bitrev_evals() {
+        let log_n = 6;
+        let n = 1 << log_n;
+
+        let polynomial = rand_poly::<BbExt>(n - 1);
+        let generator = BbExt::two_adic_generator(log_n);
+
+        let evals_bitrev = (0..n)
+            .map(|i: usize| {
+                let exponent = i.reverse_bits() >> (usize::BITS as usize - log_n);
+                polynomial.evaluate(&generator.exp_u64(exponent as u64))
+            })
+            .collect_vec();
+
+        assert_eq!(interpolate_bitrev_evals(evals_bitrev), polynomial);
+
+        assert_eq!(
+            interpolate_bitrev_evals(vec![BbExt::ZERO; n]),
+            Polynomial::zero()
+        );
+
+        let constant = BbExt::from_u32(7);
+        assert_eq!(
+            interpolate_bitrev_evals(vec![constant]),
+            Polynomial::constant(constant)
+        );
+    }
+
     #[test]
     // Checks that multiply_by_power_polynomial returns the same as
     // multiplication by power_polynomial() (the latter being more transparent,
@@ -388,6 +936,89 @@ mod tests {
         }
     }
 
+    #[test]
+    // Checks that the k-ary folding of the evaluations over each fold group of
+    // a domain L (cf. stack_evaluations) yields the evaluation of the folded
+    // polynomial at the corresponding element of L.fold(log2(k))
+    fn test_fold_evaluations_over_folded_domain() {
+        let rng = &mut rand::rng();
+        let polynomial = rand_poly((1 << 6) - 1);
+        let folding_randomness: BB = rng.random();
+
+        let log_size = 8;
+        let log_folding_factor = 2;
+        let mut domain = TwoAdicCoset::new(rng.random(), log_size);
+        let mut folded_domain = domain.fold(log_folding_factor);
+
+        let folded_polynomial =
+            fold_polynomial(&polynomial, folding_randomness, log_folding_factor);
+        let omega = BB::two_adic_generator(log_folding_factor);
+        let num_groups = folded_domain.size();
+
+        for j in 0..num_groups {
+            let evals = (0..1 << log_folding_factor)
+                .map(|t| polynomial.evaluate(&domain.element(j + t * num_groups)))
+                .collect_vec();
+
+            assert_eq!(
+                fold_evaluations(
+                    evals,
+                    (domain.element(j), None),
+                    log_folding_factor,
+                    (omega, None),
+                    folding_randomness,
+                    None
+                ),
+                folded_polynomial.evaluate(&folded_domain.element(j))
+            );
+        }
+    }
+
+    #[test]
+    // Checks that opening the fold groups of all queried indices at once
+    // produces the same openings as opening them one by one, and that these
+    // verify against the commitment, for several leaf widths
+    fn test_open_fold_groups() {
+        let mmcs = test_bb_mmcs_config();
+        let log_folding_factor = 3;
+        let evals = rand_poly::<BbExt>((1 << 8) - 1).coeffs().to_vec();
+
+        // Repeated indices are allowed
+        let indices = [3, 17, 0, 3, 31];
+
+        for log_rows_per_group in 0..=log_folding_factor {
+            let matrix = stack_evaluations(evals.clone(), log_folding_factor, log_rows_per_group);
+            let dimensions = matrix.dimensions();
+            let (commitment, prover_data) = mmcs.commit_matrix(matrix);
+
+            let batched = open_fold_groups(&mmcs, &indices, log_rows_per_group, &prover_data);
+
+            for (&index, (evals, proofs)) in indices.iter().zip(batched) {
+                let (expected_evals, expected_proofs) =
+                    open_fold_group(&mmcs, index, log_rows_per_group, &prover_data);
+
+                assert_eq!(evals, expected_evals);
+                assert_eq!(proofs, expected_proofs);
+
+                for (t, (row, proof)) in evals
+                    .chunks_exact(dimensions.width)
+                    .zip(proofs.iter())
+                    .enumerate()
+                {
+                    assert!(mmcs
+                        .verify_batch(
+                            &commitment,
+                            &[dimensions],
+                            (index << log_rows_per_group) + t,
+                            &[row.to_vec()],
+                            proof
+                        )
+                        .is_ok());
+                }
+            }
+        }
+    }
+
     #[test]
     // Checks that fold_evaluations_binary() returns the expected results
     fn test_fold_evaluations_binary() {
@@ -437,4 +1068,44 @@ mod tests {
 
         assert_eq!(folded_evals, expected_folded_evals);
     }
+
+    // Challenger which ignores observations and returns the given values (in
+    // order) when sampled, allowing collisions to be forced
+    struct ScriptedChallenger(Vec<BB>);
+
+    impl CanObserve<BB> for ScriptedChallenger {
+        fn observe(&mut self, _value: BB) {}
+    }
+
+    impl CanSample<BB> for ScriptedChallenger {
+        fn sample(&mut self) -> BB {
+            self.0.remove(0)
+        }
+    }
+
+    impl CanSampleBits<usize> for ScriptedChallenger {
+        fn sample_bits(&mut self, _bits: usize) -> usize {
+            0
+        }
+    }
+
+    impl FieldChallenger<BB> for ScriptedChallenger {}
+
+    #[test]
+    // Checks that sample_ood_points rejects points inside the domain and
+    // resamples repeated points, and that it gives up on the latter after
+    // MAX_OOD_RESAMPLES attempts rather than looping forever
+    fn test_sample_ood_points() {
+        let domain = TwoAdicCoset::new(BB::ONE, 2);
+        let in_domain = domain.element_immutable(1);
+        let (a, b) = (BB::from_u8(5), BB::from_u8(7));
+
+        let mut challenger = ScriptedChallenger(vec![in_domain, a, a, in_domain, a, b]);
+        assert_eq!(sample_ood_points(&mut challenger, &domain, 2), vec![a, b]);
+        assert!(challenger.0.is_empty());
+
+        let mut challenger = ScriptedChallenger(vec![a; MAX_OOD_RESAMPLES + 2]);
+        assert_eq!(sample_ood_points(&mut challenger, &domain, 2), vec![a, a]);
+        assert!(challenger.0.is_empty());
+    }
 }
@@ -9,13 +9,138 @@ pub enum VerificationError {
     /// At least one of the Merkle proofs of the committed evaluations of `g_M`
     /// is invalid
     FinalQueryPath,
+    /// The proof contains `got` final-round openings of `g_M` instead of one
+    /// per (de-duplicated) final queried index, i. e. `expected`, which
+    /// typically means it was produced under a configuration with a different
+    /// number of final queries
+    QueryCountMismatch { expected: usize, got: usize },
+    /// The proof contains `got` full-round proofs instead of the `expected`
+    /// number `M` of full rounds of the configuration
+    RoundCountMismatch { expected: usize, got: usize },
+    /// One of the final-round openings of `g_M` contains `got` evaluations
+    /// instead of the `expected` ones, i. e. the size `k_M` of a fold group
+    MalformedLeaf { expected: usize, got: usize },
     /// The evaluations of the final polynomial `p = g_{M + 1}` sent in plain
     /// do not match the folded evaluations of `f_M`
     FinalPolynomialEvaluations,
     /// The proof of work for the final round `i = M + 1` is incorrect
     FinalProofOfWork,
+    /// The proof was produced under a configuration whose parameter
+    /// fingerprint (cf. `StirConfig::parameter_fingerprint`) is `got` instead
+    /// of the `expected` one of the verifier's configuration
+    ParameterMismatch { expected: u64, got: u64 },
+    /// The domain schedule passed to `verify_with_domains` does not consist
+    /// of the domains `L_0, ..., L_M` of the configuration (cf.
+    /// `StirConfig::domains`)
+    DomainMismatch,
+    /// The envelope passed to `verify_envelope` has an unsupported format
+    /// version, its parameters do not form a valid configuration or they fix
+    /// fewer out-of-domain samples than their security level requires
+    InvalidEnvelope,
+    /// The parameters of the envelope passed to `verify_envelope` declare a
+    /// security level of `got` bits, below the `required` minimum
+    InsufficientSecurityLevel { required: usize, got: usize },
+    /// The proof passed to `verify_against_any` is not valid for any of the
+    /// candidate commitments
+    NoMatchingCommitment,
     /// Invalid proof for the `i`-th full round (`1 <= i <= M`)
     Round(usize, FullRoundVerificationError),
+    /// One of the degree bounds passed to `verify_mixed_degrees` is greater
+    /// than the degree bound of the configuration, or none was passed
+    InvalidDegreeBound,
+    /// One of the openings of the batched polynomials or of their combination
+    /// at the consistency queries of a mixed-degree proof is missing or
+    /// malformed, or its Merkle proof is invalid
+    CombinationQueryPath,
+    /// The combination of the batched polynomials committed to in a
+    /// mixed-degree proof does not match the combination of their opened
+    /// evaluations at some consistency query, e. g. because one of them does
+    /// not satisfy its declared degree bound
+    CombinationMismatch,
+    /// The proof contains more field elements or Merkle-proof nodes than
+    /// allowed by the limits passed to `verify_with_limits`
+    ProofTooLarge {
+        field_elements: usize,
+        merkle_nodes: usize,
+    },
+    /// The proof passed to `verify_from_reader` could not be decoded (it is
+    /// not a valid `encode_v1` encoding, is tagged with an unsupported format
+    /// version or is followed by trailing data)
+    #[cfg(feature = "std")]
+    Decoding,
+}
+
+/// Detailed description of the rejection of a proof, as returned by
+/// [`verify_diagnostic`](crate::verify_diagnostic)
+#[derive(Debug, PartialEq)]
+pub struct VerificationFailure<F> {
+    /// The reason for the rejection
+    pub error: VerificationError,
+    /// The round in which the proof was rejected: `i` for the `i`-th full
+    /// round (`1 <= i <= M`) and `M + 1` for the final round, or `None` if the
+    /// rejection is not specific to a round (e. g. a parameter mismatch or a
+    /// malformed proof)
+    pub round: Option<usize>,
+    /// The first query of that round whose folded value does not match the
+    /// value claimed by the prover, if this is the reason for the rejection
+    /// (i. e. if `error` is `FinalPolynomialEvaluations` or
+    /// `AnsPolynomialEvaluations` and a specific query is at fault)
+    pub mismatch: Option<QueryMismatch<F>>,
+}
+
+/// A query whose folded value does not match the value claimed by the prover
+#[derive(Debug, PartialEq)]
+pub struct QueryMismatch<F> {
+    /// Position of the query among those of its round, i. e. index of the
+    /// corresponding opening in the round's list of query proofs
+    pub query: usize,
+    /// Index of the queried point in the domain `L_{i - 1}^{k_{i - 1}}` from
+    /// which it is sampled
+    pub domain_index: usize,
+    /// The folded value computed by the verifier from the opened evaluations
+    pub expected: F,
+    /// The value at the folded point of the polynomial sent by the prover
+    /// (`Ans_i` in full round `i` and the final polynomial in the final round)
+    pub actual: F,
+}
+
+/// Error during the verification of a pair of proofs sharing a transcript,
+/// indicating which of the two proofs was rejected
+#[derive(Debug, PartialEq)]
+pub enum PairVerificationError {
+    /// The first proof of the pair is invalid
+    First(VerificationError),
+    /// The second proof of the pair is invalid (the first one being valid)
+    Second(VerificationError),
+}
+
+/// Structural inconsistency between a proof and a configuration, as detected
+/// by [`StirProof::validate_shape`](crate::StirProof::validate_shape) without
+/// any Merkle-proof verification or transcript replay
+#[derive(Debug, PartialEq)]
+pub enum ShapeError {
+    /// The proof contains `got` full-round proofs instead of the `expected`
+    /// number `M` of full rounds of the configuration
+    NumRounds { expected: usize, got: usize },
+    /// The degree plus 1 of the final polynomial `p = g_{M + 1}` is greater
+    /// than the final degree bound of the configuration
+    FinalPolynomialDegree,
+    /// The proof of the `round`-th full round contains `got` replies
+    /// `beta_{i, j}` instead of one per out-of-domain sample
+    NumBetas {
+        round: usize,
+        expected: usize,
+        got: usize,
+    },
+    /// The degree of `Ans_i` in the `round`-th full round is not lower than the
+    /// maximum number of points it may have to interpolate, i. e. the number
+    /// of out-of-domain samples plus that of queries of the round
+    AnsPolynomialDegree { round: usize },
+    /// The degree of the shake polynomial of the `round`-th full round is not
+    /// lower than the maximum degree of `Ans_i` allowed by the configuration
+    /// (never returned with the `no-shake` feature, under which proofs contain
+    /// no shake polynomial)
+    ShakePolynomialDegree { round: usize },
 }
 
 /// Error during the verification of the `i`-th full round (`1 <= i <= M`)
@@ -23,12 +148,31 @@ pub enum VerificationError {
 pub enum FullRoundVerificationError {
     /// Invalid proof of work for this round
     ProofOfWork,
+    /// The out-of-domain points derived from the transcript are not pairwise
+    /// distinct, which can only happen if the challenger is degenerate
+    DuplicateOodPoint,
+    /// The proof contains `got` replies `beta_{i, j}` instead of one per
+    /// out-of-domain sample of this round, i. e. `expected`, which typically
+    /// means it was truncated or padded
+    BetaCountMismatch { expected: usize, got: usize },
+    /// At least one of the replies `beta_{i, j}` to the out-of-domain queries
+    /// does not match the evaluation of `Ans_i` at the corresponding
+    /// out-of-domain point
+    OodReplyInconsistent,
     /// At least one of the Merkle proofs of the evaluations of `g_{i - 1}` at
     /// the queried indices is incorrect
     QueryPath,
-    /// The degree of the polynomial `Ans_i` is greater than the length of the
-    /// (de-duplicated) list of in-domain and out-of-domain sampled points for
-    /// this round
+    /// The proof contains `got` openings of `g_{i - 1}` instead of one per
+    /// (de-duplicated) queried index, i. e. `expected`, which typically means
+    /// it was produced under a configuration with a different number of
+    /// queries for this round
+    QueryCountMismatch { expected: usize, got: usize },
+    /// One of the openings of `g_{i - 1}` contains `got` evaluations instead
+    /// of the `expected` ones, i. e. the size `k_{i - 1}` of a fold group
+    MalformedLeaf { expected: usize, got: usize },
+    /// The degree of the polynomial `Ans_i` is not lower than the number of
+    /// (de-duplicated) in-domain and out-of-domain sampled points for this
+    /// round, which it is supposed to interpolate
     AnsPolynomialDegree,
     /// The folded evaluations of the previous round's polynomial `f_{i - 1}`
     /// do not match the purported evaluations of `g_i` interpolated by `Ans_i`
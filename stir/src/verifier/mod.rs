@@ -1,21 +1,39 @@
 use alloc::vec;
 use alloc::vec::Vec;
+use core::iter;
 
-use error::{FullRoundVerificationError, VerificationError};
-use itertools::{iterate, Itertools};
+use error::{
+    FullRoundVerificationError, PairVerificationError, QueryMismatch, VerificationError,
+    VerificationFailure,
+};
+use itertools::{iterate, izip, Itertools};
 use p3_challenger::{CanObserve, FieldChallenger, GrindingChallenger};
 use p3_commit::Mmcs;
 use p3_coset::TwoAdicCoset;
 use p3_field::{batch_multiplicative_inverse, ExtensionField, Field, TwoAdicField};
 use p3_matrix::Dimensions;
 use p3_poly::Polynomial;
+use size::num_serialized_values;
 
 use crate::config::{observe_public_parameters, RoundConfig};
 use crate::proof::RoundProof;
-use crate::utils::{fold_evaluations, observe_ext_slice_with_size};
-use crate::{Messages, StirConfig, StirProof, POW_BITS_WARNING};
-
-mod error;
+#[cfg(feature = "std")]
+use crate::proof::{FormatVersion, V1Decoder};
+use crate::prover::initial_domain;
+use crate::utils::{
+    combine_mixed_degree_evaluations, fold_evaluations, num_combination_queries,
+    observe_ext_slice_with_size, observe_paired_commitments, observe_public_inputs,
+    sample_combination_queries, sample_combination_randomness, sample_folding_randomness,
+    sample_next_folding_randomness, sample_ood_points, FoldChallengeSource,
+    TranscriptFoldChallenge,
+};
+use crate::{
+    Messages, MixedDegreeProof, StirConfig, StirParametersSpec, StirProof, StirProofEnvelope,
+    POW_BITS_WARNING,
+};
+
+pub(crate) mod error;
+mod size;
 
 #[cfg(test)]
 mod tests;
@@ -64,6 +82,9 @@ impl<F: TwoAdicField> Oracle<F> {
         quotient_denom_inverse_hint: Option<F>,
         // A similar phenomenon occurs for the degree-correction factor
         deg_cor_hint: Option<(F, F)>,
+        // The value of the interpolating polynomial at the point x, which can
+        // likewise be computed for many points at once outside
+        interpolating_eval_hint: Option<F>,
     ) -> F {
         match self {
             // In this case, the oracle contains the values of f_0 = g_0
@@ -78,7 +99,9 @@ impl<F: TwoAdicField> Oracle<F> {
                 );
 
                 // Computing the quotient (Quot in the article)
-                let quotient_num = f_x - virtual_function.interpolating_polynomial.evaluate(&x);
+                let interpolating_eval = interpolating_eval_hint
+                    .unwrap_or_else(|| virtual_function.interpolating_polynomial.evaluate(&x));
+                let quotient_num = f_x - interpolating_eval;
 
                 let quotient_denom_inverse = quotient_denom_inverse_hint.unwrap_or_else(|| {
                     virtual_function
@@ -142,6 +165,215 @@ pub struct VerificationState<F: TwoAdicField, M: Mmcs<F>> {
     root: M::Commitment,
 }
 
+/// Merkle-proof checks postponed by [`verify_cheap_checks`], to be performed
+/// later by calling [`DeferredQueries::verify`].
+pub struct DeferredQueries<F: Field, M: Mmcs<F>> {
+    // One entry per round whose openings have been deferred, in the order
+    // in which the rounds were verified
+    rounds: Vec<DeferredRoundQueries<F, M>>,
+}
+
+// The openings of the committed evaluations of g_{i - 1} (or g_M in the final
+// round) at the queried indices of one round
+struct DeferredRoundQueries<F: Field, M: Mmcs<F>> {
+    // Round i in {1, ..., M} for full rounds, None for the final round
+    round: Option<usize>,
+    // Root of the Merkle tree the openings refer to
+    root: M::Commitment,
+    // Layout of the committed matrix of stacked evaluations
+    layout: StackedLayout,
+    // Indices of the opened fold groups
+    queried_indices: Vec<usize>,
+    // Opened fold groups and the Merkle proofs of their rows
+    query_proofs: Vec<(Vec<F>, Vec<M::Proof>)>,
+}
+
+// Layout of a committed matrix of stacked evaluations (cf.
+// utils::stack_evaluations)
+#[derive(Clone, Copy)]
+struct StackedLayout {
+    // Dimensions of the committed matrix
+    dimensions: Dimensions,
+    // log2 of the number of consecutive rows each fold group is split into
+    log_rows_per_group: usize,
+}
+
+impl StackedLayout {
+    // Layout of the evaluations over a domain of size 2^log_domain_size
+    // stacked for folding with factor 2^log_folding_factor
+    fn new(log_domain_size: usize, log_folding_factor: usize, log_rows_per_group: usize) -> Self {
+        let log_width = log_folding_factor - log_rows_per_group;
+        StackedLayout {
+            dimensions: Dimensions {
+                width: 1 << log_width,
+                height: 1 << (log_domain_size - log_width),
+            },
+            log_rows_per_group,
+        }
+    }
+}
+
+// Handling of failed checks: by default, verification stops at the first one
+// and returns its error, whereas verify_collect_errors records each of them and
+// carries on with the remaining checks. Failures after which the remaining
+// checks are meaningless (e. g. openings which cannot be matched to the
+// queries or folded) always stop verification.
+#[derive(Default)]
+struct FailureSink {
+    // The errors recorded so far, if failures are being collected
+    collected: Option<Vec<VerificationError>>,
+}
+
+impl FailureSink {
+    // Sink recording all failures instead of stopping at the first one
+    fn collecting() -> Self {
+        FailureSink {
+            collected: Some(Vec::new()),
+        }
+    }
+
+    // Reports a failed check, returning its error if verification must stop
+    // there and recording it (as converted by wrap) otherwise
+    fn report<E>(
+        &mut self,
+        // The error describing the failed check
+        error: E,
+        // Conversion into a VerificationError, e. g. attaching the round
+        wrap: impl FnOnce(E) -> VerificationError,
+    ) -> Result<(), E> {
+        match &mut self.collected {
+            Some(collected) => {
+                collected.push(wrap(error));
+                Ok(())
+            }
+            None => Err(error),
+        }
+    }
+}
+
+// Destinations of the by-products of verification, shared by all of its rounds
+struct VerificationOutputs<'a, F: Field, M: Mmcs<F>> {
+    // If provided, storage for the Merkle proofs of the queried evaluations,
+    // which are then not verified
+    deferred: Option<&'a mut DeferredQueries<F, M>>,
+    // The first query whose folded value does not match the polynomial sent
+    // by the prover (Ans_i or the final polynomial), if verification fails for
+    // that reason
+    mismatch: Option<QueryMismatch<F>>,
+    // Handling of failed checks
+    failures: FailureSink,
+}
+
+impl<'a, F: Field, M: Mmcs<F>> VerificationOutputs<'a, F, M> {
+    fn new(deferred: Option<&'a mut DeferredQueries<F, M>>, failures: FailureSink) -> Self {
+        VerificationOutputs {
+            deferred,
+            mismatch: None,
+            failures,
+        }
+    }
+}
+
+impl<F: Field, M: Mmcs<F>> DeferredQueries<F, M> {
+    /// Verifies all the Merkle proofs which were deferred by
+    /// [`verify_cheap_checks`]. The proof is valid if and only if both this
+    /// method and the call to `verify_cheap_checks` which produced `self`
+    /// succeed.
+    ///
+    /// # Parameters
+    ///
+    /// - `mmcs`: The MMCS used to commit to the codewords, i. e.
+    ///   `config.mmcs_config()` where `config` is the one passed to
+    ///   `verify_cheap_checks`.
+    pub fn verify(&self, mmcs: &M) -> Result<(), VerificationError> {
+        for round_queries in &self.rounds {
+            if !verify_query_paths(
+                mmcs,
+                &round_queries.root,
+                round_queries.layout,
+                &round_queries.queried_indices,
+                &round_queries.query_proofs,
+            ) {
+                return Err(match round_queries.round {
+                    Some(i) => VerificationError::Round(i, FullRoundVerificationError::QueryPath),
+                    None => VerificationError::FinalQueryPath,
+                });
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Largest security level (in bits) of the parameters of an envelope accepted
+/// by [`verify_envelope`]. Since the number of queries, and hence the work of
+/// the verifier, grows with the security level, envelopes cannot declare
+/// arbitrarily large ones.
+pub const MAX_ENVELOPE_SECURITY_LEVEL: usize = 256;
+
+/// Caps on the size of a proof accepted by [`verify_with_limits`], which
+/// rejects larger proofs before performing any expensive work.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VerificationLimits {
+    /// Maximum total number of field elements in the proof: coefficients of
+    /// the polynomials sent in plain, out-of-domain replies and opened
+    /// evaluations
+    pub max_field_elements: usize,
+
+    /// Maximum total number of nodes (i. e. sibling digests) in the Merkle
+    /// proofs of the opened rows of the committed matrices. Since the type of
+    /// Merkle proofs is specific to the MMCS, the number of nodes of one of
+    /// them is measured as the number of primitive values it serializes to,
+    /// divided by that of the commitment (i. e. of one digest) and rounded up.
+    pub max_merkle_nodes: usize,
+}
+
+impl VerificationLimits {
+    /// Returns the tightest limits satisfied by every proof produced by an
+    /// honest prover under `config`, i. e. the sizes of such a proof when no
+    /// queried indices are repeated, with one node per level of the committed
+    /// matrix in each Merkle proof.
+    pub fn from_config<M: Clone>(config: &StirConfig<M>) -> Self {
+        // Openings of round i (in {1, ..., M + 1}) with the given number of
+        // queries: one fold group of k_{i - 1} evaluations per query, whose
+        // rows each come with a Merkle proof of the matrix committed to in the
+        // previous round
+        let openings = |i: usize, num_queries: usize| {
+            let log_folding_factor = config.log_folding_factors()[i - 1];
+            let log_height = config.committed_matrix_dimensions(i - 1).height.ilog2() as usize;
+
+            (
+                num_queries << log_folding_factor,
+                (num_queries << config.log_rows_per_fold_group(log_folding_factor)) * log_height,
+            )
+        };
+
+        let (mut max_field_elements, mut max_merkle_nodes) =
+            openings(config.total_rounds(), config.final_num_queries());
+        max_field_elements += config.final_degree_bound();
+
+        for (i, round_config) in config.round_configs().iter().enumerate() {
+            let (evaluations, merkle_nodes) = openings(i + 1, round_config.num_queries);
+            let max_num_points = round_config.num_ood_samples + round_config.num_queries;
+
+            // Replies beta_{i, j}, Ans_i and openings
+            max_field_elements += round_config.num_ood_samples + max_num_points + evaluations;
+            max_merkle_nodes += merkle_nodes;
+
+            // Shake polynomial
+            #[cfg(not(feature = "no-shake"))]
+            {
+                max_field_elements += max_num_points - 1;
+            }
+        }
+
+        VerificationLimits {
+            max_field_elements,
+            max_merkle_nodes,
+        }
+    }
+}
+
 /// Verifies the proof that the committed codeword satisfies the low-degreeness
 /// bound specified in the configuration.
 ///
@@ -149,14 +381,19 @@ pub struct VerificationState<F: TwoAdicField, M: Mmcs<F>> {
 ///
 /// - `config`: The full STIR configuration, including the degree bound.
 /// - `commitment`: The commitment to the codeword encoding the polynomial of interest.
-/// - `proof`: The proof to verify, which includes the committed .
-/// - `challenger`: The challenger to use for the proof verification.
+/// - `proof`: The proof to verify. It is only borrowed, so that it can be
+///   verified several times (e. g. against different challengers) or
+///   inspected afterwards.
+/// - `challenger`: The challenger to use for the proof verification. It is
+///   left in the state reached at the end of verification, which coincides
+///   with that of the prover's challenger if verification succeeds. The
+///   requirements on it are the same as in [`prove`](crate::prove).
 ///
 /// # Returns
 pub fn verify<F, EF, M, C>(
     config: &StirConfig<M>,
     commitment: M::Commitment,
-    proof: StirProof<EF, M, C::Witness>,
+    proof: &StirProof<EF, M, C::Witness>,
     challenger: &mut C,
 ) -> Result<(), VerificationError>
 where
@@ -165,65 +402,1177 @@ where
     M: Mmcs<EF>,
     C: FieldChallenger<F> + GrindingChallenger + CanObserve<M::Commitment>,
 {
-    // Inform the verifier if the configuration requires a proof of work from
-    // the prover larger than the POW_BITS_WARNING constant. This is only logged
-    // if the tracing module has been init()ialised.
-    if config
+    verify_with_fold_challenge_source(
+        config,
+        commitment,
+        proof,
+        challenger,
+        &TranscriptFoldChallenge,
+    )
+}
+
+/// Variant of [`verify`] for proofs produced by
+/// [`prove_with_public_inputs`](crate::prove_with_public_inputs), which are
+/// only accepted if `public_inputs` are the ones they were produced with.
+/// Passing no public inputs is equivalent to calling [`verify`].
+///
+/// # Parameters
+///
+/// - `config`, `commitment`, `proof`, `challenger`: Same as in [`verify`].
+/// - `public_inputs`: Public context of the statement, in the base field of
+///   the challenger.
+pub fn verify_with_public_inputs<F, EF, M, C>(
+    config: &StirConfig<M>,
+    commitment: M::Commitment,
+    proof: &StirProof<EF, M, C::Witness>,
+    public_inputs: &[F],
+    challenger: &mut C,
+) -> Result<(), VerificationError>
+where
+    F: Field,
+    EF: TwoAdicField + ExtensionField<F>,
+    M: Mmcs<EF>,
+    C: FieldChallenger<F> + GrindingChallenger + CanObserve<M::Commitment>,
+{
+    observe_public_inputs(challenger, public_inputs);
+
+    verify(config, commitment, proof, challenger)
+}
+
+/// Variant of [`verify`] for proofs produced by
+/// [`prove_with_fold_challenge_source`](crate::prove_with_fold_challenge_source),
+/// deriving the folding randomness through the given [`FoldChallengeSource`].
+/// The source must derive the same challenges as the prover's for the proof to
+/// be accepted.
+///
+/// # Parameters
+///
+/// - `config`, `commitment`, `proof`, `challenger`: Same as in [`verify`].
+/// - `source`: Derivation of the folding randomness `r_i` from the
+///   transcript.
+pub fn verify_with_fold_challenge_source<F, EF, M, C, S>(
+    config: &StirConfig<M>,
+    commitment: M::Commitment,
+    proof: &StirProof<EF, M, C::Witness>,
+    challenger: &mut C,
+    source: &S,
+) -> Result<(), VerificationError>
+where
+    F: Field,
+    EF: TwoAdicField + ExtensionField<F>,
+    M: Mmcs<EF>,
+    C: FieldChallenger<F> + GrindingChallenger + CanObserve<M::Commitment>,
+    S: FoldChallengeSource<F>,
+{
+    verify_with_deferral(
+        config,
+        commitment,
+        proof,
+        challenger,
+        source,
+        None,
+        &mut VerificationOutputs::new(None, FailureSink::default()),
+    )
+}
+
+/// Verifies the proof contained in `envelope` against the commitment contained
+/// in it, under the configuration reconstructed from its parameters and the
+/// given MMCS configuration. Since the envelope is untrusted, so are its
+/// parameters: the caller specifies the minimum security level it accepts,
+/// which the parameters must declare and meet.
+///
+/// # Parameters
+///
+/// - `envelope`: The envelope bundling the proof, the commitment and the
+///   parameters of the configuration.
+/// - `mmcs_config`: The MMCS configuration the proof was produced with, which
+///   is not part of the envelope.
+/// - `min_security_level`: The minimum number of bits of security of the
+///   configuration under which the proof is accepted.
+/// - `challenger`: The challenger to use for the proof verification, as in
+///   [`verify`].
+///
+/// # Errors
+///
+/// Returns [`VerificationError::InvalidEnvelope`] if the envelope has a
+/// different format version than [`StirProofEnvelope::FORMAT_VERSION`], its
+/// parameters cannot be expanded into a configuration, they declare a
+/// security level above [`MAX_ENVELOPE_SECURITY_LEVEL`], they require a proof
+/// of work of at least as many bits as the field of the challenger has or
+/// they fix fewer out-of-domain samples than their security level requires,
+/// [`VerificationError::InsufficientSecurityLevel`] if they declare a security
+/// level below `min_security_level`, and the errors of [`verify`] otherwise.
+pub fn verify_envelope<F, EF, M, C>(
+    envelope: &StirProofEnvelope<EF, M, C::Witness>,
+    mmcs_config: M,
+    min_security_level: usize,
+    challenger: &mut C,
+) -> Result<(), VerificationError>
+where
+    F: Field,
+    EF: TwoAdicField + ExtensionField<F>,
+    M: Mmcs<EF>,
+    C: FieldChallenger<F> + GrindingChallenger + CanObserve<M::Commitment>,
+{
+    let StirProofEnvelope {
+        format_version,
+        parameters,
+        commitment,
+        proof,
+    } = envelope;
+
+    if *format_version != StirProofEnvelope::<EF, M, C::Witness>::FORMAT_VERSION {
+        return Err(VerificationError::InvalidEnvelope);
+    }
+
+    if parameters.security_level < min_security_level {
+        return Err(VerificationError::InsufficientSecurityLevel {
+            required: min_security_level,
+            got: parameters.security_level,
+        });
+    }
+
+    // The number of queries grows with the security level, so an unbounded
+    // one would let the envelope make the verifier do arbitrary work
+    if parameters.security_level > MAX_ENVELOPE_SECURITY_LEVEL {
+        return Err(VerificationError::InvalidEnvelope);
+    }
+
+    let scheduled_pow_bits = parameters
+        .query_pow_schedule
+        .iter()
+        .flatten()
+        .map(|&(_, pow_bits)| pow_bits);
+
+    if !iter::once(parameters.pow_bits)
+        .chain(scheduled_pow_bits)
+        .all(is_grindable::<F>)
+    {
+        return Err(VerificationError::InvalidEnvelope);
+    }
+
+    let config = StirConfig::new::<EF>(parameters.clone().with_mmcs_config(mmcs_config.clone()))
+        .map_err(|_| VerificationError::InvalidEnvelope)?;
+
+    // The proof-of-work bits derived from the security level must be
+    // checkable too
+    if !config
         .pow_bits_all_rounds()
+        .into_iter()
+        .all(is_grindable::<F>)
+    {
+        return Err(VerificationError::InvalidEnvelope);
+    }
+
+    // StirConfig::new does not check fixed numbers of out-of-domain samples
+    // against the security level, so they must be at least the derived ones
+    if parameters.num_ood_samples.is_some() {
+        let derived_config = StirConfig::new::<EF>(
+            StirParametersSpec {
+                num_ood_samples: None,
+                ..parameters.clone()
+            }
+            .with_mmcs_config(mmcs_config),
+        )
+        .map_err(|_| VerificationError::InvalidEnvelope)?;
+
+        if config
+            .round_configs()
+            .iter()
+            .zip(derived_config.round_configs())
+            .any(|(fixed, derived)| fixed.num_ood_samples < derived.num_ood_samples)
+        {
+            return Err(VerificationError::InvalidEnvelope);
+        }
+    }
+
+    verify(&config, commitment.clone(), proof, challenger)
+}
+
+// Whether a proof of work of the given number of bits can be checked by a
+// challenger over F, i. e. whether 2^bits is smaller than the order of F
+// (challengers panic when sampling more bits)
+fn is_grindable<F: Field>(bits: usize) -> bool {
+    bits < F::bits()
+}
+
+/// Verifies the proof like [`verify`], taking the domains `L_0, ..., L_M`
+/// from a schedule precomputed with [`StirConfig::domains`] instead of
+/// deriving them from the configuration. When verifying many proofs under the
+/// same configuration, the schedule can be computed once and reused, together
+/// with the powers of the generators its domains have memoised.
+///
+/// # Parameters
+///
+/// - `config`, `commitment`, `proof`, `challenger`: Same as in [`verify`].
+/// - `domains`: The domains `L_0, ..., L_M` of `config`, as returned by
+///   [`StirConfig::domains`].
+///
+/// # Errors
+///
+/// Returns [`VerificationError::DomainMismatch`] if `domains` does not contain
+/// exactly the domains of `config` (with the same sizes, shifts and
+/// generators), and the errors of [`verify`] otherwise.
+pub fn verify_with_domains<F, EF, M, C>(
+    config: &StirConfig<M>,
+    commitment: M::Commitment,
+    domains: &[TwoAdicCoset<EF>],
+    proof: &StirProof<EF, M, C::Witness>,
+    challenger: &mut C,
+) -> Result<(), VerificationError>
+where
+    F: Field,
+    EF: TwoAdicField + ExtensionField<F>,
+    M: Mmcs<EF>,
+    C: FieldChallenger<F> + GrindingChallenger + CanObserve<M::Commitment>,
+{
+    check_domains(config, domains)?;
+
+    verify_with_deferral(
+        config,
+        commitment,
+        proof,
+        challenger,
+        &TranscriptFoldChallenge,
+        Some(domains),
+        &mut VerificationOutputs::new(None, FailureSink::default()),
+    )
+}
+
+/// Verifies the proof like [`verify`], but describes the reason for a rejection
+/// in more detail to help debug a prover. If the folded value of a query (i. e.
+/// the one computed by the verifier from the opened evaluations) does not
+/// match the value of the polynomial sent by the prover (`Ans_i` or the final
+/// polynomial), the failure indicates the round and the query in question as
+/// well as both values.
+///
+/// In order to be able to pinpoint such queries even when the opened
+/// evaluations themselves have been tampered with, the Merkle proofs are
+/// verified after all other checks (as in [`verify_cheap_checks`]). Hence,
+/// the error reported for an invalid proof may differ from the one returned
+/// by [`verify`], although a proof is accepted by one function if and only if
+/// it is accepted by the other.
+///
+/// # Parameters
+///
+/// Same as [`verify`].
+pub fn verify_diagnostic<F, EF, M, C>(
+    config: &StirConfig<M>,
+    commitment: M::Commitment,
+    proof: &StirProof<EF, M, C::Witness>,
+    challenger: &mut C,
+) -> Result<(), VerificationFailure<EF>>
+where
+    F: Field,
+    EF: TwoAdicField + ExtensionField<F>,
+    M: Mmcs<EF>,
+    C: FieldChallenger<F> + GrindingChallenger + CanObserve<M::Commitment>,
+{
+    let mut deferred = DeferredQueries { rounds: Vec::new() };
+
+    let failure = |error| {
+        let round = match error {
+            VerificationError::Round(i, _) => Some(i),
+            VerificationError::FinalPolynomialDegree
+            | VerificationError::FinalQueryPath
+            | VerificationError::FinalPolynomialEvaluations
+            | VerificationError::FinalProofOfWork => Some(config.total_rounds()),
+            _ => None,
+        };
+        VerificationFailure {
+            error,
+            round,
+            mismatch: None,
+        }
+    };
+
+    let mut outputs = VerificationOutputs::new(Some(&mut deferred), FailureSink::default());
+
+    verify_with_deferral(
+        config,
+        commitment,
+        proof,
+        challenger,
+        &TranscriptFoldChallenge,
+        None,
+        &mut outputs,
+    )
+    .map_err(|error| VerificationFailure {
+        mismatch: outputs.mismatch,
+        ..failure(error)
+    })?;
+
+    deferred.verify(config.mmcs_config()).map_err(failure)
+}
+
+/// Verifies the proof like [`verify`], but carries on after a failed check
+/// and reports every failure found, rather than only the first one. This is
+/// meant for debugging a prover which gets several things wrong at once (e. g.
+/// an invalid proof of work as well as wrong evaluations). Checks after a
+/// failed Merkle proof use the opened evaluations as they are.
+///
+/// Some failures leave nothing meaningful to check in the remainder of the
+/// proof. These are a number of openings different from that of the queried
+/// indices and an opening of the wrong size. Such a failure ends verification
+/// and is the last error reported.
+///
+/// # Parameters
+///
+/// Same as [`verify`].
+///
+/// # Errors
+///
+/// Returns all the errors found, in the order in which the checks are
+/// performed, if the proof is invalid. The first of them is the error
+/// returned by [`verify`], and the proof is accepted if and only if it is
+/// accepted by [`verify`].
+pub fn verify_collect_errors<F, EF, M, C>(
+    config: &StirConfig<M>,
+    commitment: M::Commitment,
+    proof: &StirProof<EF, M, C::Witness>,
+    challenger: &mut C,
+) -> Result<(), Vec<VerificationError>>
+where
+    F: Field,
+    EF: TwoAdicField + ExtensionField<F>,
+    M: Mmcs<EF>,
+    C: FieldChallenger<F> + GrindingChallenger + CanObserve<M::Commitment>,
+{
+    let mut outputs = VerificationOutputs::new(None, FailureSink::collecting());
+
+    let outcome = verify_with_deferral(
+        config,
+        commitment,
+        proof,
+        challenger,
+        &TranscriptFoldChallenge,
+        None,
+        &mut outputs,
+    );
+
+    let mut errors = outputs.failures.collected.unwrap_or_default();
+    errors.extend(outcome.err());
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+/// Verifies a pair of proofs produced by [`prove_pair`](crate::prove_pair)
+/// under a single shared transcript. The pair is accepted if and only if both
+/// proofs are valid for their respective configurations and commitments, and
+/// the error indicates which of the two was rejected otherwise.
+///
+/// # Parameters
+///
+/// - `configs`: The full STIR configurations of the first and second statements.
+/// - `commitments`: The commitments to the first and second codewords.
+/// - `proofs`: The first and second proofs, in the order in which they were
+///   produced.
+/// - `challenger`: The challenger to use for the shared transcript, as in
+///   [`verify`].
+pub fn verify_pair<F, EF, M, C>(
+    configs: (&StirConfig<M>, &StirConfig<M>),
+    commitments: (M::Commitment, M::Commitment),
+    proofs: (&StirProof<EF, M, C::Witness>, &StirProof<EF, M, C::Witness>),
+    challenger: &mut C,
+) -> Result<(), PairVerificationError>
+where
+    F: Field,
+    EF: TwoAdicField + ExtensionField<F>,
+    M: Mmcs<EF>,
+    C: FieldChallenger<F> + GrindingChallenger + CanObserve<M::Commitment>,
+{
+    observe_paired_commitments::<F, _, _>(challenger, (&commitments.0, &commitments.1));
+
+    verify(configs.0, commitments.0, proofs.0, challenger).map_err(PairVerificationError::First)?;
+    verify(configs.1, commitments.1, proofs.1, challenger).map_err(PairVerificationError::Second)
+}
+
+/// Verifier for a chain of proofs produced by
+/// [`ChainedProver`](crate::ChainedProver). The proofs must be verified in the
+/// order in which they were produced, with the same configurations and
+/// commitments, and starting from the same challenger: a proof verified out of
+/// its position in the chain is rejected, since the transcript it was produced
+/// with does not match. Once a proof has been rejected, the state of the
+/// challenger is meaningless and the whole chain must be rejected.
+pub struct ChainedVerifier<C> {
+    // Challenger threaded through all the proofs of the chain
+    challenger: C,
+
+    // Number of proofs accepted so far
+    num_proofs: usize,
+}
+
+impl<C> ChainedVerifier<C> {
+    /// Starts verifying a chain of proofs with `challenger`, which must be in
+    /// the same state as the one the [`ChainedProver`](crate::ChainedProver)
+    /// was created with.
+    pub fn new(challenger: C) -> Self {
+        Self {
+            challenger,
+            num_proofs: 0,
+        }
+    }
+
+    /// Verifies the next proof of the chain as in [`verify`].
+    ///
+    /// # Parameters
+    ///
+    /// - `config`: The full STIR configuration of the statement.
+    /// - `commitment`: The commitment to the codeword of the statement.
+    /// - `proof`: The next proof of the chain.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`verify`]. In particular, a proof which is not the next one of
+    /// the chain is rejected (as a proof for a different transcript).
+    pub fn verify<F, EF, M>(
+        &mut self,
+        config: &StirConfig<M>,
+        commitment: M::Commitment,
+        proof: &StirProof<EF, M, C::Witness>,
+    ) -> Result<(), VerificationError>
+    where
+        F: Field,
+        EF: TwoAdicField + ExtensionField<F>,
+        M: Mmcs<EF>,
+        C: FieldChallenger<F> + GrindingChallenger + CanObserve<M::Commitment>,
+    {
+        verify(config, commitment, proof, &mut self.challenger)?;
+        self.num_proofs += 1;
+        Ok(())
+    }
+
+    /// Number of proofs of the chain accepted so far.
+    pub fn num_proofs(&self) -> usize {
+        self.num_proofs
+    }
+
+    /// Ends the chain, returning the challenger in its final state, which
+    /// matches that of the prover if all the proofs of the chain have been
+    /// accepted.
+    pub fn into_challenger(self) -> C {
+        self.challenger
+    }
+}
+
+/// Verifies a proof produced by [`prove_mixed_degrees`](crate::prove_mixed_degrees)
+/// that each of the batched polynomials `f_j` has degree less than its bound
+/// `d_j`. The combination `g` of the `f_j` is recomputed from their opened
+/// evaluations at the consistency queries and compared to the committed one,
+/// whose low-degreeness is then verified as in [`verify`].
+///
+/// # Parameters
+///
+/// - `config`: The full STIR configuration, whose degree bound is the common
+///   bound of the batched polynomials.
+/// - `degree_bounds`: The declared degree bounds `d_j` of the batched
+///   polynomials, in the order in which they were passed to the prover.
+/// - `proof`: The proof to verify, which contains the commitment to the
+///   batched polynomials (cf. [`MixedDegreeProof::inputs_commitment`]).
+/// - `challenger`: The challenger to use for the proof verification, as in
+///   [`verify`].
+///
+/// # Errors
+///
+/// Returns [`VerificationError::InvalidDegreeBound`] if some bound exceeds
+/// that of the configuration (or none is given),
+/// [`VerificationError::CombinationQueryPath`] if the consistency openings
+/// are not authenticated, [`VerificationError::CombinationMismatch`] if they
+/// are inconsistent and the errors of [`verify`] if the proof of the
+/// low-degreeness of the combination is invalid.
+pub fn verify_mixed_degrees<F, EF, M, C>(
+    config: &StirConfig<M>,
+    degree_bounds: &[usize],
+    proof: &MixedDegreeProof<EF, M, C::Witness>,
+    challenger: &mut C,
+) -> Result<(), VerificationError>
+where
+    F: Field,
+    EF: TwoAdicField + ExtensionField<F>,
+    M: Mmcs<EF>,
+    C: FieldChallenger<F> + GrindingChallenger + CanObserve<M::Commitment>,
+{
+    let degree_bound = 1 << config.log_starting_degree();
+
+    if degree_bounds.is_empty() || degree_bounds.iter().any(|&bound| bound > degree_bound) {
+        return Err(VerificationError::InvalidDegreeBound);
+    }
+
+    let MixedDegreeProof {
+        inputs_commitment,
+        combination_commitment,
+        consistency_queries,
+        stir_proof,
+    } = proof;
+
+    let alpha: EF =
+        sample_combination_randomness::<F, EF, _, _>(challenger, degree_bounds, inputs_commitment);
+
+    let log_folding_factor = config.log_starting_folding_factor();
+    let folding_factor = 1 << log_folding_factor;
+
+    let mut domain = initial_domain::<EF, M>(config);
+    let log_num_groups = domain.log_size() - log_folding_factor;
+
+    let queried_indices = sample_combination_queries::<F, _, _>(
+        challenger,
+        combination_commitment,
+        log_num_groups,
+        num_combination_queries(config),
+    );
+
+    if consistency_queries.len() != queried_indices.len() {
+        return Err(VerificationError::CombinationQueryPath);
+    }
+
+    // Authenticating the opened rows of the matrix of evaluations of the f_j
+    // (cf. MixedDegreeProof::inputs_commitment) and the opened fold groups of g
+    let inputs_dimensions = Dimensions {
+        width: degree_bounds.len() * folding_factor,
+        height: 1 << log_num_groups,
+    };
+
+    let inputs_valid = queried_indices.iter().zip(consistency_queries).all(
+        |(&i, ((inputs_row, inputs_proof), _))| {
+            inputs_row.len() == inputs_dimensions.width
+                && config
+                    .mmcs_config()
+                    .verify_batch(
+                        inputs_commitment,
+                        &[inputs_dimensions],
+                        i,
+                        &[inputs_row.clone()],
+                        inputs_proof,
+                    )
+                    .is_ok()
+        },
+    );
+
+    let combination_openings = consistency_queries
         .iter()
-        .any(|&x| x > POW_BITS_WARNING)
+        .map(|(_, combination_opening)| combination_opening.clone())
+        .collect_vec();
+
+    if !inputs_valid
+        || !verify_query_paths(
+            config.mmcs_config(),
+            combination_commitment,
+            StackedLayout::new(
+                domain.log_size(),
+                log_folding_factor,
+                config.log_rows_per_fold_group(log_folding_factor),
+            ),
+            &queried_indices,
+            &combination_openings,
+        )
     {
-        tracing::warn!(
-            "The configuration requires the prover to compute a proof of work of more than {} bits",
-            POW_BITS_WARNING
-        );
+        return Err(VerificationError::CombinationQueryPath);
     }
 
-    // Observe the public parameters
-    observe_public_parameters(config.parameters(), challenger);
+    // Checking that g is the combination of the f_j at each opened point: the
+    // t-th element of the i-th fold group is the (i + t * num_groups)-th
+    // point of L_0
+    for (&i, ((inputs_row, _), (combination_evals, _))) in
+        queried_indices.iter().zip(consistency_queries)
+    {
+        for (t, &combination_eval) in combination_evals.iter().enumerate() {
+            let point = domain.element(i + (t << log_num_groups));
+
+            let expected = combine_mixed_degree_evaluations(
+                point,
+                alpha,
+                degree_bound,
+                degree_bounds,
+                inputs_row.iter().skip(t).step_by(folding_factor).copied(),
+            );
+
+            if expected != combination_eval {
+                return Err(VerificationError::CombinationMismatch);
+            }
+        }
+    }
+
+    verify(
+        config,
+        combination_commitment.clone(),
+        stir_proof,
+        challenger,
+    )
+}
+
+/// Performs all verification steps except for the (comparatively expensive)
+/// verification of the Merkle proofs of the queried evaluations, which are
+/// returned as [`DeferredQueries`] to be checked later with
+/// [`DeferredQueries::verify`]. This allows, for instance, pipelining the two
+/// stages.
+///
+/// Until the deferred queries are verified, the opened evaluations are not
+/// authenticated. Therefore, although an honest proof passes both stages and
+/// an invalid one fails at least one of them, the specific error returned for
+/// an invalid proof may differ from the one returned by [`verify`].
+///
+/// # Parameters
+///
+/// Same as [`verify`].
+pub fn verify_cheap_checks<F, EF, M, C>(
+    config: &StirConfig<M>,
+    commitment: M::Commitment,
+    proof: &StirProof<EF, M, C::Witness>,
+    challenger: &mut C,
+) -> Result<DeferredQueries<EF, M>, VerificationError>
+where
+    F: Field,
+    EF: TwoAdicField + ExtensionField<F>,
+    M: Mmcs<EF>,
+    C: FieldChallenger<F> + GrindingChallenger + CanObserve<M::Commitment>,
+{
+    let mut deferred = DeferredQueries { rounds: Vec::new() };
+    verify_with_deferral(
+        config,
+        commitment,
+        proof,
+        challenger,
+        &TranscriptFoldChallenge,
+        None,
+        &mut VerificationOutputs::new(Some(&mut deferred), FailureSink::default()),
+    )?;
+    Ok(deferred)
+}
+
+/// Checks only the proofs of work contained in `proof`, which is much cheaper
+/// than full verification: the transcript is replayed in order to derive the
+/// challenges each proof of work depends on, but no Merkle paths are verified
+/// and no queried evaluations are processed. This is intended for cheaply
+/// pre-screening proofs (e. g. to filter out spam before investing in full
+/// verification). Passing this check does not imply that the proof is valid,
+/// whereas failing it implies that [`verify`] rejects it.
+///
+/// # Parameters
+///
+/// Same as [`verify`]. The commitment is needed since it is part of the
+/// transcript.
+///
+/// # Errors
+///
+/// Returns [`VerificationError::Round`] with
+/// [`FullRoundVerificationError::ProofOfWork`] for the first full round with an
+/// invalid proof-of-work witness, or [`VerificationError::FinalProofOfWork`]
+/// if only the final one is invalid. Returns
+/// [`VerificationError::RoundCountMismatch`] before any proof of work is
+/// checked if the proof does not contain one round proof per full round of
+/// the configuration.
+pub fn verify_pow_only<F, EF, M, C>(
+    config: &StirConfig<M>,
+    commitment: M::Commitment,
+    proof: &StirProof<EF, M, C::Witness>,
+    challenger: &mut C,
+) -> Result<(), VerificationError>
+where
+    F: Field,
+    EF: TwoAdicField + ExtensionField<F>,
+    M: Mmcs<EF>,
+    C: FieldChallenger<F> + GrindingChallenger + CanObserve<M::Commitment>,
+{
+    check_num_rounds(config, proof.round_proofs.len())?;
+
+    let VerificationState {
+        mut domain,
+        mut folding_randomness,
+        ..
+    } = initial_verification_state(config, commitment, challenger, &TranscriptFoldChallenge);
+
+    for (i, round_proof) in proof.round_proofs.iter().enumerate() {
+        let round = i + 1;
+
+        folding_randomness = sample_round_challenges(
+            config,
+            round,
+            &domain,
+            folding_randomness,
+            round_proof,
+            challenger,
+            &TranscriptFoldChallenge,
+        )
+        .folding_randomness;
+
+        if !challenger.check_witness(config.round_config(round).pow_bits, round_proof.pow_witness) {
+            return Err(VerificationError::Round(
+                round,
+                FullRoundVerificationError::ProofOfWork,
+            ));
+        }
+
+        observe_ans_polynomial(round_proof, challenger);
+
+        #[cfg(not(feature = "no-shake"))]
+        sample_shake_randomness(round_proof, challenger);
+
+        domain = domain.shrink_subgroup(1);
+    }
+
+    sample_final_queried_indices(config, &domain, &proof.final_polynomial, challenger);
+
+    if !challenger.check_witness(config.final_pow_bits(), proof.pow_witness) {
+        return Err(VerificationError::FinalProofOfWork);
+    }
+
+    Ok(())
+}
+
+/// Verifies the proof against each of several candidate commitments in turn
+/// and returns the index of the first one it is valid for, e. g. when the
+/// committed root is known to be one of a few published candidates. The
+/// parameter fingerprint of the proof is checked once for all candidates.
+/// Beyond that, little can be shared between candidates: the commitment is
+/// observed at the start of the transcript, so every challenge (and hence
+/// every other check) depends on it. The cost is therefore that of up to one
+/// verification per candidate.
+///
+/// # Parameters
+///
+/// - `config`, `proof`: Same as in [`verify`].
+/// - `commitments`: The candidate commitments, tried in order.
+/// - `challenger`: The challenger to use for the proof verification, as in
+///   [`verify`]. Each candidate is tried with a copy of it. If one is
+///   accepted, the challenger is left in the state reached by its
+///   verification. Otherwise, it is left untouched.
+///
+/// # Errors
+///
+/// Returns [`VerificationError::ParameterMismatch`] if the proof was produced
+/// under a different configuration, and
+/// [`VerificationError::NoMatchingCommitment`] if it is not valid for any of
+/// the candidates (in particular, if there are none).
+pub fn verify_against_any<F, EF, M, C>(
+    config: &StirConfig<M>,
+    commitments: &[M::Commitment],
+    proof: &StirProof<EF, M, C::Witness>,
+    challenger: &mut C,
+) -> Result<usize, VerificationError>
+where
+    F: Field,
+    EF: TwoAdicField + ExtensionField<F>,
+    M: Mmcs<EF>,
+    C: FieldChallenger<F> + GrindingChallenger + CanObserve<M::Commitment> + Clone,
+{
+    check_parameter_fingerprint(config, proof.parameter_fingerprint)?;
+
+    for (i, commitment) in commitments.iter().enumerate() {
+        let mut candidate_challenger = challenger.clone();
+
+        if verify(config, commitment.clone(), proof, &mut candidate_challenger).is_ok() {
+            *challenger = candidate_challenger;
+            return Ok(i);
+        }
+    }
+
+    Err(VerificationError::NoMatchingCommitment)
+}
+
+/// Verifies the proof like [`verify`] after checking that its size is within
+/// `limits`, so that oversized (e. g. adversarially crafted) proofs are
+/// rejected before any Merkle proof is verified or any transcript is replayed.
+/// [`VerificationLimits::from_config`] provides limits which never cause
+/// honestly generated proofs to be rejected.
+///
+/// # Parameters
+///
+/// - `config`, `commitment`, `proof`, `challenger`: Same as in [`verify`].
+/// - `limits`: Caps on the number of field elements and Merkle-proof nodes
+///   contained in `proof`.
+///
+/// # Errors
+///
+/// Returns [`VerificationError::ProofTooLarge`] if the proof exceeds either
+/// limit, in which case the challenger is left untouched, and the same errors
+/// as [`verify`] otherwise.
+pub fn verify_with_limits<F, EF, M, C>(
+    config: &StirConfig<M>,
+    commitment: M::Commitment,
+    proof: &StirProof<EF, M, C::Witness>,
+    limits: VerificationLimits,
+    challenger: &mut C,
+) -> Result<(), VerificationError>
+where
+    F: Field,
+    EF: TwoAdicField + ExtensionField<F>,
+    M: Mmcs<EF>,
+    C: FieldChallenger<F> + GrindingChallenger + CanObserve<M::Commitment>,
+{
+    let (field_elements, merkle_nodes) = proof_size(proof, &commitment);
+
+    if field_elements > limits.max_field_elements || merkle_nodes > limits.max_merkle_nodes {
+        return Err(VerificationError::ProofTooLarge {
+            field_elements,
+            merkle_nodes,
+        });
+    }
+
+    verify(config, commitment, proof, challenger)
+}
+
+// Count the field elements and Merkle-proof nodes contained in the proof (cf.
+// VerificationLimits)
+fn proof_size<F: Field, M: Mmcs<F>, Witness>(
+    // The proof whose size is computed
+    proof: &StirProof<F, M, Witness>,
+    // The commitment the proof refers to, whose size is that of one node
+    commitment: &M::Commitment,
+) -> (usize, usize) {
+    let node_size = num_serialized_values(commitment).max(1);
+
+    let openings_size = |query_proofs: &[(Vec<F>, Vec<M::Proof>)]| {
+        query_proofs
+            .iter()
+            .fold((0_usize, 0_usize), |(evals, nodes), (leaf, leaf_proofs)| {
+                let leaf_nodes = leaf_proofs
+                    .iter()
+                    .map(|leaf_proof| num_serialized_values(leaf_proof).div_ceil(node_size))
+                    .fold(0_usize, usize::saturating_add);
+
+                (
+                    evals.saturating_add(leaf.len()),
+                    nodes.saturating_add(leaf_nodes),
+                )
+            })
+    };
+
+    let (mut field_elements, mut merkle_nodes) = openings_size(&proof.final_round_queries);
+    field_elements = field_elements.saturating_add(proof.final_polynomial.coeffs().len());
+
+    for round_proof in &proof.round_proofs {
+        let (evals, nodes) = openings_size(&round_proof.query_proofs);
+
+        field_elements = field_elements
+            .saturating_add(round_proof.betas.len())
+            .saturating_add(round_proof.ans_polynomial.coeffs().len())
+            .saturating_add(evals);
+        merkle_nodes = merkle_nodes.saturating_add(nodes);
+
+        #[cfg(not(feature = "no-shake"))]
+        {
+            field_elements =
+                field_elements.saturating_add(round_proof.shake_polynomial.coeffs().len());
+        }
+    }
+
+    (field_elements, merkle_nodes)
+}
+
+// Verifies the proof, either checking the Merkle proofs of the queried
+// evaluations directly (if deferred is None) or storing them in deferred. If
+// verification fails because of a query whose folded value does not match the
+// one claimed by the prover, that query is recorded in mismatch. If domains is
+// provided, it must be the (already validated) schedule L_0, ..., L_M, whose
+// elements are used in place of the ones derived from the configuration.
+// Failed checks are handled as determined by outputs.failures (cf.
+// FailureSink).
+fn verify_with_deferral<F, EF, M, C, S>(
+    config: &StirConfig<M>,
+    commitment: M::Commitment,
+    proof: &StirProof<EF, M, C::Witness>,
+    challenger: &mut C,
+    source: &S,
+    domains: Option<&[TwoAdicCoset<EF>]>,
+    outputs: &mut VerificationOutputs<'_, EF, M>,
+) -> Result<(), VerificationError>
+where
+    F: Field,
+    EF: TwoAdicField + ExtensionField<F>,
+    M: Mmcs<EF>,
+    C: FieldChallenger<F> + GrindingChallenger + CanObserve<M::Commitment>,
+    S: FoldChallengeSource<F>,
+{
+    warn_on_large_pow_bits(config);
 
     let StirProof {
+        format_version: _,
+        parameter_fingerprint,
         round_proofs,
         final_polynomial,
         pow_witness,
         final_round_queries,
     } = proof;
 
-    // Degree check on p = g_{M + 1}
-    if final_polynomial
-        .degree()
-        .is_some_and(|d| d + 1 > 1 << config.log_stopping_degree())
+    if let Err(error) = check_parameter_fingerprint(config, *parameter_fingerprint) {
+        outputs.failures.report(error, |error| error)?;
+    }
+
+    check_num_rounds(config, round_proofs.len())?;
+
+    let mut verification_state = initial_verification_state(config, commitment, challenger, source);
+
+    // The supplied domains have been checked to coincide with the derived
+    // ones, but carry the powers of their generators memoised by earlier uses
+    let use_domain = |verification_state: &mut VerificationState<EF, M>, i: usize| {
+        if let Some(domain) = domains.and_then(|domains| domains.get(i)) {
+            verification_state.domain = domain.clone();
+        }
+    };
+
+    use_domain(&mut verification_state, 0);
+
+    // ====================== Verification of full rounds ======================
+    for (i, round_proof) in round_proofs.iter().enumerate() {
+        verification_state = verify_round(
+            config,
+            verification_state,
+            round_proof,
+            challenger,
+            source,
+            outputs,
+        )
+        .map_err(|e| VerificationError::Round(i + 1, e))?;
+
+        use_domain(&mut verification_state, i + 1);
+    }
+
+    // ==================== Verification of the final round ====================
+    verify_final_round(
+        config,
+        verification_state,
+        (final_polynomial, *pow_witness),
+        final_round_queries,
+        challenger,
+        outputs,
+    )
+}
+
+/// Verifies a proof read incrementally from `reader`, where it is expected to
+/// be encoded with [`StirProof::encode_v1`]. Each component is checked as soon
+/// as it has been decoded and is dropped afterwards, so that at most one
+/// round's worth of proof data is held in memory at any given time. The
+/// outcome is the same as that of calling [`verify`] on the decoded proof,
+/// except that a proof which cannot be decoded is rejected with
+/// [`VerificationError::Decoding`].
+///
+/// The reader is read in many small chunks, so wrapping unbuffered sources
+/// such as files in a [`std::io::BufReader`] is recommended.
+///
+/// # Parameters
+///
+/// - `config`: The full STIR configuration, including the degree bound.
+/// - `commitment`: The commitment to the codeword encoding the polynomial of interest.
+/// - `reader`: The source of the encoded proof. It is consumed up to the end
+///   of the proof, and any data following it causes the proof to be rejected.
+/// - `challenger`: The challenger to use for the proof verification, as in
+///   [`verify`].
+#[cfg(feature = "std")]
+pub fn verify_from_reader<F, EF, M, C, R>(
+    config: &StirConfig<M>,
+    commitment: M::Commitment,
+    reader: R,
+    challenger: &mut C,
+) -> Result<(), VerificationError>
+where
+    F: Field,
+    EF: TwoAdicField + ExtensionField<F>,
+    M: Mmcs<EF>,
+    C: FieldChallenger<F> + GrindingChallenger + CanObserve<M::Commitment>,
+    C::Witness: serde::de::DeserializeOwned,
+    R: std::io::Read,
+{
+    warn_on_large_pow_bits(config);
+
+    let mut decoder = V1Decoder::new(reader);
+    let decoding_error = |_| VerificationError::Decoding;
+
+    decoder
+        .read_next::<FormatVersion>()
+        .map_err(decoding_error)?;
+    check_parameter_fingerprint(config, decoder.read_next().map_err(decoding_error)?)?;
+    let num_round_proofs: usize = decoder.read_next().map_err(decoding_error)?;
+    check_num_rounds(config, num_round_proofs)?;
+
+    let mut verification_state =
+        initial_verification_state(config, commitment, challenger, &TranscriptFoldChallenge);
+
+    // ====================== Verification of full rounds ======================
+    for i in 0..num_round_proofs {
+        let round_proof: RoundProof<EF, M, C::Witness> =
+            decoder.read_next().map_err(decoding_error)?;
+
+        verification_state = verify_round(
+            config,
+            verification_state,
+            &round_proof,
+            challenger,
+            &TranscriptFoldChallenge,
+            &mut VerificationOutputs::new(None, FailureSink::default()),
+        )
+        .map_err(|e| VerificationError::Round(i + 1, e))?;
+    }
+
+    // ==================== Verification of the final round ====================
+    let final_polynomial: Polynomial<EF> = decoder.read_next().map_err(decoding_error)?;
+    let pow_witness = decoder.read_next().map_err(decoding_error)?;
+    let final_round_queries: Vec<(Vec<EF>, Vec<M::Proof>)> =
+        decoder.read_next().map_err(decoding_error)?;
+    decoder.end().map_err(decoding_error)?;
+
+    verify_final_round(
+        config,
+        verification_state,
+        (&final_polynomial, pow_witness),
+        &final_round_queries,
+        challenger,
+        &mut VerificationOutputs::new(None, FailureSink::default()),
+    )
+}
+
+// Checks that the parameter fingerprint of a proof matches that of the
+// configuration of the verifier
+fn check_parameter_fingerprint<M: Clone>(
+    config: &StirConfig<M>,
+    got: u64,
+) -> Result<(), VerificationError> {
+    let expected = config.parameter_fingerprint();
+
+    if got != expected {
+        return Err(VerificationError::ParameterMismatch { expected, got });
+    }
+
+    Ok(())
+}
+
+// Checks that a proof contains one round proof per full round of the
+// configuration, as in StirProof::validate_shape. The verification of each
+// round looks up its parameters in the configuration, so this must be checked
+// before any round is verified
+fn check_num_rounds<M: Clone>(config: &StirConfig<M>, got: usize) -> Result<(), VerificationError> {
+    let expected = config.num_full_rounds();
+
+    if got != expected {
+        return Err(VerificationError::RoundCountMismatch { expected, got });
+    }
+
+    Ok(())
+}
+
+// Checks that the given domains are L_0, ..., L_M as derived from the
+// configuration: L_0 has the root generator w as both its shift and its
+// generator and each subsequent domain has half the size, the same shift and
+// the square of the generator of the previous one
+fn check_domains<F: TwoAdicField, M: Clone>(
+    config: &StirConfig<M>,
+    domains: &[TwoAdicCoset<F>],
+) -> Result<(), VerificationError> {
+    if domains.len() != config.total_rounds() {
+        return Err(VerificationError::DomainMismatch);
+    }
+
+    let root_generator = config.root_generator::<F>();
+    let mut generator = root_generator;
+
+    for (i, domain) in domains.iter().enumerate() {
+        if domain.log_size() + i != config.starting_domain_log_size()
+            || domain.shift() != root_generator
+            || domain.generator() != generator
+        {
+            return Err(VerificationError::DomainMismatch);
+        }
+
+        generator = generator.square();
+    }
+
+    Ok(())
+}
+
+// Informs the verifier if the configuration requires a proof of work from the
+// prover larger than the POW_BITS_WARNING constant. This is only logged if the
+// tracing module has been init()ialised.
+fn warn_on_large_pow_bits<M: Clone>(config: &StirConfig<M>) {
+    if config
+        .pow_bits_all_rounds()
+        .iter()
+        .any(|&x| x > POW_BITS_WARNING)
     {
-        return Err(VerificationError::FinalPolynomialDegree);
+        tracing::warn!(
+            "The configuration requires the prover to compute a proof of work of more than {} bits",
+            POW_BITS_WARNING
+        );
     }
+}
+
+// Observes the public parameters and the commitment to the initial codeword
+// and prepares the verification state for the first round
+fn initial_verification_state<F, EF, M, C, S>(
+    // The full STIR configuration
+    config: &StirConfig<M>,
+    // The commitment to the initial codeword g_0
+    commitment: M::Commitment,
+    // Challenger for the transcript
+    challenger: &mut C,
+    // Derivation of the folding randomness from the transcript
+    source: &S,
+) -> VerificationState<EF, M>
+where
+    F: Field,
+    EF: TwoAdicField + ExtensionField<F>,
+    M: Mmcs<EF>,
+    C: FieldChallenger<F> + GrindingChallenger + CanObserve<M::Commitment>,
+    S: FoldChallengeSource<F>,
+{
+    // Observe the public parameters
+    observe_public_parameters(config.parameters(), challenger);
 
     // Observe the commitment
     challenger.observe(F::from_u8(Messages::Commitment as u8));
     challenger.observe(commitment.clone());
 
     // Sample the folding randomness r_0
-    challenger.observe(F::from_u8(Messages::FoldingRandomness as u8));
-    let folding_randomness = challenger.sample_algebra_element();
-
-    let log_size = config.log_starting_degree() + config.log_starting_inv_rate();
+    let folding_randomness = sample_folding_randomness(challenger, source, 0);
 
-    // Cf. the commit method in prover/mod.rs for an explanation on the chosen
+    // Cf. initial_domain in prover/mod.rs for an explanation on the chosen
     // domain sequence L_0, L_1, ...
-    let domain = TwoAdicCoset::new(EF::two_adic_generator(log_size), log_size);
+    let domain = initial_domain(config);
 
     // Preparing the initial verification state manually
-    let mut verification_state = VerificationState {
+    VerificationState {
         oracle: Oracle::Transparent,
         domain,
         folding_randomness,
         round: 0,
         root: commitment,
-    };
+    }
+}
 
-    // ====================== Verification of full rounds ======================
-    for (i, round_proof) in round_proofs.into_iter().enumerate() {
-        verification_state = verify_round(config, verification_state, round_proof, challenger)
-            .map_err(|e| VerificationError::Round(i + 1, e))?;
+// Verifies the final round M + 1 of STIR given the verification state produced
+// by the last full round
+fn verify_final_round<F, EF, M, C>(
+    // The full STIR configuration
+    config: &StirConfig<M>,
+    // The verification state produced by the last full round (or the initial
+    // one if there are no full rounds)
+    verification_state: VerificationState<EF, M>,
+    // The final polynomial p = g_{M + 1} and the final proof of work
+    (final_polynomial, pow_witness): (&Polynomial<EF>, C::Witness),
+    // The final-round openings of the committed evaluations of g_M
+    final_round_queries: &[(Vec<EF>, Vec<M::Proof>)],
+    // Challenger for the transcript
+    challenger: &mut C,
+    // Destinations of the deferred Merkle proofs, the first mismatching query
+    // and the failed checks
+    outputs: &mut VerificationOutputs<'_, EF, M>,
+) -> Result<(), VerificationError>
+where
+    F: Field,
+    EF: TwoAdicField + ExtensionField<F>,
+    M: Mmcs<EF>,
+    C: FieldChallenger<F> + GrindingChallenger + CanObserve<M::Commitment>,
+{
+    // Degree check on p = g_{M + 1}
+    if final_polynomial
+        .degree_checked()
+        .is_some_and(|d| d >= config.final_degree_bound())
+    {
+        outputs
+            .failures
+            .report(VerificationError::FinalPolynomialDegree, |error| error)?;
     }
 
     let VerificationState {
@@ -234,60 +1583,60 @@ where
         ..
     } = verification_state;
 
-    // ==================== Verification of the final round ====================
-
     // log2(k_M)
     let log_last_folding_factor = config.log_last_folding_factor();
 
     // Logarithm of |(L_M)^{k_M}|
     let log_final_query_domain_size = final_domain.log_size() - log_last_folding_factor;
 
-    // Observe the final polynomial
-    challenger.observe(F::from_u8(Messages::FinalPolynomial as u8));
-    observe_ext_slice_with_size(challenger, final_polynomial.coeffs());
-
-    // Sample the final queried indices
-    challenger.observe(F::from_u8(Messages::FinalQueryIndices as u8));
-    let final_queried_indices: Vec<usize> = (0..config.final_num_queries())
-        .map(|_| challenger.sample_bits(log_final_query_domain_size))
-        .unique()
-        .collect();
-
-    // Verifying paths of the evaluations of g_M at the k_M-th roots of the
-    // final queried points
-    for (&i, (leaf, proof)) in final_queried_indices
-        .iter()
-        .unique()
-        .zip(final_round_queries.iter())
-    {
-        if config
-            .mmcs_config()
-            .verify_batch(
-                &g_m_root,
-                &[Dimensions {
-                    width: 1 << log_last_folding_factor,
-                    height: 1 << (final_domain.log_size() - log_last_folding_factor),
-                }],
-                i,
-                &[leaf.clone()],
-                proof,
-            )
-            .is_err()
-        {
-            return Err(VerificationError::FinalQueryPath);
-        }
+    // Observe the final polynomial and sample the final queried indices
+    let final_queried_indices =
+        sample_final_queried_indices(config, &final_domain, final_polynomial, challenger);
+
+    // The proof must open exactly one fold group per (de-duplicated) queried
+    // index (cf. verify_round)
+    if final_round_queries.len() != final_queried_indices.len() {
+        return Err(VerificationError::QueryCountMismatch {
+            expected: final_queried_indices.len(),
+            got: final_round_queries.len(),
+        });
     }
 
-    // Recover the evaluations of g_M needed to compute the values of f_M the
-    // k_M-th roots of the final queried points
-    let g_m_evals = final_round_queries
-        .into_iter()
-        .map(|(eval_batch, _)| eval_batch)
-        .collect_vec();
+    // Each opening must contain exactly the k_M evaluations of a fold group
+    // before it is used
+    check_leaf_lengths(1 << log_last_folding_factor, final_round_queries)
+        .map_err(|(expected, got)| VerificationError::MalformedLeaf { expected, got })?;
+
+    // Verifying (or deferring) paths of the evaluations of g_M at the k_M-th
+    // roots of the final queried points and recovering said evaluations, which
+    // are needed to compute the values of f_M at the same points
+    let g_m_evals = match verify_or_defer_query_paths(
+        config.mmcs_config(),
+        None,
+        &g_m_root,
+        StackedLayout::new(
+            final_domain.log_size(),
+            log_last_folding_factor,
+            config.log_rows_per_fold_group(log_last_folding_factor),
+        ),
+        &final_queried_indices,
+        final_round_queries,
+        outputs.deferred.as_deref_mut(),
+    ) {
+        Some(g_m_evals) => g_m_evals,
+        None => {
+            // If failures are collected, the remaining checks proceed with
+            // the unauthenticated evaluations
+            outputs
+                .failures
+                .report(VerificationError::FinalQueryPath, |error| error)?;
+            opened_evaluations(final_round_queries)
+        }
+    };
 
     // Compute the values of f_M at the relevant points given the evaluations of
     // g_M
-    let f_m_evals = compute_f_oracle_from_g(
+    let f_m_evals = compute_f_oracle_from_g::<F, EF>(
         &final_oracle,
         g_m_evals,
         &final_queried_indices,
@@ -315,26 +1664,33 @@ where
 
     // Match the evaluations of the final polynomial p = g_{M + 1} sent by the
     // prover against the expected ones computed above
-    if !p_evals
+    let folded_answers = final_queried_point_roots
         .into_iter()
-        .zip(final_queried_point_roots)
-        .all(|(eval, root)| {
-            final_polynomial.evaluate(&root.exp_power_of_2(log_last_folding_factor)) == eval
-        })
+        .zip(p_evals)
+        .map(|(root, eval)| (root.exp_power_of_2(log_last_folding_factor), eval))
+        .collect_vec();
+
+    if let Some(query_mismatch) =
+        find_query_mismatch::<F, EF>(final_polynomial, &final_queried_indices, &folded_answers)
     {
-        return Err(VerificationError::FinalPolynomialEvaluations);
+        outputs.mismatch = Some(query_mismatch);
+        outputs
+            .failures
+            .report(VerificationError::FinalPolynomialEvaluations, |error| error)?;
     }
 
     // Check the final proof of work
     if !challenger.check_witness(config.final_pow_bits(), pow_witness) {
-        return Err(VerificationError::FinalProofOfWork);
+        outputs
+            .failures
+            .report(VerificationError::FinalProofOfWork, |error| error)?;
     }
 
     Ok(())
 }
 
 // Verifies the proof of a single full round i = 1, ..., M of STIR
-fn verify_round<F, EF, M, C>(
+fn verify_round<F, EF, M, C, S>(
     // The full STIR configuration from which the round-specific configuration
     // is extracted
     config: &StirConfig<M>,
@@ -342,25 +1698,30 @@ fn verify_round<F, EF, M, C>(
     // initial one computed manually)
     verification_state: VerificationState<EF, M>,
     // The proof for the current round
-    round_proof: RoundProof<EF, M, C::Witness>,
+    round_proof: &RoundProof<EF, M, C::Witness>,
     // Challenger for the transcript
     challenger: &mut C,
+    // Derivation of the folding randomness from the transcript
+    source: &S,
+    // Destinations of the deferred Merkle proofs, the first query whose folded
+    // value is not interpolated by Ans_i and the failed checks
+    outputs: &mut VerificationOutputs<'_, EF, M>,
 ) -> Result<VerificationState<EF, M>, FullRoundVerificationError>
 where
     F: Field,
     EF: TwoAdicField + ExtensionField<F>,
     M: Mmcs<EF>,
     C: FieldChallenger<F> + GrindingChallenger + CanObserve<M::Commitment>,
+    S: FoldChallengeSource<F>,
 {
     let round = verification_state.round + 1;
+    let in_round = |error| VerificationError::Round(round, error);
 
     // De-structure the round-specific configuration, verification state and
     // round proof
     let RoundConfig {
         log_folding_factor,
         pow_bits,
-        num_queries,
-        num_ood_samples,
         log_evaluation_domain_size,
         log_inv_rate,
         ..
@@ -379,94 +1740,143 @@ where
         betas,
         ans_polynomial,
         query_proofs,
+        #[cfg(not(feature = "no-shake"))]
         shake_polynomial,
         pow_witness,
     } = round_proof;
 
-    // Observe the commitment to g_{i - 1}
-    challenger.observe(F::from_u8(Messages::RoundCommitment as u8));
-    challenger.observe(g_root.clone());
-
-    // Sampling the out-of-domain points
-    let mut ood_samples = Vec::new();
-
-    challenger.observe(F::from_u8(Messages::OodSamples as u8));
-    while ood_samples.len() < num_ood_samples {
-        let el: EF = challenger.sample_algebra_element();
-
-        // Rejection sampling: these points have to be outside L_{i - 1}
-        if !domain.contains(el) {
-            ood_samples.push(el);
-        }
+    // The proof must contain exactly one reply per out-of-domain sample. This
+    // only depends on the configuration, so it is checked before the betas are
+    // observed.
+    let num_ood_samples = config.num_ood_samples(round);
+    if betas.len() != num_ood_samples {
+        outputs.failures.report(
+            FullRoundVerificationError::BetaCountMismatch {
+                expected: num_ood_samples,
+                got: betas.len(),
+            },
+            in_round,
+        )?;
     }
 
-    // Observe the betas, i. e. the replies to the out-of-domain queries
-    challenger.observe(F::from_u8(Messages::Betas as u8));
-    betas
-        .iter()
-        .for_each(|&beta| challenger.observe_algebra_element(beta));
+    // Replay the transcript up to the proof of work
+    let RoundChallenges {
+        ood_samples,
+        comb_randomness,
+        folding_randomness: new_folding_randomness,
+        queried_indices,
+    } = sample_round_challenges(
+        config,
+        round,
+        &domain,
+        folding_randomness,
+        round_proof,
+        challenger,
+        source,
+    );
 
-    // Sample the degree-correction randomness
-    challenger.observe(F::from_u8(Messages::CombRandomness as u8));
-    let comb_randomness = challenger.sample_algebra_element();
+    // The out-of-domain points are resampled on collision, so they can only be
+    // repeated if the challenger is degenerate. Ans_i could not interpolate
+    // the betas at repeated points, hence the proof is rejected.
+    if !ood_samples.iter().all_unique() {
+        outputs
+            .failures
+            .report(FullRoundVerificationError::DuplicateOodPoint, in_round)?;
+    }
 
-    // Sample the folding randomness for the next round
-    challenger.observe(F::from_u8(Messages::FoldingRandomness as u8));
-    let new_folding_randomness = challenger.sample_algebra_element();
+    // The proof must open exactly one fold group per (de-duplicated) queried
+    // index. A mismatch typically stems from the prover using a configuration
+    // with a different number of queries, and is reported before the proof of
+    // work (which would fail as well in that case) to make this apparent.
+    if query_proofs.len() != queried_indices.len() {
+        return Err(FullRoundVerificationError::QueryCountMismatch {
+            expected: queried_indices.len(),
+            got: query_proofs.len(),
+        });
+    }
 
-    // Sample queried indices of elements in L_{i - 1}^k_{i-1}
-    let log_query_domain_size = domain.log_size() - log_folding_factor;
+    // Ans_i interpolates the betas at the out-of-domain points and the folded
+    // values at the (de-duplicated) in-domain queried points, so its degree
+    // must be lower than the number of these points. Since the latter is known
+    // as soon as the challenges are sampled, this is checked before anything
+    // else involves Ans_i.
+    if ans_polynomial
+        .degree_checked()
+        .is_some_and(|d| d >= ood_samples.len() + queried_indices.len())
+    {
+        outputs
+            .failures
+            .report(FullRoundVerificationError::AnsPolynomialDegree, in_round)?;
+    }
 
-    challenger.observe(F::from_u8(Messages::QueryIndices as u8));
-    let queried_indices: Vec<usize> = (0..num_queries)
-        .map(|_| challenger.sample_bits(log_query_domain_size))
-        .unique()
-        .collect();
+    // Check that the replies to the out-of-domain queries are interpolated by
+    // Ans_i. This is also implied by the evaluation check below, but
+    // performing it now (it only depends on the out-of-domain points, which
+    // are sampled before the betas are observed) pinpoints inconsistent
+    // replies before any in-domain query is processed. The betas are in turn
+    // bound to g_i in the next round through the virtual function f_i.
+    if ood_samples
+        .iter()
+        .zip(betas.iter())
+        .any(|(x, &beta)| ans_polynomial.evaluate(x) != beta)
+    {
+        outputs
+            .failures
+            .report(FullRoundVerificationError::OodReplyInconsistent, in_round)?;
+    }
 
     // Check the proof of work for this round
-    if !challenger.check_witness(pow_bits, pow_witness) {
-        return Err(FullRoundVerificationError::ProofOfWork);
+    if !challenger.check_witness(pow_bits, *pow_witness) {
+        outputs
+            .failures
+            .report(FullRoundVerificationError::ProofOfWork, in_round)?;
     }
 
-    // Observe the Ans and shake polynomials
-    challenger.observe(F::from_u8(Messages::AnsPolynomial as u8));
-    observe_ext_slice_with_size(challenger, ans_polynomial.coeffs());
+    // Observe the Ans and shake polynomials and sample the shake randomness
+    observe_ans_polynomial(round_proof, challenger);
 
-    challenger.observe(F::from_u8(Messages::ShakePolynomial as u8));
-    observe_ext_slice_with_size(challenger, shake_polynomial.coeffs());
+    #[cfg(not(feature = "no-shake"))]
+    let shake_randomness = sample_shake_randomness(round_proof, challenger);
 
-    // Sample the shake randomness
-    challenger.observe(F::from_u8(Messages::ShakeRandomness as u8));
-    let shake_randomness: EF = challenger.sample_algebra_element();
+    // Logarithm of |L_{i - 1}^k_{i - 1}|
+    let log_query_domain_size = domain.log_size() - log_folding_factor;
 
-    // Verify the Merkle proofs of the evaluations of g_{i - 1}
-    for (&i, (leaf, proof)) in queried_indices.iter().unique().zip(query_proofs.iter()) {
-        if config
-            .mmcs_config()
-            .verify_batch(
-                &prev_root,
-                &[Dimensions {
-                    width: 1 << log_folding_factor,
-                    height: 1 << (domain.log_size() - log_folding_factor),
-                }],
-                i,
-                &[leaf.clone()],
-                proof,
-            )
-            .is_err()
-        {
-            return Err(FullRoundVerificationError::QueryPath);
+    // Each opening must contain exactly the k_{i - 1} evaluations of a fold
+    // group before it is used
+    check_leaf_lengths(1 << log_folding_factor, query_proofs)
+        .map_err(|(expected, got)| FullRoundVerificationError::MalformedLeaf { expected, got })?;
+
+    // Verify (or defer) the Merkle proofs of the evaluations of g_{i - 1}. The
+    // j-th element of the returned vector is the list of evaluations of
+    // g_{i - 1} at the k_{i - 1}-th roots of the j-th sampled point
+    // r^shift_{i, j}. These give rise to the values of f_{i - 1} at the same
+    // points, which got folded into g_i(r^shift_{i, j}).
+    let previous_g_values = match verify_or_defer_query_paths(
+        config.mmcs_config(),
+        Some(round),
+        &prev_root,
+        StackedLayout::new(
+            domain.log_size(),
+            log_folding_factor,
+            config.log_rows_per_fold_group(log_folding_factor),
+        ),
+        &queried_indices,
+        query_proofs,
+        outputs.deferred.as_deref_mut(),
+    ) {
+        Some(previous_g_values) => previous_g_values,
+        None => {
+            // If failures are collected, the remaining checks proceed with
+            // the unauthenticated evaluations
+            outputs
+                .failures
+                .report(FullRoundVerificationError::QueryPath, in_round)?;
+            opened_evaluations(query_proofs)
         }
-    }
-
-    // The j-th element of this vector is the list of evaluations of g_{i - 1}
-    // at the k_{i - 1}-th roots of the j-th sampled point r^shift_{i, j}.
-    // These give rise to the values of f_{i - 1} at the same points, which got
-    // folded into g_i(r^shift_{i, j}).
-    let previous_g_values = query_proofs.into_iter().map(|(leaf, _)| leaf).collect_vec();
+    };
 
     // Compute the values of f_{i - 1} from those of g_{i - 1}
-    let previous_f_values = compute_f_oracle_from_g(
+    let previous_f_values = compute_f_oracle_from_g::<F, EF>(
         &oracle,
         previous_g_values,
         &queried_indices,
@@ -490,6 +1900,12 @@ where
         domain.generator().exp_power_of_2(log_query_domain_size),
     );
 
+    // The folded point r^shift_{i, j} = s_j^{k_{i - 1}} is the constant term of
+    // the vanishing polynomial x^{k_{i - 1}} - s_j^{k_{i - 1}} of the coset of
+    // roots s_j * <c> above. Since the queried indices are de-duplicated, each
+    // such coset occurs exactly once per round (and the cosets of different
+    // rounds live in different domains), so this is computed once per query
+    // and there is nothing to be gained from caching it.
     let folded_answers = queried_point_roots
         .into_iter()
         .zip(folded_evals)
@@ -498,18 +1914,11 @@ where
     // The quotient definining the function
     let quotient_answers: Vec<_> = ood_samples
         .into_iter()
-        .zip(betas)
+        .zip(betas.iter().copied())
         .chain(folded_answers)
         .collect();
 
     // Check that Ans interpolates the expected values using the shake polynomial
-    if ans_polynomial
-        .degree()
-        .is_some_and(|d| d >= quotient_answers.len())
-    {
-        return Err(FullRoundVerificationError::AnsPolynomialDegree);
-    }
-
     let quotient_set = quotient_answers.iter().map(|(x, _)| *x).collect_vec();
 
     // This is the degree bound (plus 1) for g_i:
@@ -525,45 +1934,342 @@ where
         tracing::info!("Warning: quotient polynomial is zero in round {}", round);
     }
 
+    // The answers preceding the folded ones are the betas, which have already
+    // been checked against Ans_i
+    let num_ood_answers = quotient_answers.len() - queried_indices.len();
+
     // Check that the Ans polynomial interpolates the expected values using
     // the shake polynomial
+    #[cfg(not(feature = "no-shake"))]
     if !verify_evaluations(
-        &ans_polynomial,
-        &shake_polynomial,
+        ans_polynomial,
+        shake_polynomial,
         shake_randomness,
-        quotient_answers,
+        &quotient_answers,
     ) {
-        return Err(FullRoundVerificationError::AnsPolynomialEvaluations);
+        outputs.mismatch = find_query_mismatch::<F, EF>(
+            ans_polynomial,
+            &queried_indices,
+            &quotient_answers[num_ood_answers..],
+        );
+        outputs.failures.report(
+            FullRoundVerificationError::AnsPolynomialEvaluations,
+            in_round,
+        )?;
+    }
+
+    // Without a shake polynomial, Ans_i is evaluated at the folded points
+    // directly
+    #[cfg(feature = "no-shake")]
+    if let Some(query_mismatch) = find_query_mismatch::<F, EF>(
+        ans_polynomial,
+        &queried_indices,
+        &quotient_answers[num_ood_answers..],
+    ) {
+        outputs.mismatch = Some(query_mismatch);
+        outputs.failures.report(
+            FullRoundVerificationError::AnsPolynomialEvaluations,
+            in_round,
+        )?;
     }
 
     // Produce the new verification state
     Ok(VerificationState {
         oracle: Oracle::Virtual(VirtualFunction {
             comb_randomness,
-            interpolating_polynomial: ans_polynomial,
+            interpolating_polynomial: ans_polynomial.clone(),
             quotient_set,
         }),
         domain: domain.shrink_subgroup(1),
         folding_randomness: new_folding_randomness,
         round,
-        root: g_root,
+        root: g_root.clone(),
     })
 }
 
+// The challenges of a full round which are sampled before its proof of work
+// is checked
+struct RoundChallenges<F> {
+    // The out-of-domain points, outside L_{i - 1}
+    ood_samples: Vec<F>,
+    // The degree-correction randomness r^comb_i
+    comb_randomness: F,
+    // The folding randomness r_i for the next round
+    folding_randomness: F,
+    // The (deduplicated) indices of the queried points in L_{i - 1}^k_{i - 1}
+    queried_indices: Vec<usize>,
+}
+
+// Observes the prover messages of full round i up to its proof of work and
+// samples the corresponding challenges. This only depends on the transcript
+// (not on the queried evaluations), so it is shared by the full verifier and
+// verify_pow_only.
+fn sample_round_challenges<F, EF, M, C, S>(
+    // The full STIR configuration
+    config: &StirConfig<M>,
+    // Index i of the round
+    round: usize,
+    // Domain L_{i - 1}
+    domain: &TwoAdicCoset<EF>,
+    // Folding randomness r_{i - 1}, used in this round
+    folding_randomness: EF,
+    // The proof for the current round
+    round_proof: &RoundProof<EF, M, C::Witness>,
+    // Challenger for the transcript
+    challenger: &mut C,
+    // Derivation of the folding randomness from the transcript
+    source: &S,
+) -> RoundChallenges<EF>
+where
+    F: Field,
+    EF: TwoAdicField + ExtensionField<F>,
+    M: Mmcs<EF>,
+    C: FieldChallenger<F> + GrindingChallenger + CanObserve<M::Commitment>,
+    S: FoldChallengeSource<F>,
+{
+    let RoundConfig {
+        log_folding_factor,
+        num_queries,
+        num_ood_samples,
+        ..
+    } = config.round_config(round).clone();
+
+    // Observe the commitment to g_{i - 1}
+    challenger.observe(F::from_u8(Messages::RoundCommitment as u8));
+    challenger.observe(round_proof.g_root.clone());
+
+    // Sampling the out-of-domain points, which have to be outside L_{i - 1}
+    let ood_samples = sample_ood_points(challenger, domain, num_ood_samples);
+
+    // Observe the betas, i. e. the replies to the out-of-domain queries
+    challenger.observe(F::from_u8(Messages::Betas as u8));
+    round_proof
+        .betas
+        .iter()
+        .for_each(|&beta| challenger.observe_algebra_element(beta));
+
+    // Sample the degree-correction randomness
+    challenger.observe(F::from_u8(Messages::CombRandomness as u8));
+    let comb_randomness = challenger.sample_algebra_element();
+
+    // Sample the folding randomness for the next round
+    let folding_randomness = sample_next_folding_randomness(
+        config.batched_folding_randomness(),
+        folding_randomness,
+        challenger,
+        source,
+        round,
+    );
+
+    // Sample queried indices of elements in L_{i - 1}^k_{i-1}
+    let log_query_domain_size = domain.log_size() - log_folding_factor;
+
+    challenger.observe(F::from_u8(Messages::QueryIndices as u8));
+    let queried_indices: Vec<usize> = (0..num_queries)
+        .map(|_| challenger.sample_bits(log_query_domain_size))
+        .unique()
+        .collect();
+
+    RoundChallenges {
+        ood_samples,
+        comb_randomness,
+        folding_randomness,
+        queried_indices,
+    }
+}
+
+// Observes the Ans polynomial of a full round, which follows its proof of work
+// in the transcript
+fn observe_ans_polynomial<F, EF, M, C>(
+    // The proof for the current round
+    round_proof: &RoundProof<EF, M, C::Witness>,
+    // Challenger for the transcript
+    challenger: &mut C,
+) where
+    F: Field,
+    EF: TwoAdicField + ExtensionField<F>,
+    M: Mmcs<EF>,
+    C: FieldChallenger<F> + GrindingChallenger + CanObserve<M::Commitment>,
+{
+    challenger.observe(F::from_u8(Messages::AnsPolynomial as u8));
+    observe_ext_slice_with_size(challenger, round_proof.ans_polynomial.coeffs());
+}
+
+// Observes the shake polynomial of a full round, which follows its Ans
+// polynomial in the transcript, and samples the shake randomness
+#[cfg(not(feature = "no-shake"))]
+fn sample_shake_randomness<F, EF, M, C>(
+    // The proof for the current round
+    round_proof: &RoundProof<EF, M, C::Witness>,
+    // Challenger for the transcript
+    challenger: &mut C,
+) -> EF
+where
+    F: Field,
+    EF: TwoAdicField + ExtensionField<F>,
+    M: Mmcs<EF>,
+    C: FieldChallenger<F> + GrindingChallenger + CanObserve<M::Commitment>,
+{
+    challenger.observe(F::from_u8(Messages::ShakePolynomial as u8));
+    observe_ext_slice_with_size(challenger, round_proof.shake_polynomial.coeffs());
+
+    challenger.observe(F::from_u8(Messages::ShakeRandomness as u8));
+    challenger.sample_algebra_element()
+}
+
+// Observes the final polynomial and samples the (deduplicated) indices of the
+// final queried points in (L_M)^{k_M}
+fn sample_final_queried_indices<F, EF, M, C>(
+    // The full STIR configuration
+    config: &StirConfig<M>,
+    // Domain L_M
+    final_domain: &TwoAdicCoset<EF>,
+    // The final polynomial p = g_{M + 1}
+    final_polynomial: &Polynomial<EF>,
+    // Challenger for the transcript
+    challenger: &mut C,
+) -> Vec<usize>
+where
+    F: Field,
+    EF: TwoAdicField + ExtensionField<F>,
+    M: Clone,
+    C: FieldChallenger<F>,
+{
+    let log_final_query_domain_size = final_domain.log_size() - config.log_last_folding_factor();
+
+    challenger.observe(F::from_u8(Messages::FinalPolynomial as u8));
+    observe_ext_slice_with_size(challenger, final_polynomial.coeffs());
+
+    challenger.observe(F::from_u8(Messages::FinalQueryIndices as u8));
+    (0..config.final_num_queries())
+        .map(|_| challenger.sample_bits(log_final_query_domain_size))
+        .unique()
+        .collect()
+}
+
+// Verify the Merkle proofs of the rows of the committed matrix making up the
+// fold groups opened at the queried indices
+fn verify_query_paths<F: Field, M: Mmcs<F>>(
+    // The MMCS used to commit to the matrix
+    mmcs: &M,
+    // Root of the Merkle tree
+    root: &M::Commitment,
+    // Layout of the committed matrix
+    layout: StackedLayout,
+    // Indices of the opened fold groups
+    queried_indices: &[usize],
+    // Opened fold groups and the Merkle proofs of their rows
+    query_proofs: &[(Vec<F>, Vec<M::Proof>)],
+) -> bool {
+    let StackedLayout {
+        dimensions,
+        log_rows_per_group,
+    } = layout;
+
+    let rows_per_group = 1 << log_rows_per_group;
+
+    queried_indices
+        .iter()
+        .zip(query_proofs.iter())
+        .all(|(&i, (evals, proofs))| {
+            evals.len() == rows_per_group * dimensions.width
+                && proofs.len() == rows_per_group
+                && evals
+                    .chunks_exact(dimensions.width)
+                    .zip(proofs)
+                    .enumerate()
+                    .all(|(t, (row, proof))| {
+                        mmcs.verify_batch(
+                            root,
+                            &[dimensions],
+                            (i << log_rows_per_group) + t,
+                            &[row.to_vec()],
+                            proof,
+                        )
+                        .is_ok()
+                    })
+        })
+}
+
+// Check that each opened fold group contains exactly fold_group_size
+// evaluations, returning the expected and actual lengths of the first one
+// which does not otherwise
+fn check_leaf_lengths<F, P>(
+    // The number of evaluations in a fold group, i. e. the folding factor
+    fold_group_size: usize,
+    // Opened fold groups and the Merkle proofs of their rows
+    query_proofs: &[(Vec<F>, P)],
+) -> Result<(), (usize, usize)> {
+    match query_proofs
+        .iter()
+        .find(|(evals, _)| evals.len() != fold_group_size)
+    {
+        Some((evals, _)) => Err((fold_group_size, evals.len())),
+        None => Ok(()),
+    }
+}
+
+// Verify the Merkle proofs of the fold groups opened at the queried indices of
+// one round (or store them in deferred for later verification if the latter is
+// provided) and return the opened evaluations. Returns None if the proofs are
+// verified and at least one of them is invalid.
+fn verify_or_defer_query_paths<F: Field, M: Mmcs<F>>(
+    // The MMCS used to commit to the matrix
+    mmcs: &M,
+    // Round i in {1, ..., M} for full rounds, None for the final round
+    round: Option<usize>,
+    // Root of the Merkle tree
+    root: &M::Commitment,
+    // Layout of the committed matrix
+    layout: StackedLayout,
+    // Indices of the opened fold groups
+    queried_indices: &[usize],
+    // Opened fold groups and the Merkle proofs of their rows
+    query_proofs: &[(Vec<F>, Vec<M::Proof>)],
+    // Storage for the deferred Merkle proofs
+    deferred: Option<&mut DeferredQueries<F, M>>,
+) -> Option<Vec<Vec<F>>> {
+    match deferred {
+        Some(deferred) => deferred.rounds.push(DeferredRoundQueries {
+            round,
+            root: root.clone(),
+            layout,
+            queried_indices: queried_indices.to_vec(),
+            query_proofs: query_proofs.to_vec(),
+        }),
+        None => {
+            if !verify_query_paths(mmcs, root, layout, queried_indices, query_proofs) {
+                return None;
+            }
+        }
+    }
+
+    Some(opened_evaluations(query_proofs))
+}
+
+// Returns the evaluations contained in the opened fold groups, without
+// verifying their Merkle proofs
+fn opened_evaluations<F: Clone, P>(
+    // Opened fold groups and the Merkle proofs of their rows
+    query_proofs: &[(Vec<F>, P)],
+) -> Vec<Vec<F>> {
+    query_proofs.iter().map(|(leaf, _)| leaf.clone()).collect()
+}
+
 // Compute the values of the oracle f_i given its underlying function g_i
 // (or f_i itself in the case of a transparent oracle)
-fn compute_f_oracle_from_g<F: TwoAdicField>(
+fn compute_f_oracle_from_g<F: Field, EF: TwoAdicField + ExtensionField<F>>(
     // Oracle relating f_i to its underlying function g_i
-    oracle: &Oracle<F>,
+    oracle: &Oracle<EF>,
     // Evaluations of g_i at the points of interest
-    g_eval_batches: Vec<Vec<F>>,
+    g_eval_batches: Vec<Vec<EF>>,
     // The indices of the queried elements of L_i^{k_i}
     queried_indices: &[usize],
     // The domain L_i
-    domain: &mut TwoAdicCoset<F>,
+    domain: &mut TwoAdicCoset<EF>,
     // The log of the folding factor k_i
     log_folding_factor: usize,
-) -> Vec<Vec<F>> {
+) -> Vec<Vec<EF>> {
     // 1. Compute the set of k_i-th roots of r^shift_{i, j} for each sampled
     // point r^shift_{i, j} from L_i^k_i. This is simply the coset
     //   (s_j) * {1, c, ..., c^{k_i - 1}},
@@ -597,7 +2303,7 @@ fn compute_f_oracle_from_g<F: TwoAdicField>(
     let denom_inv_hints = match oracle {
         Oracle::Transparent => vec![vec![None; folding_factor]; queried_point_preimages.len()],
         Oracle::Virtual(virtual_function) => {
-            let flat_denoms: Vec<F> = queried_point_preimages
+            let flat_denoms: Vec<EF> = queried_point_preimages
                 .iter()
                 .flat_map(|points| {
                     // Computing the denominator, i. e. the value of the vanishing
@@ -638,16 +2344,16 @@ fn compute_f_oracle_from_g<F: TwoAdicField>(
     let deg_cor_hints = match oracle {
         Oracle::Transparent => vec![vec![None; folding_factor]; queried_point_preimages.len()],
         Oracle::Virtual(virtual_function) => {
-            let (flat_rx_s, flat_denoms): (Vec<F>, Vec<F>) = queried_point_preimages
+            let (flat_rx_s, flat_denoms): (Vec<EF>, Vec<EF>) = queried_point_preimages
                 .iter()
                 .flat_map(|points| {
                     points.iter().map(|point| {
                         let rx = *point * virtual_function.comb_randomness;
 
-                        if rx == F::ONE {
-                            (F::ONE, F::ONE)
+                        if rx == EF::ONE {
+                            (EF::ONE, EF::ONE)
                         } else {
-                            (rx, (F::ONE - rx))
+                            (rx, (EF::ONE - rx))
                         }
                     })
                 })
@@ -666,27 +2372,59 @@ fn compute_f_oracle_from_g<F: TwoAdicField>(
         }
     };
 
+    // Each call to the oracle also evaluates the interpolating polynomial Ans_i
+    // at the point. Doing so for all points at once allows packing them into
+    // batches, so that each step of Horner's rule processes a full batch
+    let interpolating_eval_hints = match oracle {
+        Oracle::Transparent => vec![vec![None; folding_factor]; queried_point_preimages.len()],
+        Oracle::Virtual(virtual_function) => {
+            let flat_points = queried_point_preimages.concat();
+
+            virtual_function
+                .interpolating_polynomial
+                .evaluate_packed::<F>(&flat_points)
+                .into_iter()
+                .map(Some)
+                .collect_vec()
+                .chunks_exact(folding_factor)
+                .map(|chunk| chunk.to_vec())
+                .collect_vec()
+        }
+    };
+
     // Compute the values of f_i at the each element of each coset using the
     // precomputed hints
-    queried_point_preimages
-        .into_iter()
-        .zip(g_eval_batches)
-        .zip(denom_inv_hints)
-        .zip(deg_cor_hints)
-        .map(
-            |(((points, g_eval_batch), denom_inverse_hints), deg_cor_hints)| {
-                points
-                    .into_iter()
-                    .zip(g_eval_batch)
-                    .zip(denom_inverse_hints)
-                    .zip(deg_cor_hints)
-                    .map(|(((point, g_eval), denom_inverse_hint), deg_cor_hint)| {
-                        oracle.evaluate(point, g_eval, denom_inverse_hint, deg_cor_hint)
-                    })
-                    .collect_vec()
-            },
-        )
-        .collect_vec()
+    izip!(
+        queried_point_preimages,
+        g_eval_batches,
+        denom_inv_hints,
+        deg_cor_hints,
+        interpolating_eval_hints
+    )
+    .map(
+        |(points, g_eval_batch, denom_inverse_hints, deg_cor_hints, interpolating_eval_hints)| {
+            izip!(
+                points,
+                g_eval_batch,
+                denom_inverse_hints,
+                deg_cor_hints,
+                interpolating_eval_hints
+            )
+            .map(
+                |(point, g_eval, denom_inverse_hint, deg_cor_hint, interpolating_eval_hint)| {
+                    oracle.evaluate(
+                        point,
+                        g_eval,
+                        denom_inverse_hint,
+                        deg_cor_hint,
+                        interpolating_eval_hint,
+                    )
+                },
+            )
+            .collect_vec()
+        },
+    )
+    .collect_vec()
 }
 
 // Given the evaluations of a polynomial at the set of k-th roots of a point,
@@ -733,10 +2471,42 @@ fn compute_folded_evaluations<F: TwoAdicField>(
         .collect()
 }
 
+// Returns the first query whose folded value (computed by the verifier from the
+// opened evaluations) differs from the evaluation of the polynomial sent by the
+// prover (Ans_i or the final polynomial) at the corresponding folded point, if
+// any
+fn find_query_mismatch<F: Field, EF: TwoAdicField + ExtensionField<F>>(
+    // Polynomial sent by the prover
+    polynomial: &Polynomial<EF>,
+    // Indices of the queried points in the query domain, in the same order as
+    // the folded answers
+    queried_indices: &[usize],
+    // Pairs of folded points and folded values, one per query
+    folded_answers: &[(EF, EF)],
+) -> Option<QueryMismatch<EF>> {
+    // The polynomial is evaluated at all folded points at once, packing them
+    // into batches
+    let points = folded_answers.iter().map(|&(point, _)| point).collect_vec();
+    let evaluations = polynomial.evaluate_packed::<F>(&points);
+
+    izip!(queried_indices, folded_answers, evaluations)
+        .enumerate()
+        .map(
+            |(query, (&domain_index, &(_, expected), actual))| QueryMismatch {
+                query,
+                domain_index,
+                expected,
+                actual,
+            },
+        )
+        .find(|query_mismatch| query_mismatch.expected != query_mismatch.actual)
+}
+
 // Verify that f takes the values y_1, ..., y_n at x_1, ..., x_n (resp.)
 // using the auxiliary shake polynomial
 //     q(x) = (f(x) - y_1) / (x - x_1) + ... + (f(x) - y_n) / (x - x_n).
 // The above equation is checked at a uniformly sampled random point r.
+#[cfg(not(feature = "no-shake"))]
 fn verify_evaluations<F: TwoAdicField>(
     // Polynomial whose evaluations are being checked
     f: &Polynomial<F>,
@@ -745,12 +2515,12 @@ fn verify_evaluations<F: TwoAdicField>(
     // Random field element where the equation is checked
     r: F,
     // Vector of point-evaluation pairs (x_i, y_i)
-    points: Vec<(F, F)>,
+    points: &[(F, F)],
 ) -> bool {
     let f_eval = f.evaluate(&r);
     let shake_eval = shake_polynomial.evaluate(&r);
 
-    let (xs, ys): (Vec<F>, Vec<F>) = points.into_iter().unzip();
+    let (xs, ys): (Vec<F>, Vec<F>) = points.iter().copied().unzip();
 
     // Batch-invert all the denominators for efficiency
     let denominators = batch_multiplicative_inverse(&xs.into_iter().map(|x| r - x).collect_vec());
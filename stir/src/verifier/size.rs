@@ -0,0 +1,273 @@
+// Measurement of the size of proof components whose type is only known to be
+// serialisable, such as the Merkle proofs of a generic MMCS. The size of a
+// value is the number of primitive values (integers, field elements in their
+// serialised form, characters, bytes...) it serialises to, regardless of the
+// encoding: a Merkle path of n siblings, each a digest of d words, has size
+// n * d.
+
+use core::fmt::{Display, Formatter, Result as FmtResult};
+
+use serde::ser::{
+    self, Serialize, SerializeMap, SerializeSeq, SerializeStruct, SerializeStructVariant,
+    SerializeTuple, SerializeTupleStruct, SerializeTupleVariant, Serializer,
+};
+
+// Returns the number of primitive values `value` serialises to, or usize::MAX
+// if its serialisation fails (which is then treated as an oversized value)
+pub(crate) fn num_serialized_values<T: Serialize + ?Sized>(value: &T) -> usize {
+    let mut counter = ValueCounter { count: 0 };
+
+    match value.serialize(&mut counter) {
+        Ok(()) => counter.count,
+        Err(CountingError) => usize::MAX,
+    }
+}
+
+// Serializer which discards everything but the number of primitive values
+struct ValueCounter {
+    count: usize,
+}
+
+impl ValueCounter {
+    fn add(&mut self, count: usize) -> Result<(), CountingError> {
+        self.count = self.count.saturating_add(count);
+        Ok(())
+    }
+}
+
+// Error raised by the Serialize implementation of the value being measured
+#[derive(Debug)]
+struct CountingError;
+
+impl Display for CountingError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        f.write_str("serialization failed")
+    }
+}
+
+impl ser::StdError for CountingError {}
+
+impl ser::Error for CountingError {
+    fn custom<T: Display>(_msg: T) -> Self {
+        CountingError
+    }
+}
+
+// Every primitive counts as one value, except strings and byte arrays, which
+// count as their length
+macro_rules! count_one {
+    ($($method:ident: $ty:ty),* $(,)?) => {
+        $(
+            fn $method(self, _value: $ty) -> Result<(), CountingError> {
+                self.add(1)
+            }
+        )*
+    };
+}
+
+impl Serializer for &mut ValueCounter {
+    type Ok = ();
+    type Error = CountingError;
+    type SerializeSeq = Self;
+    type SerializeTuple = Self;
+    type SerializeTupleStruct = Self;
+    type SerializeTupleVariant = Self;
+    type SerializeMap = Self;
+    type SerializeStruct = Self;
+    type SerializeStructVariant = Self;
+
+    count_one!(
+        serialize_bool: bool,
+        serialize_i8: i8,
+        serialize_i16: i16,
+        serialize_i32: i32,
+        serialize_i64: i64,
+        serialize_i128: i128,
+        serialize_u8: u8,
+        serialize_u16: u16,
+        serialize_u32: u32,
+        serialize_u64: u64,
+        serialize_u128: u128,
+        serialize_f32: f32,
+        serialize_f64: f64,
+        serialize_char: char,
+    );
+
+    fn serialize_str(self, value: &str) -> Result<(), CountingError> {
+        self.add(value.len())
+    }
+
+    fn serialize_bytes(self, value: &[u8]) -> Result<(), CountingError> {
+        self.add(value.len())
+    }
+
+    fn collect_str<T: Display + ?Sized>(self, _value: &T) -> Result<(), CountingError> {
+        self.add(1)
+    }
+
+    fn serialize_none(self) -> Result<(), CountingError> {
+        Ok(())
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<(), CountingError> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<(), CountingError> {
+        Ok(())
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<(), CountingError> {
+        Ok(())
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+    ) -> Result<(), CountingError> {
+        Ok(())
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<(), CountingError> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        value: &T,
+    ) -> Result<(), CountingError> {
+        value.serialize(self)
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self, CountingError> {
+        Ok(self)
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Self, CountingError> {
+        Ok(self)
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self, CountingError> {
+        Ok(self)
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self, CountingError> {
+        Ok(self)
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self, CountingError> {
+        Ok(self)
+    }
+
+    fn serialize_struct(self, _name: &'static str, _len: usize) -> Result<Self, CountingError> {
+        Ok(self)
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self, CountingError> {
+        Ok(self)
+    }
+}
+
+// All compound values are measured as the sum of their components (map keys
+// included)
+macro_rules! impl_compound {
+    ($($trait:ident :: $method:ident),* $(,)?) => {
+        $(
+            impl $trait for &mut ValueCounter {
+                type Ok = ();
+                type Error = CountingError;
+
+                fn $method<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), CountingError> {
+                    value.serialize(&mut **self)
+                }
+
+                fn end(self) -> Result<(), CountingError> {
+                    Ok(())
+                }
+            }
+        )*
+    };
+}
+
+impl_compound!(
+    SerializeSeq::serialize_element,
+    SerializeTuple::serialize_element,
+    SerializeTupleStruct::serialize_field,
+    SerializeTupleVariant::serialize_field,
+);
+
+impl SerializeMap for &mut ValueCounter {
+    type Ok = ();
+    type Error = CountingError;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), CountingError> {
+        key.serialize(&mut **self)
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), CountingError> {
+        value.serialize(&mut **self)
+    }
+
+    fn end(self) -> Result<(), CountingError> {
+        Ok(())
+    }
+}
+
+impl SerializeStruct for &mut ValueCounter {
+    type Ok = ();
+    type Error = CountingError;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        _key: &'static str,
+        value: &T,
+    ) -> Result<(), CountingError> {
+        value.serialize(&mut **self)
+    }
+
+    fn end(self) -> Result<(), CountingError> {
+        Ok(())
+    }
+}
+
+impl SerializeStructVariant for &mut ValueCounter {
+    type Ok = ();
+    type Error = CountingError;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        _key: &'static str,
+        value: &T,
+    ) -> Result<(), CountingError> {
+        value.serialize(&mut **self)
+    }
+
+    fn end(self) -> Result<(), CountingError> {
+        Ok(())
+    }
+}
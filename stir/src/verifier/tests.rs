@@ -1,26 +1,55 @@
+use alloc::string::ToString;
 use alloc::vec;
 use alloc::vec::Vec;
+use core::iter;
 use core::iter::Iterator;
 
 use itertools::Itertools;
-use p3_challenger::{CanObserve, CanSampleBits, FieldChallenger, GrindingChallenger};
+use p3_challenger::{CanObserve, CanSample, CanSampleBits, FieldChallenger, GrindingChallenger};
 use p3_commit::Mmcs;
 use p3_coset::TwoAdicCoset;
-use p3_field::PrimeCharacteristicRing;
+use p3_field::{BasedVectorSpace, ExtensionField, PrimeCharacteristicRing, TwoAdicField};
 use p3_poly::test_utils::rand_poly;
+use p3_poly::Polynomial;
 use p3_symmetric::Hash;
 use rand::{rng, Rng};
 
-use crate::config::observe_public_parameters;
-use crate::prover::{commit, prove, prove_round, StirRoundWitness};
+use crate::challenger::challenger_from_seed;
+use crate::config::{observe_public_parameters, RoundConfig};
+use crate::proof::{FormatVersion, NO_SHAKE_FORMAT_FLAG};
+use crate::prover::{
+    build_answer_polynomial, commit, initial_domain, prove, prove_mixed_degrees, prove_pair,
+    prove_round, prove_with_public_inputs, stack_initial_evaluations, StirRoundWitness,
+};
 use crate::test_utils::*;
-use crate::utils::{fold_polynomial, observe_ext_slice_with_size};
-use crate::verifier::error::{FullRoundVerificationError, VerificationError};
-use crate::verifier::{compute_folded_evaluations, verify};
-use crate::{Messages, SecurityAssumption, StirConfig, StirProof};
+use crate::utils::{
+    fold_polynomial, observe_ext_slice_with_size, observe_usize_slice, open_fold_group,
+    TranscriptFoldChallenge,
+};
+use crate::verifier::error::{
+    FullRoundVerificationError, PairVerificationError, ShapeError, VerificationError,
+};
+use crate::verifier::{
+    compute_f_oracle_from_g, compute_folded_evaluations, initial_verification_state, proof_size,
+    sample_round_challenges, verify, verify_against_any, verify_cheap_checks,
+    verify_collect_errors, verify_diagnostic, verify_envelope, verify_mixed_degrees, verify_pair,
+    verify_pow_only, verify_query_paths, verify_with_domains, verify_with_limits,
+    verify_with_public_inputs, Oracle, StackedLayout, VerificationLimits, VirtualFunction,
+    MAX_ENVELOPE_SECURITY_LEVEL,
+};
+use crate::{
+    batching_challenge, prove_with_fold_challenge_source, verify_with_fold_challenge_source,
+    ChainedProver, ChainedVerifier, EncodedLeafMmcs, FoldChallengeSource, IdentityLeafEncoding,
+    LeafEncoding, Messages, SecurityAssumption, StirConfig, StirParameters, StirParametersSpec,
+    StirProof, StirProofEnvelope,
+};
 
 type BBProof = StirProof<BbExt, BbExtMmcs, Bb>;
+type BBKeccakProof = StirProof<BbExt, BbKeccakExtMmcs, Bb>;
 type GLProof = StirProof<GlExt, GlExtMmcs, Gl>;
+type GLQuinticProof = StirProof<GlQuinticExt, GlQuinticExtMmcs, Gl>;
+type M31Proof = StirProof<M31Ext, M31ExtMmcs, M31>;
+type BBMockProof = StirProof<BbExt, BbMockMmcs, Bb>;
 
 // This macro creates a function that commits to a random polynomial and
 // produces a STIR proof for it given a configuration
@@ -44,8 +73,8 @@ macro_rules! impl_generate_proof_with_config {
             let polynomial = rand_poly((1 << config.log_starting_degree()) - 1);
             let (witness, commitment) = commit(&config, polynomial);
             (
-                prove(&config, witness, commitment, challenger),
-                commitment.clone(),
+                prove(&config, witness, commitment.clone(), challenger),
+                commitment,
             )
         }
     };
@@ -72,7 +101,7 @@ macro_rules! impl_test_verify_with_config {
                 ($challenger_fn(), $challenger_fn());
 
             let (proof, commitment) = $proof_fn(config, &mut prover_challenger);
-            verify(config, commitment, proof, &mut verifier_challenger).unwrap();
+            verify(config, commitment, &proof, &mut verifier_challenger).unwrap();
 
             // Check that the sponge is consistent at the end
             assert_eq!(
@@ -92,6 +121,15 @@ impl_generate_proof_with_config!(
     BbChallenger
 );
 
+// Create the function generate_bb_keccak_proof_with_config
+impl_generate_proof_with_config!(
+    generate_bb_keccak_proof_with_config,
+    BbKeccakExtMmcs,
+    BBKeccakProof,
+    Hash<Bb, u8, 32>,
+    BbKeccakChallenger
+);
+
 // Create the function generate_gl_proof_with_config
 impl_generate_proof_with_config!(
     generate_gl_proof_with_config,
@@ -101,6 +139,33 @@ impl_generate_proof_with_config!(
     GlChallenger
 );
 
+// Create the function generate_gl_quintic_proof_with_config
+impl_generate_proof_with_config!(
+    generate_gl_quintic_proof_with_config,
+    GlQuinticExtMmcs,
+    GLQuinticProof,
+    Hash<Gl, Gl, 4>,
+    GlChallenger
+);
+
+// Create the function generate_m31_proof_with_config
+impl_generate_proof_with_config!(
+    generate_m31_proof_with_config,
+    M31ExtMmcs,
+    M31Proof,
+    Hash<M31, M31, 8>,
+    M31Challenger
+);
+
+// Create the function generate_bb_mock_proof_with_config
+impl_generate_proof_with_config!(
+    generate_bb_mock_proof_with_config,
+    BbMockMmcs,
+    BBMockProof,
+    Vec<Vec<Bb>>,
+    BbChallenger
+);
+
 // Create the function test_bb_verify_with_config
 impl_test_verify_with_config!(
     test_bb_verify_with_config,
@@ -110,6 +175,24 @@ impl_test_verify_with_config!(
     generate_bb_proof_with_config
 );
 
+// Create the function test_bb_keccak_verify_with_config
+impl_test_verify_with_config!(
+    test_bb_keccak_verify_with_config,
+    BbExt,
+    BbKeccakExtMmcs,
+    test_bb_keccak_challenger,
+    generate_bb_keccak_proof_with_config
+);
+
+// Create the function test_bb_mock_verify_with_config
+impl_test_verify_with_config!(
+    test_bb_mock_verify_with_config,
+    BbExt,
+    BbMockMmcs,
+    test_bb_challenger,
+    generate_bb_mock_proof_with_config
+);
+
 // Create the function test_gl_verify_with_config
 impl_test_verify_with_config!(
     test_gl_verify_with_config,
@@ -119,6 +202,24 @@ impl_test_verify_with_config!(
     generate_gl_proof_with_config
 );
 
+// Create the function test_gl_quintic_verify_with_config
+impl_test_verify_with_config!(
+    test_gl_quintic_verify_with_config,
+    GlQuinticExt,
+    GlQuinticExtMmcs,
+    test_gl_challenger,
+    generate_gl_quintic_proof_with_config
+);
+
+// Create the function test_m31_verify_with_config
+impl_test_verify_with_config!(
+    test_m31_verify_with_config,
+    M31Ext,
+    M31ExtMmcs,
+    test_m31_challenger,
+    generate_m31_proof_with_config
+);
+
 // Auxiliary function to trigger a tricky verification error which mimics the
 // honest proving procedure but modifies the final polynomial near the end.
 fn tamper_with_final_polynomial(config: &StirConfig<BbExtMmcs>) -> (BBProof, Hash<Bb, Bb, 8>) {
@@ -148,8 +249,9 @@ fn tamper_with_final_polynomial(config: &StirConfig<BbExtMmcs>) -> (BBProof, Has
     };
 
     let mut round_proofs = vec![];
-    for _ in 0..config.num_rounds() - 1 {
-        let (new_witness, round_proof) = prove_round(config, witness, &mut challenger);
+    for _ in 0..config.num_full_rounds() {
+        let (new_witness, round_proof) =
+            prove_round(config, witness, &mut challenger, &TranscriptFoldChallenge);
         witness = new_witness;
         round_proofs.push(round_proof);
     }
@@ -178,20 +280,25 @@ fn tamper_with_final_polynomial(config: &StirConfig<BbExtMmcs>) -> (BBProof, Has
         .unique()
         .collect();
 
+    let log_rows_per_group = config.log_rows_per_fold_group(log_last_folding_factor);
     let queries_to_final: Vec<(Vec<BbExt>, _)> = queried_indices
         .into_iter()
         .map(|index| {
-            config
-                .mmcs_config()
-                .open_batch(index as usize, &witness.merkle_tree)
+            open_fold_group(
+                config.mmcs_config(),
+                index as usize,
+                log_rows_per_group,
+                &witness.merkle_tree,
+            )
         })
-        .map(|(mut k, v)| (k.remove(0), v))
         .collect();
 
     let pow_witness = challenger.grind(config.final_pow_bits());
 
     (
         StirProof {
+            format_version: FormatVersion,
+            parameter_fingerprint: config.parameter_fingerprint(),
             round_proofs,
             final_polynomial,
             pow_witness,
@@ -230,6 +337,66 @@ fn test_compute_folded_evals() {
     assert_eq!(folded_eval, expected_folded_eval);
 }
 
+#[test]
+// Check that compute_f_oracle_from_g, which evaluates the interpolating
+// polynomial at the queried points in packed batches, yields the same values of
+// a virtual oracle as evaluating the oracle point by point without any hints
+fn test_compute_f_oracle_packed() {
+    let mut rng = rng();
+
+    let log_folding_factor = 2;
+    let mut domain = TwoAdicCoset::new(rng.random::<BbExt>(), 10);
+    let queried_indices = vec![3, 17, 42, 100, 211];
+
+    let oracle = Oracle::Virtual(VirtualFunction {
+        comb_randomness: rng.random(),
+        interpolating_polynomial: rand_poly(7),
+        quotient_set: (0..8).map(|_| rng.random()).collect_vec(),
+    });
+
+    let generator = domain
+        .generator()
+        .exp_power_of_2(domain.log_size() - log_folding_factor);
+
+    let preimages = queried_indices
+        .iter()
+        .map(|&index| {
+            let root = domain.element(index);
+            (0..1 << log_folding_factor)
+                .map(|j| root * generator.exp_u64(j))
+                .collect_vec()
+        })
+        .collect_vec();
+
+    let g_evals = preimages
+        .iter()
+        .map(|points| points.iter().map(|_| rng.random()).collect_vec())
+        .collect_vec();
+
+    let expected = preimages
+        .iter()
+        .zip(&g_evals)
+        .map(|(points, evals)| {
+            points
+                .iter()
+                .zip(evals)
+                .map(|(&x, &f_x)| oracle.evaluate(x, f_x, None, None, None))
+                .collect_vec()
+        })
+        .collect_vec();
+
+    assert_eq!(
+        compute_f_oracle_from_g::<Bb, BbExt>(
+            &oracle,
+            g_evals,
+            &queried_indices,
+            &mut domain,
+            log_folding_factor,
+        ),
+        expected
+    );
+}
+
 #[test]
 // Check that verification of a honest proof over the quintic extension of
 // BabyBear with fixed folding factor 2^4 works
@@ -309,165 +476,2991 @@ fn test_gl_verify_variable_folding_factor() {
 }
 
 #[test]
-// Check that the warning "The quotient polynomial is zero" is logged correctly
-// (cf. prover.rs or verifier.rs for more details)
-fn test_verify_zero() {
-    let _ = tracing_subscriber::fmt::try_init();
+// Check that verification of a honest proof works over the quintic extension
+// of Goldilocks, i. e. that the protocol does not depend on the particular
+// extension (the tree contains no two-adic non-binomial extension, so we use
+// one of higher degree instead). Its size allows for 128 bits of security.
+fn test_gl_quintic_verify() {
+    let config = test_gl_quintic_stir_config(
+        BB_EXT_SEC_LEVEL,
+        SecurityAssumption::CapacityBound,
+        12,
+        1,
+        2,
+        3,
+    );
+    test_gl_quintic_verify_with_config(&config);
+}
 
-    // Since we deliberately use a small polynomial to trigger the cancellation
-    // of g_i, we drastically reduce the security level to avoid getting the
-    // unrelated warning "The configuration requires the prover to compute a proof
-    // of work of more than 25 bits"
-    let security_level = 100;
+#[test]
+// Check that honest proofs verify both with the usual duplex challenger and
+// with a byte-oriented, hash-based one (together with a matching MMCS), i. e.
+// that the prover and verifier only rely on the challenger traits
+fn test_bb_verify_challenger_types() {
+    let duplex_config = test_bb_stir_config(
+        BB_EXT_SEC_LEVEL,
+        SecurityAssumption::CapacityBound,
+        12,
+        1,
+        2,
+        3,
+    );
+    test_bb_verify_with_config(&duplex_config);
 
-    let config = test_bb_stir_config(
-        security_level,
-        SecurityAssumption::JohnsonBound,
+    let keccak_config = test_bb_keccak_stir_config(
+        BB_EXT_SEC_LEVEL,
+        SecurityAssumption::CapacityBound,
         12,
         1,
-        4,
+        2,
         3,
     );
-    test_bb_verify_with_config(&config);
+    test_bb_keccak_verify_with_config(&keccak_config);
 }
 
 #[test]
-// Check that proofs can be serialized and deserialized, then verified correctly
-fn test_serialize_deserialize_proof() {
-    let config = test_bb_stir_config(
-        BB_EXT_SEC_LEVEL,
+// Check that verification of a honest proof works over the complex extension
+// of Mersenne31, a base field whose two-adicity is only 1: the evaluation
+// domains live in the extension field, so nothing is required of the base
+// field besides its challenger being able to sample query indices and grind.
+fn test_m31_verify() {
+    let config = test_m31_stir_config(
+        M31_EXT_SEC_LEVEL,
         SecurityAssumption::CapacityBound,
-        10,
+        12,
         1,
         2,
         3,
     );
-    let (proof, commitment) = generate_bb_proof_with_config(&config, &mut test_bb_challenger());
+    test_m31_verify_with_config(&config);
+}
 
-    let serialized_proof = serde_json::to_string(&proof).unwrap();
-    let deserialized_proof: BBProof = serde_json::from_str(&serialized_proof).unwrap();
+#[test]
+// Check that verification of honest proofs works when the fold groups are split
+// over several leaves, and that narrower leaves result in larger proofs
+fn test_bb_verify_leaf_width() {
+    let parameters = StirParameters::constant_folding_factor(
+        (BB_EXT_SEC_LEVEL, SecurityAssumption::CapacityBound),
+        12,
+        1,
+        3,
+        3,
+        20,
+        test_bb_mmcs_config(),
+    );
+
+    let proof_size = |log_leaf_width| {
+        let config =
+            StirConfig::new::<BbExt>(parameters.clone().with_log_leaf_width(log_leaf_width))
+                .unwrap();
+
+        let (proof, commitment) = generate_bb_proof_with_config(&config, &mut test_bb_challenger());
+
+        let proof_size = serde_json::to_string(&proof).unwrap().len();
+
+        assert!(verify(&config, commitment, &proof, &mut test_bb_challenger()).is_ok());
 
+        proof_size
+    };
+
+    assert!(proof_size(1) > proof_size(3));
+}
+
+#[test]
+// Check that proving and verifying work with a non-canonical generator of the
+// initial domain, that the committed evaluations follow the order given by that
+// generator and that the proof is rejected under the canonical generator
+fn test_bb_verify_root_generator() {
+    let (log_starting_degree, log_starting_inv_rate) = (8, 1);
+    let log_size = log_starting_degree + log_starting_inv_rate;
+
+    let parameters = StirParameters::constant_folding_factor(
+        (BB_EXT_SEC_LEVEL, SecurityAssumption::CapacityBound),
+        log_starting_degree,
+        log_starting_inv_rate,
+        2,
+        2,
+        20,
+        test_bb_mmcs_config(),
+    );
+
+    let generator = BbExt::two_adic_generator(log_size).exp_u64(7);
+    let config =
+        StirConfig::new::<BbExt>(parameters.clone().with_root_generator(generator)).unwrap();
+    assert_eq!(config.root_generator::<BbExt>(), generator);
+
+    // The committed evaluations are those at w, w^2, ... for the chosen w
+    let polynomial = rand_poly((1 << log_starting_degree) - 1);
+    let evals = (1..=1 << log_size)
+        .map(|i| polynomial.evaluate(&generator.exp_u64(i)))
+        .collect_vec();
+    let (external_commitment, _) = config
+        .mmcs_config()
+        .commit_matrix(stack_initial_evaluations(&config, evals));
+
+    let (witness, commitment) = commit(&config, polynomial);
+    assert_eq!(commitment, external_commitment);
+
+    let proof = prove(&config, witness, commitment, &mut test_bb_challenger());
+    verify(&config, commitment, &proof, &mut test_bb_challenger()).unwrap();
+
+    let canonical_config = StirConfig::new::<BbExt>(parameters).unwrap();
     assert!(verify(
-        &config,
+        &canonical_config,
         commitment,
-        deserialized_proof,
+        &proof,
         &mut test_bb_challenger()
     )
-    .is_ok());
+    .is_err());
 }
 
 #[test]
-// Check that each possible VerificationError is triggered correctly by
-// producing various dishonest proofs
-fn test_verify_failing_cases() {
-    let mut rng = rng();
-    let config = test_bb_stir_config(
-        BB_EXT_SEC_LEVEL,
-        SecurityAssumption::CapacityBound,
-        10,
+// Check that, with batched folding randomness, the prover derives the folding
+// randomness of each round as r_0^(2^i), that the resulting proof verifies and
+// that it is rejected by a verifier expecting per-round folding randomness
+fn test_bb_verify_batched_folding_randomness() {
+    let parameters = StirParameters::constant_folding_factor(
+        (BB_EXT_SEC_LEVEL, SecurityAssumption::CapacityBound),
+        12,
         1,
         2,
         3,
+        20,
+        test_bb_mmcs_config(),
     );
 
-    // This is the honest proof we will tamper with
-    let (proof, commitment) = generate_bb_proof_with_config(&config, &mut test_bb_challenger());
+    let config =
+        StirConfig::new::<BbExt>(parameters.clone().with_batched_folding_randomness()).unwrap();
+    assert!(config.batched_folding_randomness());
 
-    // ============================== ProofOfWork ==============================
+    let polynomial = rand_poly((1 << config.log_starting_degree()) - 1);
+    let (witness, commitment) = commit(&config, polynomial.clone());
 
-    let mut invalid_proof = proof.clone();
-    invalid_proof.round_proofs[0].pow_witness = rng.random();
+    // Mimic the beginning of the honest prover to check the derived randomness
+    let mut challenger = test_bb_challenger();
+    observe_public_parameters(config.parameters(), &mut challenger);
+    challenger.observe(Bb::from_u8(Messages::Commitment as u8));
+    challenger.observe(commitment);
+    challenger.observe(Bb::from_u8(Messages::FoldingRandomness as u8));
+    let seed: BbExt = challenger.sample_algebra_element();
 
-    assert_eq!(
-        verify(
+    let mut round_witness = StirRoundWitness {
+        domain: witness.domain,
+        polynomial: witness.polynomial,
+        merkle_tree: witness.merkle_tree,
+        round: 0,
+        folding_randomness: seed,
+    };
+
+    for round in 1..=config.num_full_rounds() {
+        round_witness = prove_round(
             &config,
-            commitment,
-            invalid_proof,
-            &mut test_bb_challenger()
-        ),
-        Err(VerificationError::Round(
-            1,
-            FullRoundVerificationError::ProofOfWork
-        ))
-    );
+            round_witness,
+            &mut challenger,
+            &TranscriptFoldChallenge,
+        )
+        .0;
+        assert_eq!(round_witness.folding_randomness, seed.exp_power_of_2(round));
+    }
 
-    // ============================== QueryPath ===============================
+    let (witness, _) = commit(&config, polynomial);
+    let proof = prove(&config, witness, commitment, &mut test_bb_challenger());
+    verify(&config, commitment, &proof, &mut test_bb_challenger()).unwrap();
 
-    let mut invalid_proof = proof.clone();
-    let query_proof = proof.round_proofs[0].query_proofs[0].clone();
-    let mut invalid_leaf = query_proof.0.clone();
-    invalid_leaf[0] = rng.random();
-    let invalid_query_proof = (invalid_leaf, query_proof.1.clone());
-    invalid_proof.round_proofs[0].query_proofs[0] = invalid_query_proof;
+    let unbatched_config = StirConfig::new::<BbExt>(parameters).unwrap();
+    assert!(verify(
+        &unbatched_config,
+        commitment,
+        &proof,
+        &mut test_bb_challenger()
+    )
+    .is_err());
+}
 
+#[test]
+// Check that a positive query slack increases the number of queries of every
+// round without reducing the proof-of-work bits (thus increasing the security
+// afforded by the queries), is shown in the configuration report and still
+// yields proofs which verify
+fn test_bb_verify_query_slack() {
+    let parameters = StirParameters::constant_folding_factor(
+        (BB_EXT_SEC_LEVEL, SecurityAssumption::CapacityBound),
+        12,
+        1,
+        2,
+        3,
+        20,
+        test_bb_mmcs_config(),
+    );
+
+    let config = StirConfig::new::<BbExt>(parameters.clone()).unwrap();
+    let slack_config = StirConfig::new::<BbExt>(parameters.with_query_slack(0.1)).unwrap();
+
+    assert_eq!(slack_config.query_slack(), 0.1);
+    assert!(slack_config.to_string().contains("query slack: 0.1"));
     assert_eq!(
-        verify(
-            &config,
-            commitment,
-            invalid_proof,
-            &mut test_bb_challenger()
-        ),
-        Err(VerificationError::Round(
-            1,
-            FullRoundVerificationError::QueryPath
-        ))
+        slack_config.pow_bits_all_rounds(),
+        config.pow_bits_all_rounds()
     );
 
-    // ========================== AnsPolynomialDegree ==========================
+    let security_assumption = config.security_assumption();
+
+    for (round_config, slack_round_config) in config
+        .round_configs()
+        .iter()
+        .zip(slack_config.round_configs())
+    {
+        assert!(slack_round_config.num_queries > round_config.num_queries);
+        assert!(
+            security_assumption.queries_error(
+                slack_round_config.log_inv_rate,
+                slack_round_config.num_queries
+            ) > security_assumption
+                .queries_error(round_config.log_inv_rate, round_config.num_queries)
+        );
+    }
 
-    let mut invalid_proof = proof.clone();
-    let original_degree = invalid_proof.round_proofs[0]
-        .ans_polynomial
-        .degree()
-        .unwrap();
-    invalid_proof.round_proofs[0].ans_polynomial = rand_poly(original_degree + 1);
+    assert!(slack_config.final_num_queries() > config.final_num_queries());
+
+    let (proof, commitment) =
+        generate_bb_proof_with_config(&slack_config, &mut test_bb_challenger());
+    verify(&slack_config, commitment, &proof, &mut test_bb_challenger()).unwrap();
+}
+
+#[test]
+// Check that a round of a query/proof-of-work schedule may make fewer queries
+// than dictated by the security analysis if its proof-of-work bits compensate
+// for the larger query error, in which case the configuration honours the
+// schedule, still meets the security level and yields proofs which verify
+fn test_bb_verify_query_pow_schedule() {
+    let parameters = StirParameters::constant_folding_factor(
+        (BB_EXT_SEC_LEVEL_LOWER, SecurityAssumption::CapacityBound),
+        12,
+        1,
+        2,
+        3,
+        16,
+        test_bb_mmcs_config(),
+    );
+
+    let config = StirConfig::new::<BbExt>(parameters.clone()).unwrap();
+
+    let mut schedule = config
+        .round_configs()
+        .iter()
+        .map(|round_config| (round_config.num_queries, round_config.pow_bits))
+        .chain([(config.final_num_queries(), config.final_pow_bits())])
+        .collect_vec();
 
+    // Each query of round 2 (with inverse rate 4) contributes less than 2 bits
+    // of security, so 4 extra bits of grinding make up for 2 fewer queries
+    let (num_queries, pow_bits) = schedule[1];
+    schedule[1] = (num_queries - 2, pow_bits + 4);
+
+    let scheduled_config =
+        StirConfig::new::<BbExt>(parameters.with_query_pow_schedule(schedule.clone())).unwrap();
+
+    let round_config = &scheduled_config.round_configs()[1];
     assert_eq!(
-        verify(
-            &config,
-            commitment,
-            invalid_proof,
-            &mut test_bb_challenger()
-        ),
-        Err(VerificationError::Round(
-            1,
-            FullRoundVerificationError::AnsPolynomialDegree
-        ))
+        (round_config.num_queries, round_config.pow_bits),
+        (num_queries - 2, pow_bits + 4)
     );
 
-    // ======================= AnsPolynomialEvaluations =======================
+    // The security level is still met according to the security report: the
+    // query error plus the grinding reach it
+    let security_assumption = scheduled_config.security_assumption();
+    assert!(
+        security_assumption.queries_error(round_config.log_inv_rate, round_config.num_queries)
+            + round_config.pow_bits as f64
+            >= BB_EXT_SEC_LEVEL_LOWER as f64
+    );
 
-    let mut invalid_proof = proof.clone();
-    let original_degree = invalid_proof.round_proofs[0]
-        .ans_polynomial
-        .degree()
-        .unwrap();
-    invalid_proof.round_proofs[0].ans_polynomial = rand_poly(original_degree);
+    let (proof, commitment) =
+        generate_bb_proof_with_config(&scheduled_config, &mut test_bb_challenger());
+    verify(
+        &scheduled_config,
+        commitment,
+        &proof,
+        &mut test_bb_challenger(),
+    )
+    .unwrap();
+}
+
+#[test]
+// Check that fixing two out-of-domain samples per round is reflected in the
+// configuration and the proof, and that the resulting proof verifies
+fn test_bb_verify_num_ood_samples() {
+    let parameters = StirParameters::constant_folding_factor(
+        (BB_EXT_SEC_LEVEL, SecurityAssumption::CapacityBound),
+        12,
+        1,
+        2,
+        3,
+        16,
+        test_bb_mmcs_config(),
+    );
+
+    let config = StirConfig::new::<BbExt>(parameters.with_num_ood_samples(vec![2, 2])).unwrap();
+
+    assert_eq!(config.num_ood_samples(1), 2);
+    assert_eq!(config.num_ood_samples(2), 2);
+
+    let (proof, commitment) = generate_bb_proof_with_config(&config, &mut test_bb_challenger());
+
+    assert!(proof
+        .round_proofs
+        .iter()
+        .all(|round_proof| round_proof.betas.len() == 2));
+
+    verify(&config, commitment, &proof, &mut test_bb_challenger()).unwrap();
+}
+
+#[test]
+// Check that a proof produced with a given number of out-of-domain samples per
+// round is rejected by a verifier expecting a different one
+fn test_bb_verify_num_ood_samples_mismatch() {
+    let parameters = StirParameters::constant_folding_factor(
+        (BB_EXT_SEC_LEVEL, SecurityAssumption::CapacityBound),
+        12,
+        1,
+        2,
+        3,
+        16,
+        test_bb_mmcs_config(),
+    );
+
+    let prover_config =
+        StirConfig::new::<BbExt>(parameters.clone().with_num_ood_samples(vec![2, 2])).unwrap();
+    let verifier_config =
+        StirConfig::new::<BbExt>(parameters.with_num_ood_samples(vec![3, 3])).unwrap();
+
+    let (mut proof, commitment) =
+        generate_bb_proof_with_config(&prover_config, &mut test_bb_challenger());
+
+    // Forge the parameter fingerprint, which would otherwise reject the proof
+    // before the out-of-domain replies are looked at
+    proof.parameter_fingerprint = verifier_config.parameter_fingerprint();
 
     assert_eq!(
         verify(
-            &config,
+            &verifier_config,
             commitment,
-            invalid_proof,
+            &proof,
             &mut test_bb_challenger()
         ),
         Err(VerificationError::Round(
             1,
-            FullRoundVerificationError::AnsPolynomialEvaluations
+            FullRoundVerificationError::BetaCountMismatch {
+                expected: 3,
+                got: 2
+            }
         ))
     );
+}
 
-    // ========================= FinalPolynomialDegree =========================
-
-    let mut invalid_proof = proof.clone();
-    let original_degree = invalid_proof.final_polynomial.degree().unwrap();
-    invalid_proof.final_polynomial = rand_poly(original_degree + 1);
+#[test]
+// Check that a proof produced under a configuration is rejected by a verifier
+// with a different one because of their parameter fingerprints, and that
+// fingerprints only depend on the parameters
+fn test_verify_parameter_mismatch() {
+    let parameters = StirParameters::constant_folding_factor(
+        (BB_EXT_SEC_LEVEL, SecurityAssumption::CapacityBound),
+        12,
+        1,
+        2,
+        3,
+        16,
+        test_bb_mmcs_config(),
+    );
+
+    let config = StirConfig::new::<BbExt>(parameters.clone()).unwrap();
+    let other_config = StirConfig::new::<BbExt>(StirParameters::variable_folding_factor(
+        (BB_EXT_SEC_LEVEL, SecurityAssumption::CapacityBound),
+        12,
+        1,
+        vec![3, 2, 2],
+        16,
+        test_bb_mmcs_config(),
+    ))
+    .unwrap();
+
+    assert_eq!(
+        config.parameter_fingerprint(),
+        StirConfig::new::<BbExt>(parameters)
+            .unwrap()
+            .parameter_fingerprint()
+    );
+    assert_ne!(
+        config.parameter_fingerprint(),
+        other_config.parameter_fingerprint()
+    );
+
+    let (proof, commitment) = generate_bb_proof_with_config(&config, &mut test_bb_challenger());
+
+    assert_eq!(
+        verify(&other_config, commitment, &proof, &mut test_bb_challenger()),
+        Err(VerificationError::ParameterMismatch {
+            expected: other_config.parameter_fingerprint(),
+            got: config.parameter_fingerprint()
+        })
+    );
+}
+
+#[test]
+// Check that truncating a configuration keeps its first rounds, makes the
+// degree bound of the last kept round the final one and yields a configuration
+// which proves and verifies
+fn test_bb_verify_truncated_rounds() {
+    let log_folding_factors = vec![2, 3, 2, 2];
+    let config = test_bb_stir_config_folding_factors(
+        BB_EXT_SEC_LEVEL,
+        SecurityAssumption::CapacityBound,
+        12,
+        1,
+        log_folding_factors.clone(),
+    );
+
+    for n in 1..=config.total_rounds() {
+        let truncated_config = config.truncate_rounds::<BbExt>(n);
+
+        assert_eq!(truncated_config.total_rounds(), n);
+        let summary = |round_configs: &[RoundConfig]| {
+            round_configs
+                .iter()
+                .map(|rc| (rc.log_folding_factor, rc.num_queries, rc.pow_bits))
+                .collect_vec()
+        };
+        assert_eq!(
+            summary(truncated_config.round_configs()),
+            summary(&config.round_configs()[..n - 1])
+        );
+        assert_eq!(
+            truncated_config.final_degree_bound(),
+            1 << (config.log_starting_degree() - log_folding_factors[..n].iter().sum::<usize>())
+        );
+    }
+
+    let truncated_config = config.truncate_rounds::<BbExt>(2);
+    let (proof, commitment) =
+        generate_bb_proof_with_config(&truncated_config, &mut test_bb_challenger());
+    verify(
+        &truncated_config,
+        commitment,
+        &proof,
+        &mut test_bb_challenger(),
+    )
+    .unwrap();
+}
+
+#[test]
+// Check that a lower bound on the size of the domains drops the full rounds
+// whose domain would be smaller than it, as truncate_rounds does, and that the
+// resulting configuration proves and verifies
+fn test_bb_verify_min_log_domain() {
+    let parameters = StirParameters::constant_folding_factor(
+        (BB_EXT_SEC_LEVEL, SecurityAssumption::CapacityBound),
+        12,
+        1,
+        2,
+        5,
+        20,
+        test_bb_mmcs_config(),
+    );
+    let config = StirConfig::new::<BbExt>(parameters.clone()).unwrap();
+
+    // The domains L_1, ..., L_4 of the full rounds have log sizes 12 down to 9
+    assert_eq!(config.num_full_rounds(), 4);
+
+    let bounded_config =
+        StirConfig::new::<BbExt>(parameters.clone().with_min_log_domain(11)).unwrap();
+
+    assert_eq!(bounded_config.num_full_rounds(), 2);
+    assert_eq!(bounded_config.log_stopping_degree(), 6);
+    assert!(bounded_config
+        .round_configs()
+        .iter()
+        .all(|round_config| round_config.log_evaluation_domain_size >= 11));
+    assert_eq!(
+        bounded_config.parameter_fingerprint(),
+        config.truncate_rounds::<BbExt>(3).parameter_fingerprint()
+    );
+
+    // A bound below all domains has no effect
+    let loose_config = StirConfig::new::<BbExt>(parameters.with_min_log_domain(9)).unwrap();
+    assert_eq!(loose_config.num_full_rounds(), 4);
+
+    let (proof, commitment) =
+        generate_bb_proof_with_config(&bounded_config, &mut test_bb_challenger());
+    verify(
+        &bounded_config,
+        commitment,
+        &proof,
+        &mut test_bb_challenger(),
+    )
+    .unwrap();
+}
+
+#[test]
+#[should_panic = "The number of rounds to keep must be between 1 and 3, got 4"]
+// Check that truncating a configuration to more rounds than it has is rejected
+fn test_truncate_rounds_too_many() {
+    let config = test_bb_stir_config(
+        BB_EXT_SEC_LEVEL,
+        SecurityAssumption::CapacityBound,
+        10,
+        1,
+        2,
+        3,
+    );
+
+    config.truncate_rounds::<BbExt>(4);
+}
+
+#[test]
+// Check that the dimensions of the queried matrices derived from the
+// configuration match the openings contained in each round of the proof, both
+// with full-width leaves and with leaves split over several rows
+fn test_queried_matrix_dimensions() {
+    let parameters = StirParameters::variable_folding_factor(
+        (BB_EXT_SEC_LEVEL, SecurityAssumption::CapacityBound),
+        12,
+        1,
+        vec![3, 2, 3],
+        20,
+        test_bb_mmcs_config(),
+    );
+
+    for parameters in [parameters.clone(), parameters.with_log_leaf_width(1)] {
+        let config = StirConfig::new::<BbExt>(parameters).unwrap();
+        let (proof, _) = generate_bb_proof_with_config(&config, &mut test_bb_challenger());
+
+        let dimensions = proof.queried_matrix_dimensions(&config);
+        assert_eq!(dimensions.len(), config.total_rounds());
+
+        let round_queries = proof
+            .round_proofs
+            .iter()
+            .map(|round_proof| &round_proof.query_proofs)
+            .chain([&proof.final_round_queries]);
+
+        for ((dimensions, queries), (i, &log_folding_factor)) in dimensions
+            .into_iter()
+            .zip(round_queries)
+            .zip(config.log_folding_factors().iter().enumerate())
+        {
+            // The matrix contains all evaluations over L_i
+            assert_eq!(
+                dimensions.width * dimensions.height,
+                1 << (config.starting_domain_log_size() - i)
+            );
+
+            // Each query opens the rows containing one fold group
+            let num_rows = (1 << log_folding_factor) / dimensions.width;
+            for (evals, proofs) in queries {
+                assert_eq!(proofs.len(), num_rows);
+                assert_eq!(evals.len(), num_rows * dimensions.width);
+            }
+        }
+    }
+}
+
+#[test]
+// Check that the round-proof views of a proof are in round order and that their
+// counts match the configuration
+fn test_round_proofs_iter() {
+    let config = test_bb_stir_config_folding_factors(
+        BB_EXT_SEC_LEVEL,
+        SecurityAssumption::CapacityBound,
+        12,
+        1,
+        vec![3, 2, 3],
+    );
+    let (proof, _) = generate_bb_proof_with_config(&config, &mut test_bb_challenger());
+
+    let views = proof.round_proofs_iter().collect_vec();
+    assert_eq!(views.len(), config.num_full_rounds());
+
+    for ((i, view), round_config) in views.iter().enumerate().zip(config.round_configs()) {
+        assert_eq!(view.round(), i + 1);
+        assert_eq!(view.g_root(), &proof.round_proofs[i].g_root);
+        assert_eq!(view.betas().len(), round_config.num_ood_samples);
+        assert!((1..=round_config.num_queries).contains(&view.num_queries()));
+        assert!(view
+            .ans_polynomial_degree()
+            .is_none_or(|d| d < round_config.num_ood_samples + view.num_queries()));
+    }
+}
+
+#[test]
+// Check that the degree of Ans_i reported by a round-proof view disregards the
+// leading zeros a deserialized proof may contain, including when all of its
+// coefficients are zero
+fn test_round_proof_view_padded_ans_polynomial() {
+    let config = test_bb_stir_config(
+        BB_EXT_SEC_LEVEL,
+        SecurityAssumption::CapacityBound,
+        10,
+        1,
+        2,
+        3,
+    );
+    let (proof, _) = generate_bb_proof_with_config(&config, &mut test_bb_challenger());
+    let degree = proof
+        .round_proofs_iter()
+        .next()
+        .unwrap()
+        .ans_polynomial_degree();
+
+    let zero_coeff = serde_json::to_value(BbExt::ZERO).unwrap();
+    let mut serialized_proof = serde_json::to_value(&proof).unwrap();
+    let coeffs = serialized_proof["round_proofs"][0]["ans_polynomial"]["coeffs"]
+        .as_array_mut()
+        .unwrap();
+
+    // Padding Ans_1 with zero coefficients does not change its degree
+    coeffs.extend(iter::repeat_n(zero_coeff.clone(), 3));
+    let padded_proof: BBProof = serde_json::from_value(serialized_proof.clone()).unwrap();
+    let view = padded_proof.round_proofs_iter().next().unwrap();
+    assert_eq!(view.ans_polynomial_degree(), degree);
+
+    // A non-empty list of zero coefficients is the zero polynomial
+    serialized_proof["round_proofs"][0]["ans_polynomial"]["coeffs"] =
+        serde_json::Value::Array(vec![zero_coeff; 4]);
+    let zero_proof: BBProof = serde_json::from_value(serialized_proof).unwrap();
+    let view = zero_proof.round_proofs_iter().next().unwrap();
+    assert_eq!(view.ans_polynomial_degree(), None);
+}
+
+#[test]
+// Check that verification of honest proofs works over the in-memory MockMmcs
+// for small degrees, both with fixed and variable folding factors
+fn test_bb_mock_verify() {
+    for (log_starting_degree, log_folding_factor, num_rounds) in [(6, 1, 2), (8, 2, 3), (9, 3, 2)] {
+        let config = test_bb_mock_stir_config(
+            BB_EXT_SEC_LEVEL,
+            SecurityAssumption::CapacityBound,
+            log_starting_degree,
+            1,
+            log_folding_factor,
+            num_rounds,
+        );
+        test_bb_mock_verify_with_config(&config);
+    }
+}
+
+#[test]
+// Check that tampering with the openings or with the commitment is detected
+// when using the in-memory MockMmcs
+fn test_bb_mock_tampering() {
+    let config = test_bb_mock_stir_config(
+        BB_EXT_SEC_LEVEL,
+        SecurityAssumption::CapacityBound,
+        8,
+        1,
+        2,
+        3,
+    );
+
+    let (proof, commitment) =
+        generate_bb_mock_proof_with_config(&config, &mut test_bb_challenger());
+
+    // Opened evaluations of f_0
+    let mut invalid_proof = proof.clone();
+    invalid_proof.round_proofs[0].query_proofs[0].0[0] += BbExt::ONE;
+
+    assert_eq!(
+        verify(
+            &config,
+            commitment.clone(),
+            &invalid_proof,
+            &mut test_bb_challenger()
+        ),
+        Err(VerificationError::Round(
+            1,
+            FullRoundVerificationError::QueryPath
+        ))
+    );
+
+    // Opened evaluations of g_M
+    let mut invalid_proof = proof.clone();
+    invalid_proof.final_round_queries[0].0[0] += BbExt::ONE;
+
+    assert_eq!(
+        verify(
+            &config,
+            commitment.clone(),
+            &invalid_proof,
+            &mut test_bb_challenger()
+        ),
+        Err(VerificationError::FinalQueryPath)
+    );
+
+    // A commitment to a different codeword changes the transcript, which in
+    // turn invalidates the proof
+    let mut invalid_commitment = commitment.clone();
+    invalid_commitment[0][0] += Bb::ONE;
+
+    assert!(verify(
+        &config,
+        invalid_commitment,
+        &proof,
+        &mut test_bb_challenger()
+    )
+    .is_err());
+}
+
+#[test]
+// Check that the verifier accepts final polynomials of degree up to (but
+// excluding) the final degree bound and rejects those reaching it
+fn test_verify_final_degree_bound() {
+    let config = test_bb_stir_config(
+        BB_EXT_SEC_LEVEL,
+        SecurityAssumption::CapacityBound,
+        10,
+        1,
+        2,
+        3,
+    );
+
+    let (proof, commitment) = generate_bb_proof_with_config(&config, &mut test_bb_challenger());
+
+    // The largest admissible degree passes the degree check (and only fails
+    // later because the polynomial is not the correct one)
+    let mut invalid_proof = proof.clone();
+    invalid_proof.final_polynomial = rand_poly(config.final_degree_bound() - 1);
+
+    assert!(matches!(
+        verify(
+            &config,
+            commitment,
+            &invalid_proof,
+            &mut test_bb_challenger()
+        ),
+        Err(e) if e != VerificationError::FinalPolynomialDegree
+    ));
+
+    let mut invalid_proof = proof;
+    invalid_proof.final_polynomial = rand_poly(config.final_degree_bound());
+
+    assert_eq!(
+        verify(
+            &config,
+            commitment,
+            &invalid_proof,
+            &mut test_bb_challenger()
+        ),
+        Err(VerificationError::FinalPolynomialDegree)
+    );
+}
+
+#[test]
+// Check that the warning "The quotient polynomial is zero" is logged correctly
+// (cf. prover.rs or verifier.rs for more details)
+fn test_verify_zero() {
+    let _ = tracing_subscriber::fmt::try_init();
+
+    // Since we deliberately use a small polynomial to trigger the cancellation
+    // of g_i, we drastically reduce the security level to avoid getting the
+    // unrelated warning "The configuration requires the prover to compute a proof
+    // of work of more than 25 bits"
+    let security_level = 100;
+
+    let config = test_bb_stir_config(
+        security_level,
+        SecurityAssumption::JohnsonBound,
+        12,
+        1,
+        4,
+        3,
+    );
+    test_bb_verify_with_config(&config);
+}
+
+#[test]
+// Check that the same proof can be verified twice with two fresh challengers,
+// which end up in the same state, and inspected afterwards
+fn test_verify_twice() {
+    let config = test_bb_stir_config(
+        BB_EXT_SEC_LEVEL,
+        SecurityAssumption::CapacityBound,
+        10,
+        1,
+        2,
+        3,
+    );
+    let (proof, commitment) = generate_bb_proof_with_config(&config, &mut test_bb_challenger());
+
+    let mut first_challenger = test_bb_challenger();
+    let mut second_challenger = test_bb_challenger();
+
+    assert!(verify(&config, commitment, &proof, &mut first_challenger).is_ok());
+    assert!(verify(&config, commitment, &proof, &mut second_challenger).is_ok());
+
+    assert_eq!(
+        first_challenger.sample_algebra_element::<BbExt>(),
+        second_challenger.sample_algebra_element::<BbExt>()
+    );
+
+    // The proof is still available
+    assert_eq!(proof.round_proofs.len(), config.num_full_rounds());
+}
+
+#[test]
+// Check that the total number of rounds exceeds by one the number of round
+// commitments (to g_1, ..., g_M) in a proof, i. e. the number of full rounds
+fn test_total_rounds() {
+    for num_rounds in 1..=4 {
+        let config = test_bb_stir_config(
+            BB_EXT_SEC_LEVEL,
+            SecurityAssumption::CapacityBound,
+            10,
+            1,
+            2,
+            num_rounds,
+        );
+        let (proof, _) = generate_bb_proof_with_config(&config, &mut test_bb_challenger());
+
+        let round_commitments = proof
+            .round_proofs
+            .iter()
+            .map(|round_proof| round_proof.g_root)
+            .collect_vec();
+
+        assert_eq!(config.total_rounds(), num_rounds);
+        assert_eq!(config.total_rounds(), round_commitments.len() + 1);
+        assert_eq!(config.num_full_rounds(), round_commitments.len());
+    }
+}
+
+#[test]
+// Check that validate_shape accepts an honest proof and detects each structural
+// violation: a missing round, a surplus beta, an Ans_i or shake polynomial of
+// too high a degree and a final polynomial of too high a degree
+fn test_validate_shape() {
+    let config = test_bb_stir_config(
+        BB_EXT_SEC_LEVEL,
+        SecurityAssumption::CapacityBound,
+        10,
+        1,
+        2,
+        3,
+    );
+    let (proof, _) = generate_bb_proof_with_config(&config, &mut test_bb_challenger());
+
+    assert_eq!(proof.validate_shape(&config), Ok(()));
+
+    let RoundConfig {
+        num_ood_samples,
+        num_queries,
+        ..
+    } = config.round_configs()[0].clone();
+    let max_num_points = num_ood_samples + num_queries;
+
+    // Polynomial of the given degree
+    let poly_of_degree = |d: usize| Polynomial::from_coeffs(vec![BbExt::ONE; d + 1]);
+
+    let mut tampered_proof = proof.clone();
+    tampered_proof.round_proofs.pop();
+    assert_eq!(
+        tampered_proof.validate_shape(&config),
+        Err(ShapeError::NumRounds {
+            expected: 2,
+            got: 1
+        })
+    );
+
+    let mut tampered_proof = proof.clone();
+    tampered_proof.round_proofs[0].betas.push(BbExt::ONE);
+    assert_eq!(
+        tampered_proof.validate_shape(&config),
+        Err(ShapeError::NumBetas {
+            round: 1,
+            expected: num_ood_samples,
+            got: num_ood_samples + 1
+        })
+    );
+
+    let mut tampered_proof = proof.clone();
+    tampered_proof.round_proofs[0].ans_polynomial = poly_of_degree(max_num_points);
+    assert_eq!(
+        tampered_proof.validate_shape(&config),
+        Err(ShapeError::AnsPolynomialDegree { round: 1 })
+    );
+
+    #[cfg(not(feature = "no-shake"))]
+    {
+        let mut tampered_proof = proof.clone();
+        tampered_proof.round_proofs[0].shake_polynomial = poly_of_degree(max_num_points - 1);
+        assert_eq!(
+            tampered_proof.validate_shape(&config),
+            Err(ShapeError::ShakePolynomialDegree { round: 1 })
+        );
+    }
+
+    let mut tampered_proof = proof;
+    tampered_proof.final_polynomial = poly_of_degree(config.final_degree_bound());
+    assert_eq!(
+        tampered_proof.validate_shape(&config),
+        Err(ShapeError::FinalPolynomialDegree)
+    );
+}
+
+#[test]
+#[cfg(not(feature = "no-shake"))]
+// Check that, without the no-shake feature, each round proof carries a shake
+// polynomial which is part of the serialized proof and against which the
+// evaluations of Ans_i are checked
+fn test_verify_with_shake_polynomial() {
+    let config = test_bb_stir_config(
+        BB_EXT_SEC_LEVEL,
+        SecurityAssumption::CapacityBound,
+        10,
+        1,
+        2,
+        3,
+    );
+    let (proof, commitment) = generate_bb_proof_with_config(&config, &mut test_bb_challenger());
+
+    verify(&config, commitment, &proof, &mut test_bb_challenger()).unwrap();
+    assert!(serde_json::to_string(&proof)
+        .unwrap()
+        .contains("shake_polynomial"));
+
+    let mut invalid_proof = proof;
+    invalid_proof.round_proofs[0].shake_polynomial =
+        &invalid_proof.round_proofs[0].shake_polynomial + &Polynomial::constant(BbExt::ONE);
+
+    assert_eq!(
+        verify(
+            &config,
+            commitment,
+            &invalid_proof,
+            &mut test_bb_challenger()
+        ),
+        Err(VerificationError::Round(
+            1,
+            FullRoundVerificationError::AnsPolynomialEvaluations
+        ))
+    );
+}
+
+#[test]
+#[cfg(feature = "no-shake")]
+// Check that, with the no-shake feature, round proofs carry no shake
+// polynomial, honest proofs are accepted (also within the limits derived from
+// the configuration) and a folded value inconsistent with Ans_i is detected by
+// evaluating the latter directly
+fn test_verify_no_shake() {
+    let config = test_bb_stir_config(
+        BB_EXT_SEC_LEVEL,
+        SecurityAssumption::CapacityBound,
+        10,
+        1,
+        2,
+        3,
+    );
+    let (proof, commitment) = generate_bb_proof_with_config(&config, &mut test_bb_challenger());
+
+    verify(&config, commitment, &proof, &mut test_bb_challenger()).unwrap();
+    verify_with_limits(
+        &config,
+        commitment,
+        &proof,
+        VerificationLimits::from_config(&config),
+        &mut test_bb_challenger(),
+    )
+    .unwrap();
+    assert!(!serde_json::to_string(&proof)
+        .unwrap()
+        .contains("shake_polynomial"));
+
+    let mut invalid_proof = proof;
+    invalid_proof.round_proofs[0].query_proofs[1].0[0] += BbExt::ONE;
+
+    let failure = verify_diagnostic(
+        &config,
+        commitment,
+        &invalid_proof,
+        &mut test_bb_challenger(),
+    )
+    .unwrap_err();
+
+    assert_eq!(
+        failure.error,
+        VerificationError::Round(1, FullRoundVerificationError::AnsPolynomialEvaluations)
+    );
+    assert_eq!(failure.mismatch.unwrap().query, 1);
+}
+
+#[test]
+// Check that is_compatible_with accepts a proof for its own configuration and
+// rejects it for configurations with a different number of rounds, degree
+// bound, number of queries, folding factor or leaf width, as well as when its
+// openings are tampered with
+fn test_is_compatible_with() {
+    let config = test_bb_stir_config(
+        BB_EXT_SEC_LEVEL,
+        SecurityAssumption::CapacityBound,
+        10,
+        1,
+        2,
+        3,
+    );
+    let (proof, _) = generate_bb_proof_with_config(&config, &mut test_bb_challenger());
+
+    assert!(proof.is_compatible_with(&config));
+
+    let incompatible_configs = [
+        // Different number of rounds
+        test_bb_stir_config(
+            BB_EXT_SEC_LEVEL,
+            SecurityAssumption::CapacityBound,
+            10,
+            1,
+            2,
+            2,
+        ),
+        // Different degree bound
+        test_bb_stir_config(
+            BB_EXT_SEC_LEVEL,
+            SecurityAssumption::CapacityBound,
+            8,
+            1,
+            2,
+            3,
+        ),
+        // Fewer queries
+        test_bb_stir_config(
+            BB_EXT_SEC_LEVEL_LOWER,
+            SecurityAssumption::CapacityBound,
+            10,
+            1,
+            2,
+            3,
+        ),
+        // Different folding factor (and hence degree bound)
+        test_bb_stir_config(
+            BB_EXT_SEC_LEVEL,
+            SecurityAssumption::CapacityBound,
+            10,
+            1,
+            1,
+            3,
+        ),
+        // Narrower leaves
+        StirConfig::new::<BbExt>(config.parameters().clone().with_log_leaf_width(1)).unwrap(),
+    ];
+
+    for incompatible_config in &incompatible_configs {
+        assert!(!proof.is_compatible_with(incompatible_config));
+    }
+
+    let mut tampered_proof = proof.clone();
+    tampered_proof.final_round_queries.clear();
+    assert!(!tampered_proof.is_compatible_with(&config));
+
+    let mut tampered_proof = proof.clone();
+    tampered_proof.round_proofs[1].query_proofs[0].0.pop();
+    assert!(!tampered_proof.is_compatible_with(&config));
+
+    let mut tampered_proof = proof;
+    tampered_proof.round_proofs[0].query_proofs[0].1.clear();
+    assert!(!tampered_proof.is_compatible_with(&config));
+}
+
+#[test]
+// Check that challengers built from the same seed bytes produce identical
+// sample sequences (also after observing the same values), that different
+// seeds produce different ones and that a proof produced with a challenger
+// from a seed is accepted by a verifier using a fresh challenger from it
+fn test_challenger_from_seed() {
+    let seed = b"stir conformance seed";
+
+    let mut first = challenger_from_seed(seed);
+    let mut second = challenger_from_seed(seed);
+
+    let first_samples: Vec<Bb> = first.sample_vec(20);
+    assert_eq!(first_samples, second.sample_vec(20));
+
+    first.observe(Bb::from_u8(7));
+    second.observe(Bb::from_u8(7));
+    assert_eq!(
+        first.sample_algebra_element::<BbExt>(),
+        second.sample_algebra_element::<BbExt>()
+    );
+    assert_eq!(first.sample_bits(10), second.sample_bits(10));
+
+    let other_samples: Vec<Bb> = challenger_from_seed(b"another seed").sample_vec(20);
+    assert_ne!(first_samples, other_samples);
+
+    // The test challengers are built from the little-endian bytes of their
+    // integer seed
+    let test_samples: Vec<Bb> = test_bb_challenger_from_seed(42).sample_vec(20);
+    let bytes_samples: Vec<Bb> = challenger_from_seed(&42_u64.to_le_bytes()).sample_vec(20);
+    assert_eq!(test_samples, bytes_samples);
+
+    let config = test_bb_stir_config(
+        BB_EXT_SEC_LEVEL,
+        SecurityAssumption::CapacityBound,
+        10,
+        1,
+        2,
+        3,
+    );
+
+    let (proof, commitment) =
+        generate_bb_proof_with_config(&config, &mut challenger_from_seed(seed));
+    verify(&config, commitment, &proof, &mut challenger_from_seed(seed)).unwrap();
+}
+
+#[test]
+// Check that verifying with a challenger built from a seed is reproducible:
+// repeated runs with the same seed agree on both honest (accepted) and tampered
+// (rejected) proofs, a prover targeting the seed produces accepted proofs and a
+// proof targeting a different seed is rejected
+fn test_verify_deterministic() {
+    let config = test_bb_stir_config(
+        BB_EXT_SEC_LEVEL,
+        SecurityAssumption::CapacityBound,
+        10,
+        1,
+        2,
+        3,
+    );
+
+    let seed = 42;
+    let (proof, commitment) =
+        generate_bb_proof_with_config(&config, &mut test_bb_challenger_from_seed(seed));
+
+    assert_eq!(
+        verify_deterministic(&config, commitment, &proof, seed),
+        Ok(())
+    );
+    assert_eq!(
+        verify_deterministic(&config, commitment, &proof, seed),
+        Ok(())
+    );
+    assert!(verify_deterministic(&config, commitment, &proof, seed + 1).is_err());
+
+    // test_bb_challenger() is built from the seed 0
+    let (tampered_proof, tampered_commitment) = tamper_with_final_polynomial(&config);
+    let first_outcome = verify_deterministic(&config, tampered_commitment, &tampered_proof, 0);
+    let second_outcome = verify_deterministic(&config, tampered_commitment, &tampered_proof, 0);
+
+    assert!(first_outcome.is_err());
+    assert_eq!(first_outcome, second_outcome);
+}
+
+#[test]
+// Check that proofs can be serialized and deserialized, then verified correctly
+fn test_serialize_deserialize_proof() {
+    let config = test_bb_stir_config(
+        BB_EXT_SEC_LEVEL,
+        SecurityAssumption::CapacityBound,
+        10,
+        1,
+        2,
+        3,
+    );
+    let (proof, commitment) = generate_bb_proof_with_config(&config, &mut test_bb_challenger());
+
+    let serialized_proof = serde_json::to_string(&proof).unwrap();
+    let deserialized_proof: BBProof = serde_json::from_str(&serialized_proof).unwrap();
+
+    assert!(verify(
+        &config,
+        commitment,
+        &deserialized_proof,
+        &mut test_bb_challenger()
+    )
+    .is_ok());
+}
+
+#[cfg(feature = "std")]
+#[test]
+// Check that a proof encoded with encode_v1 can be verified while streaming it
+// from an in-memory cursor and decoded in full, and that truncated encodings
+// and encodings with trailing data are rejected
+fn test_verify_from_reader() {
+    use std::io::Cursor;
+
+    use crate::verifier::verify_from_reader;
+
+    let config = test_bb_stir_config(
+        BB_EXT_SEC_LEVEL,
+        SecurityAssumption::CapacityBound,
+        10,
+        1,
+        2,
+        3,
+    );
+    let (proof, commitment) = generate_bb_proof_with_config(&config, &mut test_bb_challenger());
+
+    let mut encoding = Vec::new();
+    proof.encode_v1(&mut encoding).unwrap();
+
+    let (mut streaming_challenger, mut in_memory_challenger) =
+        (test_bb_challenger(), test_bb_challenger());
+
+    verify_from_reader(
+        &config,
+        commitment,
+        Cursor::new(&encoding),
+        &mut streaming_challenger,
+    )
+    .unwrap();
+
+    let decoded_proof = BBProof::decode_v1(Cursor::new(&encoding)).unwrap();
+    verify(
+        &config,
+        commitment,
+        &decoded_proof,
+        &mut in_memory_challenger,
+    )
+    .unwrap();
+
+    assert_eq!(
+        streaming_challenger.sample_algebra_element::<BbExt>(),
+        in_memory_challenger.sample_algebra_element::<BbExt>()
+    );
+
+    let truncated_encoding = &encoding[..encoding.len() / 2];
+    assert_eq!(
+        verify_from_reader(
+            &config,
+            commitment,
+            Cursor::new(truncated_encoding),
+            &mut test_bb_challenger()
+        ),
+        Err(VerificationError::Decoding)
+    );
+
+    encoding.extend_from_slice(b"0\n");
+    assert_eq!(
+        verify_from_reader(
+            &config,
+            commitment,
+            Cursor::new(&encoding),
+            &mut test_bb_challenger()
+        ),
+        Err(VerificationError::Decoding)
+    );
+}
+
+#[cfg(feature = "std")]
+#[test]
+// Check that the per-round sizes of a proof, one per full round plus that of
+// the final round, add up to its encoded size minus the header of the encoding
+fn test_per_round_sizes() {
+    let config = test_bb_stir_config(
+        BB_EXT_SEC_LEVEL,
+        SecurityAssumption::CapacityBound,
+        12,
+        1,
+        2,
+        3,
+    );
+    let (proof, _) = generate_bb_proof_with_config(&config, &mut test_bb_challenger());
+
+    let sizes = proof.per_round_sizes();
+    assert_eq!(sizes.len(), config.total_rounds());
+    assert!(sizes.iter().all(|&size| size > 0));
+
+    // Format version, parameter fingerprint and number of rounds
+    let header_size = [
+        BBProof::FORMAT_VERSION.to_string(),
+        config.parameter_fingerprint().to_string(),
+        config.num_full_rounds().to_string(),
+    ]
+    .iter()
+    .map(|value| value.len() + 1)
+    .sum::<usize>();
+
+    assert_eq!(
+        sizes.iter().sum::<usize>() + header_size,
+        proof.size_in_bytes()
+    );
+}
+
+#[cfg(feature = "std")]
+#[test]
+// Check that a proof converted into bytes and back through the conversion traits
+// is unchanged and still verifies, and that malformed bytes are rejected
+fn test_proof_byte_conversions() {
+    let config = test_bb_stir_config(
+        BB_EXT_SEC_LEVEL,
+        SecurityAssumption::CapacityBound,
+        10,
+        1,
+        2,
+        3,
+    );
+    let (proof, commitment) = generate_bb_proof_with_config(&config, &mut test_bb_challenger());
+
+    let bytes = Vec::from(&proof);
+
+    let mut encoding = Vec::new();
+    proof.encode_v1(&mut encoding).unwrap();
+    assert_eq!(bytes, encoding);
+
+    let decoded_proof = BBProof::try_from(bytes.as_slice()).unwrap();
+    assert_eq!(Vec::from(&decoded_proof), bytes);
+    verify(
+        &config,
+        commitment,
+        &decoded_proof,
+        &mut test_bb_challenger(),
+    )
+    .unwrap();
+
+    assert!(BBProof::try_from(&bytes[..bytes.len() / 2]).is_err());
+    assert!(BBProof::try_from(&b"not a proof"[..]).is_err());
+    assert!(BBProof::try_from(&[][..]).is_err());
+}
+
+#[test]
+// Check that two polynomials with different degree bounds proven under a shared
+// transcript are accepted together, that the second proof is bound to the
+// shared transcript and that tampering with either proof is detected
+fn test_verify_pair() {
+    let configs = (
+        test_bb_stir_config(
+            BB_EXT_SEC_LEVEL,
+            SecurityAssumption::CapacityBound,
+            10,
+            1,
+            2,
+            3,
+        ),
+        test_bb_stir_config(
+            BB_EXT_SEC_LEVEL,
+            SecurityAssumption::CapacityBound,
+            12,
+            2,
+            3,
+            2,
+        ),
+    );
+    let configs = (&configs.0, &configs.1);
+
+    let (first_witness, first_commitment) = commit(
+        configs.0,
+        rand_poly((1 << configs.0.log_starting_degree()) - 1),
+    );
+    let (second_witness, second_commitment) = commit(
+        configs.1,
+        rand_poly((1 << configs.1.log_starting_degree()) - 1),
+    );
+    let commitments = (first_commitment, second_commitment);
+
+    let (first_proof, second_proof) = prove_pair(
+        configs,
+        (first_witness, second_witness),
+        commitments,
+        &mut test_bb_challenger(),
+    );
+
+    verify_pair(
+        configs,
+        commitments,
+        (&first_proof, &second_proof),
+        &mut test_bb_challenger(),
+    )
+    .unwrap();
+
+    // The second proof on its own is not valid outside the shared transcript
+    assert!(verify(
+        configs.1,
+        second_commitment,
+        &second_proof,
+        &mut test_bb_challenger()
+    )
+    .is_err());
+
+    let mut invalid_first_proof = first_proof.clone();
+    invalid_first_proof.final_polynomial = rand_poly(configs.0.final_degree_bound());
+
+    assert_eq!(
+        verify_pair(
+            configs,
+            commitments,
+            (&invalid_first_proof, &second_proof),
+            &mut test_bb_challenger(),
+        ),
+        Err(PairVerificationError::First(
+            VerificationError::FinalPolynomialDegree
+        ))
+    );
+
+    let mut invalid_second_proof = second_proof;
+    invalid_second_proof.final_polynomial = rand_poly(configs.1.final_degree_bound());
+
+    assert_eq!(
+        verify_pair(
+            configs,
+            commitments,
+            (&first_proof, &invalid_second_proof),
+            &mut test_bb_challenger(),
+        ),
+        Err(PairVerificationError::Second(
+            VerificationError::FinalPolynomialDegree
+        ))
+    );
+}
+
+#[test]
+// Check that a chain of proofs verifies in the order in which it was produced,
+// leaving the verifier's challenger in the same state as the prover's, and
+// that it is rejected if two of its proofs are swapped
+fn test_verify_chained() {
+    let config = test_bb_stir_config(
+        BB_EXT_SEC_LEVEL,
+        SecurityAssumption::CapacityBound,
+        10,
+        1,
+        2,
+        3,
+    );
+
+    let mut prover = ChainedProver::new(test_bb_challenger());
+
+    let (proofs, commitments): (Vec<_>, Vec<_>) = (0..3)
+        .map(|_| {
+            let (witness, commitment) =
+                commit(&config, rand_poly((1 << config.log_starting_degree()) - 1));
+            (prover.prove(&config, witness, commitment), commitment)
+        })
+        .unzip();
+
+    assert_eq!(prover.num_proofs(), 3);
+
+    let mut verifier = ChainedVerifier::new(test_bb_challenger());
+    for (proof, &commitment) in proofs.iter().zip(&commitments) {
+        verifier.verify(&config, commitment, proof).unwrap();
+    }
+    assert_eq!(verifier.num_proofs(), 3);
+
+    // The final states of both challengers match
+    let (mut prover_challenger, mut verifier_challenger) =
+        (prover.into_challenger(), verifier.into_challenger());
+    assert_eq!(
+        prover_challenger.sample_algebra_element::<BbExt>(),
+        verifier_challenger.sample_algebra_element::<BbExt>()
+    );
+
+    // Swapping the first two proofs (with their commitments) breaks the chain
+    let mut verifier = ChainedVerifier::new(test_bb_challenger());
+    assert!(verifier
+        .verify(&config, commitments[1], &proofs[1])
+        .is_err());
+
+    // Skipping a proof of the chain breaks it as well
+    let mut verifier = ChainedVerifier::new(test_bb_challenger());
+    verifier
+        .verify(&config, commitments[0], &proofs[0])
+        .unwrap();
+    assert!(verifier
+        .verify(&config, commitments[2], &proofs[2])
+        .is_err());
+}
+
+// Folding-challenge source binding a public value into every folding
+// randomness r_i
+struct PublicValueSource(Bb);
+
+impl FoldChallengeSource<Bb> for PublicValueSource {
+    fn fold_challenge<EF, C>(&self, challenger: &mut C, round: usize) -> EF
+    where
+        EF: ExtensionField<Bb>,
+        C: FieldChallenger<Bb>,
+    {
+        challenger.observe(self.0);
+        challenger.observe(Bb::from_usize(round));
+        challenger.sample_algebra_element()
+    }
+}
+
+#[test]
+// Check that a proof produced with a custom folding-challenge source verifies
+// with the same source, but not with one binding a different public value nor
+// with the default source
+fn test_verify_fold_challenge_source() {
+    let config = test_bb_stir_config(
+        BB_EXT_SEC_LEVEL,
+        SecurityAssumption::CapacityBound,
+        10,
+        1,
+        2,
+        3,
+    );
+
+    let (witness, commitment) = commit(&config, rand_poly((1 << config.log_starting_degree()) - 1));
+    let proof = prove_with_fold_challenge_source(
+        &config,
+        witness,
+        commitment,
+        &mut test_bb_challenger(),
+        &PublicValueSource(Bb::from_u32(42)),
+    );
+
+    verify_with_fold_challenge_source(
+        &config,
+        commitment,
+        &proof,
+        &mut test_bb_challenger(),
+        &PublicValueSource(Bb::from_u32(42)),
+    )
+    .unwrap();
+
+    assert!(verify_with_fold_challenge_source(
+        &config,
+        commitment,
+        &proof,
+        &mut test_bb_challenger(),
+        &PublicValueSource(Bb::from_u32(43)),
+    )
+    .is_err());
+
+    assert!(verify(&config, commitment, &proof, &mut test_bb_challenger()).is_err());
+}
+
+#[test]
+// Check that verify_against_any returns the index of the only candidate
+// commitment a proof is valid for (leaving the challenger as verify would),
+// and rejects the proof if no candidate matches or if it was produced under a
+// different configuration
+fn test_verify_against_any() {
+    let config = test_bb_stir_config(
+        BB_EXT_SEC_LEVEL,
+        SecurityAssumption::CapacityBound,
+        10,
+        1,
+        2,
+        3,
+    );
+
+    let (proof, commitment) = generate_bb_proof_with_config(&config, &mut test_bb_challenger());
+    let other_commitments = (0..3)
+        .map(|_| commit(&config, rand_poly::<BbExt>((1 << 10) - 1)).1)
+        .collect_vec();
+
+    let candidates = vec![
+        other_commitments[0],
+        other_commitments[1],
+        commitment,
+        other_commitments[2],
+    ];
+
+    let mut challenger = test_bb_challenger();
+    assert_eq!(
+        verify_against_any(&config, &candidates, &proof, &mut challenger),
+        Ok(2)
+    );
+
+    let mut verify_challenger = test_bb_challenger();
+    verify(&config, commitment, &proof, &mut verify_challenger).unwrap();
+    assert_eq!(
+        challenger.sample_algebra_element::<BbExt>(),
+        verify_challenger.sample_algebra_element::<BbExt>()
+    );
+
+    assert_eq!(
+        verify_against_any(
+            &config,
+            &other_commitments,
+            &proof,
+            &mut test_bb_challenger()
+        ),
+        Err(VerificationError::NoMatchingCommitment)
+    );
+    assert_eq!(
+        verify_against_any(&config, &[], &proof, &mut test_bb_challenger()),
+        Err(VerificationError::NoMatchingCommitment)
+    );
+
+    let other_config = test_bb_stir_config(
+        BB_EXT_SEC_LEVEL,
+        SecurityAssumption::CapacityBound,
+        10,
+        2,
+        2,
+        3,
+    );
+    assert!(matches!(
+        verify_against_any(
+            &other_config,
+            &candidates,
+            &proof,
+            &mut test_bb_challenger()
+        ),
+        Err(VerificationError::ParameterMismatch { .. })
+    ));
+}
+
+#[test]
+// Check that the degree bounds are enforced on polynomials as functions: the
+// proof of the zero polynomial, whose final polynomial is zero, is accepted,
+// and a zero final polynomial padded with zeros beyond the final degree bound
+// is not reported as exceeding it
+fn test_verify_zero_final_polynomial() {
+    let config = test_bb_stir_config(
+        BB_EXT_SEC_LEVEL,
+        SecurityAssumption::CapacityBound,
+        10,
+        1,
+        2,
+        3,
+    );
+
+    let (witness, commitment) = commit(&config, Polynomial::<BbExt>::zero());
+    let mut proof = prove(&config, witness, commitment, &mut test_bb_challenger());
+
+    assert_eq!(proof.final_polynomial.degree_checked(), None);
+    verify(&config, commitment, &proof, &mut test_bb_challenger()).unwrap();
+
+    proof.final_polynomial = Polynomial::zero().with_len(config.final_degree_bound() + 1);
+
+    assert_eq!(proof.validate_shape(&config), Ok(()));
+    assert_ne!(
+        verify(&config, commitment, &proof, &mut test_bb_challenger()),
+        Err(VerificationError::FinalPolynomialDegree)
+    );
+}
+
+#[test]
+// Check that a proof produced with some public inputs is only accepted with
+// those same public inputs, and that proving with no public inputs is the same
+// as proving with prove
+fn test_verify_with_public_inputs() {
+    let config = test_bb_stir_config(
+        BB_EXT_SEC_LEVEL,
+        SecurityAssumption::CapacityBound,
+        10,
+        1,
+        2,
+        3,
+    );
+
+    let polynomial = rand_poly::<BbExt>((1 << config.log_starting_degree()) - 1);
+    let public_inputs = [Bb::from_u8(1), Bb::from_u8(2), Bb::from_u8(3)];
+
+    let (witness, commitment) = commit(&config, polynomial.clone());
+    let proof = prove_with_public_inputs(
+        &config,
+        witness,
+        commitment,
+        &public_inputs,
+        &mut test_bb_challenger(),
+    );
+
+    verify_with_public_inputs(
+        &config,
+        commitment,
+        &proof,
+        &public_inputs,
+        &mut test_bb_challenger(),
+    )
+    .unwrap();
+
+    for other_public_inputs in [
+        &[Bb::from_u8(1), Bb::from_u8(2), Bb::from_u8(4)][..],
+        &public_inputs[..2],
+        &[][..],
+    ] {
+        assert!(verify_with_public_inputs(
+            &config,
+            commitment,
+            &proof,
+            other_public_inputs,
+            &mut test_bb_challenger(),
+        )
+        .is_err());
+    }
+    assert!(verify(&config, commitment, &proof, &mut test_bb_challenger()).is_err());
+
+    let (witness, commitment) = commit(&config, polynomial.clone());
+    let proof =
+        prove_with_public_inputs(&config, witness, commitment, &[], &mut test_bb_challenger());
+
+    let (witness, commitment) = commit(&config, polynomial);
+    let plain_proof = prove(&config, witness, commitment, &mut test_bb_challenger());
+
+    assert_eq!(
+        serde_json::to_string(&proof).unwrap(),
+        serde_json::to_string(&plain_proof).unwrap()
+    );
+}
+
+#[test]
+// Check that verify_collect_errors accepts honest proofs and, for a proof
+// tampered with in several independent ways, reports each resulting failure in
+// order, the first one being the error returned by verify. A failure after
+// which no further check is meaningful ends the list.
+fn test_verify_collect_errors() {
+    let config = test_bb_stir_config(
+        BB_EXT_SEC_LEVEL,
+        SecurityAssumption::CapacityBound,
+        10,
+        1,
+        2,
+        3,
+    );
+
+    let (proof, commitment) = generate_bb_proof_with_config(&config, &mut test_bb_challenger());
+    verify_collect_errors(&config, commitment, &proof, &mut test_bb_challenger()).unwrap();
+
+    // None of these modifications affects the transcript before the final
+    // proof of work, so the remaining checks are unaffected by each of them
+    let mut tampered_proof = proof.clone();
+    tampered_proof.round_proofs[0].query_proofs[0].0[0] += BbExt::ONE;
+    tampered_proof.final_round_queries[0].0[0] += BbExt::ONE;
+    tampered_proof.pow_witness += Bb::ONE;
+
+    let errors = verify_collect_errors(
+        &config,
+        commitment,
+        &tampered_proof,
+        &mut test_bb_challenger(),
+    )
+    .unwrap_err();
+
+    assert_eq!(
+        errors,
+        vec![
+            VerificationError::Round(1, FullRoundVerificationError::QueryPath),
+            VerificationError::Round(1, FullRoundVerificationError::AnsPolynomialEvaluations),
+            VerificationError::FinalQueryPath,
+            VerificationError::FinalPolynomialEvaluations,
+            VerificationError::FinalProofOfWork,
+        ]
+    );
+    assert_eq!(
+        verify(
+            &config,
+            commitment,
+            &tampered_proof,
+            &mut test_bb_challenger()
+        ),
+        Err(VerificationError::Round(
+            1,
+            FullRoundVerificationError::QueryPath
+        ))
+    );
+
+    // Missing final openings cannot be matched to the queried indices, which
+    // ends verification
+    tampered_proof.final_round_queries.pop();
+
+    let errors = verify_collect_errors(
+        &config,
+        commitment,
+        &tampered_proof,
+        &mut test_bb_challenger(),
+    )
+    .unwrap_err();
+
+    assert_eq!(errors.len(), 3);
+    assert!(matches!(
+        errors[2],
+        VerificationError::QueryCountMismatch { .. }
+    ));
+}
+
+#[test]
+// Check that the commitment binds the codeword: commitments to two different
+// codewords differ, the openings of one codeword are rejected against the
+// commitment to the other and against any fold-group index other than their
+// own, and a proof for one codeword does not verify against the commitment to
+// the other. This holds both with one row per fold group and with fold groups
+// split over several rows.
+fn test_commitment_binding() {
+    for log_leaf_width in [2, 1] {
+        let parameters = StirParameters::constant_folding_factor(
+            (BB_EXT_SEC_LEVEL, SecurityAssumption::CapacityBound),
+            10,
+            1,
+            2,
+            3,
+            20,
+            test_bb_mmcs_config(),
+        )
+        .with_log_leaf_width(log_leaf_width);
+        let config = StirConfig::new::<BbExt>(parameters).unwrap();
+
+        let degree = (1 << config.log_starting_degree()) - 1;
+        let (witness_1, commitment_1) = commit(&config, rand_poly::<BbExt>(degree));
+        let (witness_2, commitment_2) = commit(&config, rand_poly::<BbExt>(degree));
+        assert_ne!(commitment_1, commitment_2);
+
+        let log_folding_factor = config.log_starting_folding_factor();
+        let log_rows_per_group = config.log_rows_per_fold_group(log_folding_factor);
+        let layout = StackedLayout::new(
+            config.starting_domain_log_size(),
+            log_folding_factor,
+            log_rows_per_group,
+        );
+        let num_groups = 1 << (config.starting_domain_log_size() - log_folding_factor);
+
+        for index in 0..num_groups {
+            let opening = [open_fold_group(
+                config.mmcs_config(),
+                index,
+                log_rows_per_group,
+                &witness_1.merkle_tree,
+            )];
+            let other_index = (index + 1) % num_groups;
+
+            assert!(verify_query_paths(
+                config.mmcs_config(),
+                &commitment_1,
+                layout,
+                &[index],
+                &opening
+            ));
+            assert!(!verify_query_paths(
+                config.mmcs_config(),
+                &commitment_2,
+                layout,
+                &[index],
+                &opening
+            ));
+            assert!(!verify_query_paths(
+                config.mmcs_config(),
+                &commitment_1,
+                layout,
+                &[other_index],
+                &opening
+            ));
+        }
+
+        let proof = prove(&config, witness_1, commitment_1, &mut test_bb_challenger());
+        verify(&config, commitment_1, &proof, &mut test_bb_challenger()).unwrap();
+        assert!(verify(&config, commitment_2, &proof, &mut test_bb_challenger()).is_err());
+
+        let proof = prove(&config, witness_2, commitment_2, &mut test_bb_challenger());
+        verify(&config, commitment_2, &proof, &mut test_bb_challenger()).unwrap();
+        assert!(verify(&config, commitment_1, &proof, &mut test_bb_challenger()).is_err());
+    }
+}
+
+#[test]
+// Check that verify_with_domains accepts an honest proof given the domain
+// schedule of the configuration, and rejects it given a schedule with a missing,
+// surplus or modified domain
+fn test_verify_with_domains() {
+    let config = test_bb_stir_config(
+        BB_EXT_SEC_LEVEL,
+        SecurityAssumption::CapacityBound,
+        10,
+        1,
+        2,
+        3,
+    );
+    let (proof, commitment) = generate_bb_proof_with_config(&config, &mut test_bb_challenger());
+
+    let domains = config.domains::<BbExt>();
+    assert_eq!(domains.len(), config.total_rounds());
+    assert_eq!(domains[0], initial_domain::<BbExt, _>(&config));
+
+    // The same schedule can be used for several verifications
+    for _ in 0..2 {
+        verify_with_domains(
+            &config,
+            commitment,
+            &domains,
+            &proof,
+            &mut test_bb_challenger(),
+        )
+        .unwrap();
+    }
+
+    let mut missing_domain = domains.clone();
+    missing_domain.pop();
+
+    let mut surplus_domain = domains.clone();
+    surplus_domain.push(domains.last().unwrap().shrink_subgroup(1));
+
+    let mut shifted_domain = domains.clone();
+    shifted_domain[1] = domains[1].shift_by(BbExt::TWO);
+
+    let mut shrunk_domain = domains.clone();
+    shrunk_domain[1] = domains[1].shrink_subgroup(1);
+
+    for wrong_domains in [
+        missing_domain,
+        surplus_domain,
+        shifted_domain,
+        shrunk_domain,
+    ] {
+        assert_eq!(
+            verify_with_domains(
+                &config,
+                commitment,
+                &wrong_domains,
+                &proof,
+                &mut test_bb_challenger(),
+            ),
+            Err(VerificationError::DomainMismatch)
+        );
+    }
+}
+
+#[test]
+// Check that an envelope survives a serialization round trip and re-verifies
+// under the configuration reconstructed from it, and that it is rejected if its
+// parameters or format version are altered, if it declares a security level
+// below the required one or if its parameters are invalid
+fn test_verify_envelope() {
+    let config = test_bb_stir_config(
+        BB_EXT_SEC_LEVEL,
+        SecurityAssumption::CapacityBound,
+        10,
+        1,
+        2,
+        3,
+    );
+    let (proof, commitment) = generate_bb_proof_with_config(&config, &mut test_bb_challenger());
+
+    let envelope = StirProofEnvelope::new(&config, commitment, proof);
+    assert_eq!(envelope.parameters(), &config.parameters().spec());
+
+    let serialized = serde_json::to_string(&envelope).unwrap();
+    let deserialized: StirProofEnvelope<BbExt, BbExtMmcs, Bb> =
+        serde_json::from_str(&serialized).unwrap();
+
+    assert_eq!(deserialized.parameters(), envelope.parameters());
+    assert_eq!(deserialized.commitment(), &commitment);
+    verify_envelope(
+        &deserialized,
+        test_bb_mmcs_config(),
+        BB_EXT_SEC_LEVEL,
+        &mut test_bb_challenger(),
+    )
+    .unwrap();
+
+    assert_eq!(
+        verify_envelope(
+            &deserialized,
+            test_bb_mmcs_config(),
+            BB_EXT_SEC_LEVEL + 1,
+            &mut test_bb_challenger()
+        ),
+        Err(VerificationError::InsufficientSecurityLevel {
+            required: BB_EXT_SEC_LEVEL + 1,
+            got: BB_EXT_SEC_LEVEL
+        })
+    );
+
+    let mut altered_parameters = deserialized.clone();
+    altered_parameters.parameters.pow_bits += 1;
+    assert!(verify_envelope(
+        &altered_parameters,
+        test_bb_mmcs_config(),
+        BB_EXT_SEC_LEVEL,
+        &mut test_bb_challenger()
+    )
+    .is_err());
+
+    // Parameters which StirConfig::new rejects, some of which would make it
+    // panic if they were not validated
+    let invalid_parameters: [fn(&mut StirParametersSpec); 4] = [
+        |parameters| parameters.log_folding_factors.clear(),
+        |parameters| parameters.log_folding_factors[0] = 0,
+        |parameters| parameters.log_inv_rates.push(1),
+        |parameters| parameters.query_pow_schedule = Some(vec![(10, 20)]),
+    ];
+
+    for invalidate in invalid_parameters {
+        let mut invalid_envelope = deserialized.clone();
+        invalidate(&mut invalid_envelope.parameters);
+
+        assert_eq!(
+            verify_envelope(
+                &invalid_envelope,
+                test_bb_mmcs_config(),
+                BB_EXT_SEC_LEVEL,
+                &mut test_bb_challenger()
+            ),
+            Err(VerificationError::InvalidEnvelope)
+        );
+    }
+
+    // Parameters which would make the verifier panic (proofs of work of more
+    // bits than the challenger can sample) or perform an unbounded amount of
+    // work (huge security levels)
+    let unverifiable_parameters: [fn(&mut StirParametersSpec); 4] = [
+        |parameters| parameters.pow_bits = 64,
+        |parameters| parameters.query_pow_schedule = Some(vec![(1, 64); 4]),
+        |parameters| parameters.security_level = MAX_ENVELOPE_SECURITY_LEVEL + 1,
+        |parameters| parameters.security_level = usize::MAX,
+    ];
+
+    for invalidate in unverifiable_parameters {
+        let mut invalid_envelope = deserialized.clone();
+        invalidate(&mut invalid_envelope.parameters);
+
+        assert_eq!(
+            verify_envelope(
+                &invalid_envelope,
+                test_bb_mmcs_config(),
+                BB_EXT_SEC_LEVEL,
+                &mut test_bb_challenger()
+            ),
+            Err(VerificationError::InvalidEnvelope)
+        );
+    }
+
+    // Fixing fewer out-of-domain samples than required weakens the soundness
+    let mut weakened_envelope = deserialized.clone();
+    weakened_envelope.parameters.num_ood_samples = Some(vec![0, 0]);
+    assert_eq!(
+        verify_envelope(
+            &weakened_envelope,
+            test_bb_mmcs_config(),
+            BB_EXT_SEC_LEVEL,
+            &mut test_bb_challenger()
+        ),
+        Err(VerificationError::InvalidEnvelope)
+    );
+
+    let mut altered_version = deserialized;
+    altered_version.format_version += 1;
+    assert_eq!(
+        verify_envelope(
+            &altered_version,
+            test_bb_mmcs_config(),
+            BB_EXT_SEC_LEVEL,
+            &mut test_bb_challenger()
+        ),
+        Err(VerificationError::InvalidEnvelope)
+    );
+}
+
+// Leaf encoding which reverses each row and prepends a tag to it
+#[derive(Clone)]
+struct ReversedTaggedLeaf;
+
+impl LeafEncoding<BbExt> for ReversedTaggedLeaf {
+    fn encoded_width(&self, width: usize) -> usize {
+        width + 1
+    }
+
+    fn encode(&self, row: &[BbExt]) -> Vec<BbExt> {
+        iter::once(BbExt::from_u32(7))
+            .chain(row.iter().rev().copied())
+            .collect()
+    }
+
+    fn decode(&self, leaf: &[BbExt]) -> Vec<BbExt> {
+        leaf[1..].iter().rev().copied().collect()
+    }
+}
+
+#[test]
+// Check that proofs produced with a custom leaf encoding verify, and that
+// wrapping the MMCS with the default encoding leaves commitments unchanged
+fn test_verify_leaf_encoding() {
+    fn config<M: Clone>(mmcs_config: M, log_leaf_width: usize) -> StirConfig<M> {
+        let parameters = StirParameters::constant_folding_factor(
+            (BB_EXT_SEC_LEVEL, SecurityAssumption::CapacityBound),
+            10,
+            1,
+            2,
+            3,
+            20,
+            mmcs_config,
+        )
+        .with_log_leaf_width(log_leaf_width);
+
+        StirConfig::new::<BbExt>(parameters).unwrap()
+    }
+
+    for log_leaf_width in [2, 1] {
+        let plain_config = config(test_bb_mmcs_config(), log_leaf_width);
+        let identity_config = config(
+            EncodedLeafMmcs::new(test_bb_mmcs_config(), IdentityLeafEncoding),
+            log_leaf_width,
+        );
+        let custom_config = config(
+            EncodedLeafMmcs::new(test_bb_mmcs_config(), ReversedTaggedLeaf),
+            log_leaf_width,
+        );
+
+        let polynomial = rand_poly((1 << plain_config.log_starting_degree()) - 1);
+
+        let (_, plain_commitment) = commit(&plain_config, polynomial.clone());
+        let (_, identity_commitment) = commit(&identity_config, polynomial.clone());
+        assert_eq!(identity_commitment, plain_commitment);
+
+        let (witness, commitment) = commit(&custom_config, polynomial);
+        assert_ne!(commitment, plain_commitment);
+
+        let proof = prove(
+            &custom_config,
+            witness,
+            commitment,
+            &mut test_bb_challenger(),
+        );
+        verify(
+            &custom_config,
+            commitment,
+            &proof,
+            &mut test_bb_challenger(),
+        )
+        .unwrap();
+
+        // The proof does not verify against the commitment to the plain rows
+        assert!(verify(
+            &custom_config,
+            plain_commitment,
+            &proof,
+            &mut test_bb_challenger()
+        )
+        .is_err());
+    }
+}
+
+#[test]
+// Check that verify_diagnostic accepts an honest proof and that tampering with a
+// single opened evaluation yields a failure pointing at the round and query in
+// question, both in a full round and in the final round
+fn test_verify_diagnostic() {
+    let config = test_bb_stir_config(
+        BB_EXT_SEC_LEVEL,
+        SecurityAssumption::CapacityBound,
+        10,
+        1,
+        2,
+        3,
+    );
+    let (proof, commitment) = generate_bb_proof_with_config(&config, &mut test_bb_challenger());
+
+    verify_diagnostic(&config, commitment, &proof, &mut test_bb_challenger()).unwrap();
+
+    // Tampering with an evaluation opened in the first round
+    let mut invalid_proof = proof.clone();
+    invalid_proof.round_proofs[0].query_proofs[1].0[0] += BbExt::ONE;
+
+    let failure = verify_diagnostic(
+        &config,
+        commitment,
+        &invalid_proof,
+        &mut test_bb_challenger(),
+    )
+    .unwrap_err();
+
+    assert_eq!(failure.round, Some(1));
+    assert_eq!(
+        failure.error,
+        VerificationError::Round(1, FullRoundVerificationError::AnsPolynomialEvaluations)
+    );
+    let mismatch = failure.mismatch.unwrap();
+    assert_eq!(mismatch.query, 1);
+    assert_ne!(mismatch.expected, mismatch.actual);
+
+    // The usual verifier detects the tampering through the Merkle proof instead
+    assert_eq!(
+        verify(
+            &config,
+            commitment,
+            &invalid_proof,
+            &mut test_bb_challenger()
+        ),
+        Err(VerificationError::Round(
+            1,
+            FullRoundVerificationError::QueryPath
+        ))
+    );
+
+    // Tampering with an evaluation opened in the final round
+    let mut invalid_proof = proof.clone();
+    invalid_proof.final_round_queries[2].0[0] += BbExt::ONE;
+
+    let failure = verify_diagnostic(
+        &config,
+        commitment,
+        &invalid_proof,
+        &mut test_bb_challenger(),
+    )
+    .unwrap_err();
+
+    assert_eq!(failure.round, Some(config.total_rounds()));
+    assert_eq!(failure.error, VerificationError::FinalPolynomialEvaluations);
+    let mismatch = failure.mismatch.unwrap();
+    assert_eq!(mismatch.query, 2);
+    assert_ne!(mismatch.expected, mismatch.actual);
+
+    // A failure which is not specific to a round is reported without one
+    let mut invalid_proof = proof;
+    invalid_proof.parameter_fingerprint ^= 1;
+
+    let failure = verify_diagnostic(
+        &config,
+        commitment,
+        &invalid_proof,
+        &mut test_bb_challenger(),
+    )
+    .unwrap_err();
+
+    assert_eq!(failure.round, None);
+    assert!(matches!(
+        failure.error,
+        VerificationError::ParameterMismatch { .. }
+    ));
+    assert_eq!(failure.mismatch, None);
+}
+
+#[test]
+// Check that the serialized proof is tagged with the current format version,
+// that it deserializes under it and that deserialization rejects a proof
+// tagged with any other version
+fn test_proof_format_version() {
+    let config = test_bb_stir_config(
+        BB_EXT_SEC_LEVEL,
+        SecurityAssumption::CapacityBound,
+        10,
+        1,
+        2,
+        3,
+    );
+    let (proof, commitment) = generate_bb_proof_with_config(&config, &mut test_bb_challenger());
+
+    let mut serialized_proof = serde_json::to_value(&proof).unwrap();
+    assert_eq!(serialized_proof["format_version"], BBProof::FORMAT_VERSION);
+
+    let deserialized_proof: BBProof = serde_json::from_value(serialized_proof.clone()).unwrap();
+    verify(
+        &config,
+        commitment,
+        &deserialized_proof,
+        &mut test_bb_challenger(),
+    )
+    .unwrap();
+
+    // The next version and that of the format with (resp. without) shake
+    // polynomials are rejected
+    for version in [
+        BBProof::FORMAT_VERSION + 1,
+        BBProof::FORMAT_VERSION ^ NO_SHAKE_FORMAT_FLAG,
+    ] {
+        serialized_proof["format_version"] = version.into();
+        let error = serde_json::from_value::<BBProof>(serialized_proof.clone())
+            .err()
+            .unwrap();
+        assert!(error
+            .to_string()
+            .contains("unsupported STIR proof format version"));
+    }
+}
+
+#[test]
+// Check that verify_pow_only accepts an honest proof and one whose openings
+// have been tampered with (as Merkle paths are not checked), but rejects a proof
+// with an invalid proof-of-work witness in a full round or the final round
+fn test_verify_pow_only() {
+    let mut rng = rng();
+    let config = test_bb_stir_config(
+        BB_EXT_SEC_LEVEL,
+        SecurityAssumption::CapacityBound,
+        10,
+        1,
+        2,
+        3,
+    );
+    let (proof, commitment) = generate_bb_proof_with_config(&config, &mut test_bb_challenger());
+
+    verify_pow_only(&config, commitment, &proof, &mut test_bb_challenger()).unwrap();
+
+    let mut tampered_openings = proof.clone();
+    tampered_openings.round_proofs[0].query_proofs[0].0[0] += BbExt::ONE;
+    verify_pow_only(
+        &config,
+        commitment,
+        &tampered_openings,
+        &mut test_bb_challenger(),
+    )
+    .unwrap();
+
+    let mut invalid_proof = proof.clone();
+    invalid_proof.round_proofs[0].pow_witness = rng.random();
+    assert_eq!(
+        verify_pow_only(
+            &config,
+            commitment,
+            &invalid_proof,
+            &mut test_bb_challenger()
+        ),
+        Err(VerificationError::Round(
+            1,
+            FullRoundVerificationError::ProofOfWork
+        ))
+    );
+
+    let mut invalid_proof = proof;
+    invalid_proof.pow_witness = rng.random();
+    assert_eq!(
+        verify_pow_only(
+            &config,
+            commitment,
+            &invalid_proof,
+            &mut test_bb_challenger()
+        ),
+        Err(VerificationError::FinalProofOfWork)
+    );
+}
+
+#[test]
+// Check that the query slack, the leaf width and the root generator are bound
+// to the transcript, as are the other parameters
+fn test_public_parameters_transcript() {
+    let parameters = StirParameters::constant_folding_factor(
+        (BB_EXT_SEC_LEVEL, SecurityAssumption::CapacityBound),
+        12,
+        1,
+        2,
+        3,
+        20,
+        test_bb_mmcs_config(),
+    );
+
+    let challenge = |parameters: &StirParameters<BbExtMmcs>| {
+        let mut challenger = test_bb_challenger();
+        observe_public_parameters(parameters, &mut challenger);
+        challenger.sample_algebra_element::<BbExt>()
+    };
+
+    let challenges = [
+        parameters.clone(),
+        parameters.clone().with_query_slack(0.5),
+        parameters.clone().with_log_leaf_width(1),
+        parameters
+            .clone()
+            .with_root_generator(BbExt::two_adic_generator(13).exp_u64(3)),
+    ]
+    .iter()
+    .map(challenge)
+    .collect_vec();
+
+    assert!(challenges.iter().all_unique());
+}
+
+#[test]
+// Check that a proof with more round proofs than the configuration has full
+// rounds is rejected (rather than causing a panic) by verify, verify_pow_only
+// and, when streamed, verify_from_reader
+fn test_extra_round_proofs() {
+    let config = test_bb_stir_config(
+        BB_EXT_SEC_LEVEL,
+        SecurityAssumption::CapacityBound,
+        10,
+        1,
+        2,
+        3,
+    );
+    let (proof, commitment) = generate_bb_proof_with_config(&config, &mut test_bb_challenger());
+
+    let mut padded_proof = proof.clone();
+    padded_proof
+        .round_proofs
+        .push(proof.round_proofs.last().unwrap().clone());
+
+    let expected_error = || VerificationError::RoundCountMismatch {
+        expected: 2,
+        got: 3,
+    };
+
+    assert_eq!(
+        verify(
+            &config,
+            commitment,
+            &padded_proof,
+            &mut test_bb_challenger()
+        ),
+        Err(expected_error())
+    );
+    assert_eq!(
+        verify_pow_only(
+            &config,
+            commitment,
+            &padded_proof,
+            &mut test_bb_challenger()
+        ),
+        Err(expected_error())
+    );
+
+    #[cfg(feature = "std")]
+    {
+        use std::io::Cursor;
+
+        use crate::verifier::verify_from_reader;
+
+        let mut encoding = Vec::new();
+        padded_proof.encode_v1(&mut encoding).unwrap();
+
+        assert_eq!(
+            verify_from_reader(
+                &config,
+                commitment,
+                Cursor::new(&encoding),
+                &mut test_bb_challenger()
+            ),
+            Err(expected_error())
+        );
+    }
+}
+
+#[test]
+// Check that build_answer_polynomial, fed with the out-of-domain replies of a
+// proof and the evaluations of g_1 at the points sampled by the verifier,
+// reproduces the Ans polynomial of the first round
+fn test_build_answer_polynomial() {
+    let config = test_bb_stir_config(
+        BB_EXT_SEC_LEVEL,
+        SecurityAssumption::CapacityBound,
+        10,
+        1,
+        2,
+        3,
+    );
+
+    let polynomial = rand_poly((1 << config.log_starting_degree()) - 1);
+    let (witness, commitment) = commit(&config, polynomial.clone());
+    let proof = prove(&config, witness, commitment, &mut test_bb_challenger());
+    let round_proof = &proof.round_proofs[0];
+
+    // Replay the transcript of the first round
+    let mut challenger = test_bb_challenger();
+    let state = initial_verification_state::<Bb, BbExt, _, _, _>(
+        &config,
+        commitment,
+        &mut challenger,
+        &TranscriptFoldChallenge,
+    );
+    let challenges = sample_round_challenges::<Bb, BbExt, _, _, _>(
+        &config,
+        1,
+        &state.domain,
+        state.folding_randomness,
+        round_proof,
+        &mut challenger,
+        &TranscriptFoldChallenge,
+    );
+
+    // g_1 is the folding of f_0 = polynomial
+    let log_folding_factor = config.round_config(1).log_folding_factor;
+    let g_1 = fold_polynomial(&polynomial, state.folding_randomness, log_folding_factor);
+
+    let mut domain_k = state.domain.fold(log_folding_factor);
+    let shifts = challenges
+        .queried_indices
+        .iter()
+        .map(|&i| {
+            let x = domain_k.element(i);
+            (x, g_1.evaluate(&x))
+        })
+        .collect_vec();
+
+    let ood = challenges
+        .ood_samples
+        .into_iter()
+        .zip(round_proof.betas.iter().copied())
+        .collect_vec();
+
+    assert_eq!(
+        build_answer_polynomial(&ood, &shifts),
+        round_proof.ans_polynomial
+    );
+}
+
+#[test]
+// Check that the verifier relies on the openings following the order in which
+// the queried indices are sampled: swapping two of them, either in a full round
+// or in the final round, causes verification to fail
+fn test_verify_reordered_query_proofs() {
+    let config = test_bb_stir_config(
+        BB_EXT_SEC_LEVEL,
+        SecurityAssumption::CapacityBound,
+        10,
+        1,
+        2,
+        3,
+    );
+    let (proof, commitment) = generate_bb_proof_with_config(&config, &mut test_bb_challenger());
+
+    let mut invalid_proof = proof.clone();
+    invalid_proof.round_proofs[0].query_proofs.swap(0, 1);
+    assert_eq!(
+        verify(
+            &config,
+            commitment,
+            &invalid_proof,
+            &mut test_bb_challenger()
+        ),
+        Err(VerificationError::Round(
+            1,
+            FullRoundVerificationError::QueryPath
+        ))
+    );
+
+    let mut invalid_proof = proof;
+    invalid_proof.final_round_queries.swap(0, 1);
+    assert_eq!(
+        verify(
+            &config,
+            commitment,
+            &invalid_proof,
+            &mut test_bb_challenger()
+        ),
+        Err(VerificationError::FinalQueryPath)
+    );
+}
+
+#[test]
+// Check that an Ans polynomial of inflated degree is rejected even if it still
+// interpolates all the expected values: adding the vanishing polynomial of the
+// out-of-domain and in-domain sampled points of the first round to Ans_1 does
+// not change its evaluations at those points
+fn test_verify_ans_degree_too_high() {
+    let config = test_bb_stir_config(
+        BB_EXT_SEC_LEVEL,
+        SecurityAssumption::CapacityBound,
+        10,
+        1,
+        2,
+        3,
+    );
+    let (proof, commitment) = generate_bb_proof_with_config(&config, &mut test_bb_challenger());
+
+    // Replay the transcript of the first round up to its proof of work, which
+    // does not depend on Ans_1
+    let mut challenger = test_bb_challenger();
+    let state = initial_verification_state::<Bb, BbExt, _, _, _>(
+        &config,
+        commitment,
+        &mut challenger,
+        &TranscriptFoldChallenge,
+    );
+    let challenges = sample_round_challenges::<Bb, BbExt, _, _, _>(
+        &config,
+        1,
+        &state.domain,
+        state.folding_randomness,
+        &proof.round_proofs[0],
+        &mut challenger,
+        &TranscriptFoldChallenge,
+    );
+
+    let mut domain_k = state.domain.fold(config.round_config(1).log_folding_factor);
+    let queried_points = challenges
+        .queried_indices
+        .iter()
+        .map(|&i| domain_k.element(i))
+        .collect_vec();
+
+    let vanishing_polynomial =
+        Polynomial::vanishing_polynomial(challenges.ood_samples.into_iter().chain(queried_points));
+
+    let mut invalid_proof = proof.clone();
+    invalid_proof.round_proofs[0].ans_polynomial =
+        &proof.round_proofs[0].ans_polynomial + &vanishing_polynomial;
+
+    assert_eq!(
+        verify(
+            &config,
+            commitment,
+            &invalid_proof,
+            &mut test_bb_challenger()
+        ),
+        Err(VerificationError::Round(
+            1,
+            FullRoundVerificationError::AnsPolynomialDegree
+        ))
+    );
+}
+
+#[test]
+// Check that proofs with fewer or more openings than queried indices, either
+// in a full round or in the final round, are rejected with a specific error
+// rather than causing a panic, including when they stem from a prover using
+// more or fewer queries than the verifier's configuration
+fn test_verify_query_count_mismatch() {
+    let parameters = StirParameters::constant_folding_factor(
+        (BB_EXT_SEC_LEVEL, SecurityAssumption::CapacityBound),
+        10,
+        1,
+        2,
+        3,
+        20,
+        test_bb_mmcs_config(),
+    );
+    let config = StirConfig::new::<BbExt>(parameters.clone()).unwrap();
+    let stricter_config = StirConfig::new::<BbExt>(parameters.with_query_slack(0.5)).unwrap();
+
+    let (proof, commitment) = generate_bb_proof_with_config(&config, &mut test_bb_challenger());
+    let num_openings = proof.round_proofs[0].query_proofs.len();
+    let num_final_openings = proof.final_round_queries.len();
+
+    // Too few openings
+    let mut invalid_proof = proof.clone();
+    invalid_proof.round_proofs[0].query_proofs.pop();
+    assert_eq!(
+        verify(
+            &config,
+            commitment,
+            &invalid_proof,
+            &mut test_bb_challenger()
+        ),
+        Err(VerificationError::Round(
+            1,
+            FullRoundVerificationError::QueryCountMismatch {
+                expected: num_openings,
+                got: num_openings - 1
+            }
+        ))
+    );
+
+    // Too many openings
+    let mut invalid_proof = proof.clone();
+    let opening = invalid_proof.round_proofs[0].query_proofs[0].clone();
+    invalid_proof.round_proofs[0].query_proofs.push(opening);
+    assert_eq!(
+        verify(
+            &config,
+            commitment,
+            &invalid_proof,
+            &mut test_bb_challenger()
+        ),
+        Err(VerificationError::Round(
+            1,
+            FullRoundVerificationError::QueryCountMismatch {
+                expected: num_openings,
+                got: num_openings + 1
+            }
+        ))
+    );
+
+    let mut invalid_proof = proof.clone();
+    invalid_proof.final_round_queries.truncate(1);
+    assert_eq!(
+        verify(
+            &config,
+            commitment,
+            &invalid_proof,
+            &mut test_bb_challenger()
+        ),
+        Err(VerificationError::QueryCountMismatch {
+            expected: num_final_openings,
+            got: 1
+        })
+    );
+
+    // A verifier requiring more queries than the prover made. The parameter
+    // fingerprints of the proofs are forged so that they are not rejected
+    // before their openings are looked at
+    let mut invalid_proof = proof.clone();
+    invalid_proof.parameter_fingerprint = stricter_config.parameter_fingerprint();
+    assert!(matches!(
+        verify(&stricter_config, commitment, &invalid_proof, &mut test_bb_challenger()),
+        Err(VerificationError::Round(
+            1,
+            FullRoundVerificationError::QueryCountMismatch { expected, got }
+        )) if expected > got
+    ));
+
+    // A prover making more queries than the verifier requires
+    let (mut stricter_proof, stricter_commitment) =
+        generate_bb_proof_with_config(&stricter_config, &mut test_bb_challenger());
+    stricter_proof.parameter_fingerprint = config.parameter_fingerprint();
+    assert!(matches!(
+        verify(
+            &config,
+            stricter_commitment,
+            &stricter_proof,
+            &mut test_bb_challenger()
+        ),
+        Err(VerificationError::Round(
+            1,
+            FullRoundVerificationError::QueryCountMismatch { expected, got }
+        )) if expected < got
+    ));
+}
+
+#[test]
+// Check that openings with the wrong number of evaluations, either in a full
+// round or in the final round, are rejected with a specific error rather than
+// causing a panic
+fn test_verify_malformed_leaf() {
+    let config = test_bb_stir_config(
+        BB_EXT_SEC_LEVEL,
+        SecurityAssumption::CapacityBound,
+        10,
+        1,
+        2,
+        3,
+    );
+    let (proof, commitment) = generate_bb_proof_with_config(&config, &mut test_bb_challenger());
+
+    let mut invalid_proof = proof.clone();
+    invalid_proof.round_proofs[0].query_proofs[0].0.pop();
+    assert_eq!(
+        verify(
+            &config,
+            commitment,
+            &invalid_proof,
+            &mut test_bb_challenger()
+        ),
+        Err(VerificationError::Round(
+            1,
+            FullRoundVerificationError::MalformedLeaf {
+                expected: 4,
+                got: 3
+            }
+        ))
+    );
+
+    let mut invalid_proof = proof;
+    invalid_proof.final_round_queries[0].0.truncate(1);
+    assert_eq!(
+        verify(
+            &config,
+            commitment,
+            &invalid_proof,
+            &mut test_bb_challenger()
+        ),
+        Err(VerificationError::MalformedLeaf {
+            expected: 4,
+            got: 1
+        })
+    );
+}
+
+// Challenger which behaves like the usual test challenger except that every
+// sampled field element is the same constant, which forces the out-of-domain
+// points of each round to collide
+#[derive(Clone)]
+struct ConstantSampleChallenger(BbChallenger);
+
+impl<T> CanObserve<T> for ConstantSampleChallenger
+where
+    BbChallenger: CanObserve<T>,
+{
+    fn observe(&mut self, value: T) {
+        self.0.observe(value);
+    }
+}
+
+impl CanSample<Bb> for ConstantSampleChallenger {
+    fn sample(&mut self) -> Bb {
+        Bb::from_u8(3)
+    }
+}
+
+impl CanSampleBits<usize> for ConstantSampleChallenger {
+    fn sample_bits(&mut self, bits: usize) -> usize {
+        self.0.sample_bits(bits)
+    }
+}
+
+impl FieldChallenger<Bb> for ConstantSampleChallenger {}
+
+impl GrindingChallenger for ConstantSampleChallenger {
+    type Witness = Bb;
+
+    fn grind(&mut self, bits: usize) -> Bb {
+        self.0.grind(bits)
+    }
+}
+
+#[test]
+// Check that a challenger yielding colliding out-of-domain points causes the
+// verifier to reject the proof with DuplicateOodPoint (after the resampling
+// guard gives up) instead of looping forever or failing to interpolate
+fn test_verify_duplicate_ood_point() {
+    let config = test_bb_stir_config(
+        BB_EXT_SEC_LEVEL,
+        SecurityAssumption::CapacityBound,
+        10,
+        1,
+        2,
+        3,
+    );
+    assert!(config.round_config(1).num_ood_samples >= 2);
+
+    let (proof, commitment) = generate_bb_proof_with_config(&config, &mut test_bb_challenger());
+
+    assert_eq!(
+        verify(
+            &config,
+            commitment,
+            &proof,
+            &mut ConstantSampleChallenger(test_bb_challenger())
+        ),
+        Err(VerificationError::Round(
+            1,
+            FullRoundVerificationError::DuplicateOodPoint
+        ))
+    );
+}
+
+#[test]
+// Check that the verifier rejects the proofs of a prover which skips grinding,
+// which guards against the verifier no longer checking the proof-of-work
+// witnesses
+fn test_verify_non_grinding_prover() {
+    let config = test_bb_stir_config(
+        BB_EXT_SEC_LEVEL,
+        SecurityAssumption::CapacityBound,
+        10,
+        1,
+        2,
+        3,
+    );
+    assert!(config.round_config(1).pow_bits > 0);
+
+    let polynomial = rand_poly((1 << config.log_starting_degree()) - 1);
+    let (witness, commitment) = commit(&config, polynomial);
+    let proof = prove_without_grinding(&config, witness, commitment, &mut test_bb_challenger());
+
+    assert_eq!(proof.round_proofs[0].pow_witness, Bb::ZERO);
+    assert_eq!(
+        verify(&config, commitment, &proof, &mut test_bb_challenger()),
+        Err(VerificationError::Round(
+            1,
+            FullRoundVerificationError::ProofOfWork
+        ))
+    );
+}
+
+#[test]
+// Check that each possible VerificationError is triggered correctly by
+// producing various dishonest proofs
+fn test_verify_failing_cases() {
+    let mut rng = rng();
+    let config = test_bb_stir_config(
+        BB_EXT_SEC_LEVEL,
+        SecurityAssumption::CapacityBound,
+        10,
+        1,
+        2,
+        3,
+    );
+
+    // This is the honest proof we will tamper with
+    let (proof, commitment) = generate_bb_proof_with_config(&config, &mut test_bb_challenger());
+
+    // ============================== ProofOfWork ==============================
+
+    let mut invalid_proof = proof.clone();
+    invalid_proof.round_proofs[0].pow_witness = rng.random();
+
+    assert_eq!(
+        verify(
+            &config,
+            commitment,
+            &invalid_proof,
+            &mut test_bb_challenger()
+        ),
+        Err(VerificationError::Round(
+            1,
+            FullRoundVerificationError::ProofOfWork
+        ))
+    );
+
+    // ============================== QueryPath ===============================
+
+    let mut invalid_proof = proof.clone();
+    let query_proof = proof.round_proofs[0].query_proofs[0].clone();
+    let mut invalid_leaf = query_proof.0.clone();
+    invalid_leaf[0] = rng.random();
+    let invalid_query_proof = (invalid_leaf, query_proof.1.clone());
+    invalid_proof.round_proofs[0].query_proofs[0] = invalid_query_proof;
+
+    assert_eq!(
+        verify(
+            &config,
+            commitment,
+            &invalid_proof,
+            &mut test_bb_challenger()
+        ),
+        Err(VerificationError::Round(
+            1,
+            FullRoundVerificationError::QueryPath
+        ))
+    );
+
+    // ========================= OodReplyInconsistent =========================
+
+    let mut invalid_proof = proof.clone();
+    invalid_proof.round_proofs[0].betas[0] += BbExt::ONE;
+
+    assert_eq!(
+        verify(
+            &config,
+            commitment,
+            &invalid_proof,
+            &mut test_bb_challenger()
+        ),
+        Err(VerificationError::Round(
+            1,
+            FullRoundVerificationError::OodReplyInconsistent
+        ))
+    );
+
+    // ========================== AnsPolynomialDegree ==========================
+
+    let mut invalid_proof = proof.clone();
+    let original_degree = invalid_proof.round_proofs[0]
+        .ans_polynomial
+        .degree()
+        .unwrap();
+    invalid_proof.round_proofs[0].ans_polynomial = rand_poly(original_degree + 1);
+
+    assert_eq!(
+        verify(
+            &config,
+            commitment,
+            &invalid_proof,
+            &mut test_bb_challenger()
+        ),
+        Err(VerificationError::Round(
+            1,
+            FullRoundVerificationError::AnsPolynomialDegree
+        ))
+    );
+
+    // ======================= AnsPolynomialEvaluations =======================
+
+    let mut invalid_proof = proof.clone();
+    let original_degree = invalid_proof.round_proofs[0]
+        .ans_polynomial
+        .degree()
+        .unwrap();
+    invalid_proof.round_proofs[0].ans_polynomial = rand_poly(original_degree);
+
+    assert_eq!(
+        verify(
+            &config,
+            commitment,
+            &invalid_proof,
+            &mut test_bb_challenger()
+        ),
+        Err(VerificationError::Round(
+            1,
+            FullRoundVerificationError::AnsPolynomialEvaluations
+        ))
+    );
+
+    // ========================= FinalPolynomialDegree =========================
+
+    let mut invalid_proof = proof.clone();
+    let original_degree = invalid_proof.final_polynomial.degree().unwrap();
+    invalid_proof.final_polynomial = rand_poly(original_degree + 1);
 
     assert_eq!(
         verify(
             &config,
             commitment,
-            invalid_proof,
+            &invalid_proof,
             &mut test_bb_challenger()
         ),
         Err(VerificationError::FinalPolynomialDegree)
@@ -494,7 +3487,7 @@ fn test_verify_failing_cases() {
         verify(
             &config,
             _commitment,
-            tampered_proof,
+            &tampered_proof,
             &mut test_bb_challenger()
         ),
         Err(VerificationError::FinalPolynomialEvaluations)
@@ -513,7 +3506,7 @@ fn test_verify_failing_cases() {
         verify(
             &config,
             commitment,
-            invalid_proof,
+            &invalid_proof,
             &mut test_bb_challenger()
         ),
         Err(VerificationError::FinalQueryPath)
@@ -528,9 +3521,483 @@ fn test_verify_failing_cases() {
         verify(
             &config,
             commitment,
-            invalid_proof,
+            &invalid_proof,
             &mut test_bb_challenger()
         ),
         Err(VerificationError::FinalProofOfWork)
     );
 }
+
+#[test]
+// Checks that running verify_cheap_checks followed by DeferredQueries::verify
+// produces the same result as the monolithic verify, both for honest proofs
+// and for proofs with an invalid proof of work or Merkle path
+fn test_verify_deferred() {
+    let mut rng = rng();
+    let config = test_bb_stir_config(
+        BB_EXT_SEC_LEVEL,
+        SecurityAssumption::CapacityBound,
+        10,
+        1,
+        2,
+        3,
+    );
+
+    let (proof, commitment) = generate_bb_proof_with_config(&config, &mut test_bb_challenger());
+
+    let verify_two_stages = |proof: &BBProof| {
+        verify_cheap_checks(&config, commitment, proof, &mut test_bb_challenger())
+            .and_then(|deferred| deferred.verify(config.mmcs_config()))
+    };
+
+    let verify_monolithic =
+        |proof: &BBProof| verify(&config, commitment, proof, &mut test_bb_challenger());
+
+    // Honest proof
+    assert!(verify_two_stages(&proof).is_ok());
+    assert!(verify_monolithic(&proof).is_ok());
+
+    // Invalid proof of work, which is caught by the cheap checks
+    let mut invalid_proof = proof.clone();
+    invalid_proof.pow_witness = rng.random();
+
+    assert_eq!(
+        verify_two_stages(&invalid_proof),
+        verify_monolithic(&invalid_proof)
+    );
+
+    // Invalid Merkle paths (with untouched leaves), which are only caught when
+    // verifying the deferred queries
+    let mut invalid_proof = proof.clone();
+    invalid_proof.round_proofs[1].query_proofs[0].1[0][0][0] += Bb::ONE;
+
+    assert_eq!(
+        verify_two_stages(&invalid_proof),
+        verify_monolithic(&invalid_proof)
+    );
+
+    let mut invalid_proof = proof.clone();
+    invalid_proof.final_round_queries[0].1[0][0][0] += Bb::ONE;
+
+    assert!(verify_cheap_checks(
+        &config,
+        commitment,
+        &invalid_proof,
+        &mut test_bb_challenger()
+    )
+    .is_ok());
+
+    assert_eq!(
+        verify_two_stages(&invalid_proof),
+        Err(VerificationError::FinalQueryPath)
+    );
+    assert_eq!(
+        verify_two_stages(&invalid_proof),
+        verify_monolithic(&invalid_proof)
+    );
+}
+
+#[test]
+// Check that verify_with_limits accepts honest proofs under the limits derived
+// from the configuration and rejects oversized proofs before touching the
+// challenger
+fn test_verify_with_limits() {
+    let config = test_bb_stir_config(
+        BB_EXT_SEC_LEVEL,
+        SecurityAssumption::CapacityBound,
+        10,
+        1,
+        2,
+        3,
+    );
+    let limits = VerificationLimits::from_config(&config);
+
+    let (proof, commitment) = generate_bb_proof_with_config(&config, &mut test_bb_challenger());
+
+    assert_eq!(
+        verify_with_limits(
+            &config,
+            commitment,
+            &proof,
+            limits,
+            &mut test_bb_challenger()
+        ),
+        Ok(())
+    );
+
+    // The honest proof has no more elements than the limits allow (fewer if
+    // some queried indices are repeated)
+    let (field_elements, merkle_nodes) = proof_size(&proof, &commitment);
+    assert!(field_elements <= limits.max_field_elements);
+    assert!(merkle_nodes <= limits.max_merkle_nodes);
+
+    // Crafted proof with a huge final polynomial and many repeated openings
+    let mut oversized_proof = proof.clone();
+    oversized_proof.final_polynomial = rand_poly(1 << 16);
+    let opening = oversized_proof.final_round_queries[0].clone();
+    oversized_proof
+        .final_round_queries
+        .extend(iter::repeat_n(opening, 1 << 10));
+
+    let (field_elements, merkle_nodes) = proof_size(&oversized_proof, &commitment);
+
+    let mut challenger = test_bb_challenger();
+    assert_eq!(
+        verify_with_limits(
+            &config,
+            commitment,
+            &oversized_proof,
+            limits,
+            &mut challenger
+        ),
+        Err(VerificationError::ProofTooLarge {
+            field_elements,
+            merkle_nodes
+        })
+    );
+
+    // The proof was rejected without observing anything
+    let fresh_sample: BbExt = test_bb_challenger().sample_algebra_element();
+    assert_eq!(challenger.sample_algebra_element::<BbExt>(), fresh_sample);
+
+    // Crafted proof with as many openings as the honest one but whose first
+    // Merkle proof is padded with many spurious siblings
+    let mut padded_proof = proof.clone();
+    let merkle_proof = &mut padded_proof.final_round_queries[0].1[0];
+    let sibling = merkle_proof[0];
+    merkle_proof.extend(iter::repeat_n(sibling, 1 << 10));
+
+    let (field_elements, merkle_nodes) = proof_size(&padded_proof, &commitment);
+    assert!(field_elements <= limits.max_field_elements);
+
+    assert_eq!(
+        verify_with_limits(
+            &config,
+            commitment,
+            &padded_proof,
+            limits,
+            &mut test_bb_challenger()
+        ),
+        Err(VerificationError::ProofTooLarge {
+            field_elements,
+            merkle_nodes
+        })
+    );
+
+    // Limits tighter than the size of the honest proof reject it too
+    let (field_elements, merkle_nodes) = proof_size(&proof, &commitment);
+    let tight_limits = VerificationLimits {
+        max_merkle_nodes: merkle_nodes - 1,
+        ..limits
+    };
+    assert_eq!(
+        verify_with_limits(
+            &config,
+            commitment,
+            &proof,
+            tight_limits,
+            &mut test_bb_challenger()
+        ),
+        Err(VerificationError::ProofTooLarge {
+            field_elements,
+            merkle_nodes
+        })
+    );
+}
+
+#[test]
+// Check that a configuration with a stopping degree of 64 folds until the
+// degree bound reaches it exactly (shrinking the last folding factor to do so)
+// and yields proofs which verify, whose final polynomial respects that bound,
+// and that the verifier rejects final polynomials exceeding it
+fn test_bb_verify_stopping_degree() {
+    let parameters = StirParameters::constant_folding_factor(
+        (BB_EXT_SEC_LEVEL, SecurityAssumption::CapacityBound),
+        14,
+        1,
+        3,
+        1,
+        16,
+        test_bb_mmcs_config(),
+    )
+    .with_log_stopping_degree(6);
+
+    assert_eq!(parameters.log_folding_factors, vec![3, 3, 2]);
+    assert_eq!(parameters.log_inv_rates, vec![3, 5, 6]);
+
+    let config = StirConfig::new::<BbExt>(parameters).unwrap();
+    assert_eq!(config.final_degree_bound(), 64);
+
+    let (proof, commitment) = generate_bb_proof_with_config(&config, &mut test_bb_challenger());
+
+    // The initial polynomial has maximal degree, and so does the final one
+    // with overwhelming probability
+    assert_eq!(proof.final_polynomial.degree(), Some(63));
+
+    assert!(verify(&config, commitment, &proof, &mut test_bb_challenger()).is_ok());
+
+    let mut invalid_proof = proof.clone();
+    invalid_proof.final_polynomial = rand_poly(64);
+
+    assert_eq!(
+        verify(
+            &config,
+            commitment,
+            &invalid_proof,
+            &mut test_bb_challenger()
+        ),
+        Err(VerificationError::FinalPolynomialDegree)
+    );
+}
+
+#[test]
+// Check that a mixed-degree proof for two polynomials with different degree
+// bounds verifies, and that it is rejected if one of the polynomials exceeds
+// its declared bound or if one of the consistency openings is tampered with
+fn test_verify_mixed_degrees() {
+    let config = test_bb_stir_config(
+        BB_EXT_SEC_LEVEL,
+        SecurityAssumption::CapacityBound,
+        10,
+        1,
+        2,
+        3,
+    );
+
+    let trace_bound = 1 << 10;
+    let quotient_bound = 1 << 8;
+
+    let trace = rand_poly(trace_bound - 1);
+    let quotient = rand_poly(quotient_bound - 1);
+
+    let proof = prove_mixed_degrees(
+        &config,
+        vec![(trace.clone(), trace_bound), (quotient, quotient_bound)],
+        &mut test_bb_challenger(),
+    );
+
+    assert_eq!(
+        verify_mixed_degrees(
+            &config,
+            &[trace_bound, quotient_bound],
+            &proof,
+            &mut test_bb_challenger()
+        ),
+        Ok(())
+    );
+
+    // The honest prover refuses to exceed the declared bound, so a quotient of
+    // too high a degree can only be proven under a looser bound, which the
+    // verifier then rejects for the declared one
+    let large_quotient = rand_poly(2 * quotient_bound - 1);
+    let invalid_proof = prove_mixed_degrees(
+        &config,
+        vec![(trace, trace_bound), (large_quotient, 2 * quotient_bound)],
+        &mut test_bb_challenger(),
+    );
+
+    assert_eq!(
+        verify_mixed_degrees(
+            &config,
+            &[trace_bound, quotient_bound],
+            &invalid_proof,
+            &mut test_bb_challenger()
+        ),
+        Err(VerificationError::CombinationMismatch)
+    );
+
+    let mut invalid_proof = proof.clone();
+    invalid_proof.consistency_queries[0].0 .0[1] += BbExt::ONE;
+
+    assert_eq!(
+        verify_mixed_degrees(
+            &config,
+            &[trace_bound, quotient_bound],
+            &invalid_proof,
+            &mut test_bb_challenger()
+        ),
+        Err(VerificationError::CombinationQueryPath)
+    );
+
+    assert_eq!(
+        verify_mixed_degrees(
+            &config,
+            &[trace_bound, 2 * trace_bound],
+            &proof,
+            &mut test_bb_challenger()
+        ),
+        Err(VerificationError::InvalidDegreeBound)
+    );
+}
+
+#[test]
+// Check that the prover and verifier of a mixed-degree proof sample the same
+// batching challenge from their transcripts, namely the one returned by
+// batching_challenge once the degree bounds and the commitment to the batched
+// polynomials have been observed
+fn test_batching_challenge() {
+    let config = test_bb_stir_config(
+        BB_EXT_SEC_LEVEL,
+        SecurityAssumption::CapacityBound,
+        10,
+        1,
+        2,
+        3,
+    );
+
+    let degree_bounds = [1 << 10, 1 << 8, 1 << 9];
+    let polys_with_bounds = degree_bounds
+        .iter()
+        .map(|&bound| (rand_poly(bound - 1), bound))
+        .collect_vec();
+
+    let mut prover_challenger = TranscriptLogChallenger::new(test_bb_challenger());
+    let proof = prove_mixed_degrees(&config, polys_with_bounds, &mut prover_challenger);
+
+    let mut verifier_challenger = TranscriptLogChallenger::new(test_bb_challenger());
+    verify_mixed_degrees(&config, &degree_bounds, &proof, &mut verifier_challenger).unwrap();
+
+    assert_eq!(prover_challenger.samples, verifier_challenger.samples);
+
+    // The batching challenge is the first one to be sampled
+    let mut challenger = test_bb_challenger();
+    challenger.observe(Bb::from_u8(Messages::DegreeBounds as u8));
+    observe_usize_slice(&mut challenger, &degree_bounds, true);
+    challenger.observe(*proof.inputs_commitment());
+    let alpha: BbExt = batching_challenge(&mut challenger, degree_bounds.len());
+
+    assert_eq!(
+        &prover_challenger.samples[..BbExt::DIMENSION],
+        alpha.as_basis_coefficients_slice()
+    );
+}
+
+#[test]
+// Check that proofs with a beta added or removed in some full round are
+// rejected with a specific error, also by verify_collect_errors (which carries
+// on past the mismatch), and that the expected number of betas is the one
+// exposed by the configuration
+fn test_verify_beta_count_mismatch() {
+    let config = test_bb_stir_config(
+        BB_EXT_SEC_LEVEL,
+        SecurityAssumption::CapacityBound,
+        10,
+        1,
+        2,
+        3,
+    );
+    let (proof, commitment) = generate_bb_proof_with_config(&config, &mut test_bb_challenger());
+
+    for (i, round_proof) in proof.round_proofs.iter().enumerate() {
+        assert_eq!(round_proof.betas.len(), config.num_ood_samples(i + 1));
+    }
+
+    let num_ood_samples = config.num_ood_samples(2);
+
+    let mut invalid_proof = proof.clone();
+    invalid_proof.round_proofs[1].betas.push(BbExt::ONE);
+    assert_eq!(
+        verify_collect_errors(
+            &config,
+            commitment,
+            &invalid_proof,
+            &mut test_bb_challenger()
+        )
+        .unwrap_err()[0],
+        VerificationError::Round(
+            2,
+            FullRoundVerificationError::BetaCountMismatch {
+                expected: num_ood_samples,
+                got: num_ood_samples + 1
+            }
+        )
+    );
+    assert_eq!(
+        verify(
+            &config,
+            commitment,
+            &invalid_proof,
+            &mut test_bb_challenger()
+        ),
+        Err(VerificationError::Round(
+            2,
+            FullRoundVerificationError::BetaCountMismatch {
+                expected: num_ood_samples,
+                got: num_ood_samples + 1
+            }
+        ))
+    );
+
+    let mut invalid_proof = proof;
+    invalid_proof.round_proofs[1].betas.pop();
+    assert_eq!(
+        verify_collect_errors(
+            &config,
+            commitment,
+            &invalid_proof,
+            &mut test_bb_challenger()
+        )
+        .unwrap_err()[0],
+        VerificationError::Round(
+            2,
+            FullRoundVerificationError::BetaCountMismatch {
+                expected: num_ood_samples,
+                got: num_ood_samples - 1
+            }
+        )
+    );
+    assert_eq!(
+        verify(
+            &config,
+            commitment,
+            &invalid_proof,
+            &mut test_bb_challenger()
+        ),
+        Err(VerificationError::Round(
+            2,
+            FullRoundVerificationError::BetaCountMismatch {
+                expected: num_ood_samples,
+                got: num_ood_samples - 1
+            }
+        ))
+    );
+}
+
+#[test]
+#[ignore = "proves a polynomial of degree 2^26 - 1, which takes minutes and several GB of memory"]
+#[cfg(feature = "stress-tests")]
+// Check that a polynomial can be proved and verified on the largest initial
+// domain that fits in the two-adic subgroup of the quintic extension of
+// BabyBear (whose two-adicity is that of BabyBear, 27), reporting the time
+// taken by each step. Run with
+//     cargo test -p p3-stir --features stress-tests -- --ignored --nocapture
+fn test_bb_verify_max_two_adicity() {
+    use std::println;
+    use std::time::Instant;
+
+    let log_inv_rate = 1;
+    let log_starting_degree = BbExt::TWO_ADICITY - log_inv_rate;
+
+    let config = test_bb_stir_config(
+        BB_EXT_SEC_LEVEL,
+        SecurityAssumption::CapacityBound,
+        log_starting_degree,
+        log_inv_rate,
+        4,
+        6,
+    );
+    assert_eq!(config.starting_domain_log_size(), BbExt::TWO_ADICITY);
+
+    let start = Instant::now();
+    let polynomial = rand_poly::<BbExt>((1 << log_starting_degree) - 1);
+    let (witness, commitment) = commit(&config, polynomial);
+    println!("Committing took {:?}", start.elapsed());
+
+    let start = Instant::now();
+    let proof = prove(&config, witness, commitment, &mut test_bb_challenger());
+    println!("Proving took {:?}", start.elapsed());
+
+    let start = Instant::now();
+    verify(&config, commitment, &proof, &mut test_bb_challenger()).unwrap();
+    println!("Verifying took {:?}", start.elapsed());
+}